@@ -1,36 +1,928 @@
+# Generated from the Rust bindings by build.rs, do not edit.
+from enum import Enum
+from types import TracebackType
+from typing import Any
+
 class Session:
-    def __init__(self, backend: str = "enigo"): ...
-    def get_device(self, device: str) -> MouseDevice | KeyboardDevice | TouchDevice:
-        """Get the device of the given type.
+    """A set of devices created from a backend.
+
+    Any keys, buttons, touch contacts, sticks, triggers or pens that are still held when the
+    session is dropped are released.
+    """
+    def __init__(self, backend: str | None = None) -> None:
+        """Create a session using the named backend.
+
+        Args:
+            backend: "enigo" to simulate input (the default), or "dry-run" to check actions against
+                a virtual 1920x1080 screen and print a timestamped trace of them instead.
+        """
+    @property
+    def mouse(self) -> MouseDevice: ...
+    @property
+    def keyboard(self) -> KeyboardDevice: ...
+    @property
+    def touch(self) -> TouchDevice: ...
+    @property
+    def gamepad(self) -> GamepadDevice | None:
+        """A virtual Xbox controller, created the first time it is used. None if the backend has no
+        gamepad, "enigo" has one on Linux only.
+        """
+    @property
+    def pen(self) -> PenDevice | None:
+        """A pen on a virtual screen tablet, created the first time it is used. None if the backend
+        has no pen, "enigo" has one on Linux only.
+        """
+    def get_device(
+        self,
+        device: str,
+    ) -> MouseDevice | KeyboardDevice | TouchDevice | GamepadDevice | PenDevice:
+        """Get the first device of the given type, prefer the `mouse`, `keyboard`, `touch`, `gamepad`
+        and `pen` properties which are typed.
+
+        Args:
+            device: "mouse", "keyboard", "touch", "gamepad" or "pen".
+
+        Raises:
+            ValueError: If there is no device of that type.
+        """
+    def devices(
+        self,
+        kind: str | None = None,
+    ) -> list[MouseDevice | KeyboardDevice | TouchDevice | GamepadDevice | PenDevice]:
+        """The devices of the session, all of them or those of one type.
+
+        Args:
+            kind: "mouse", "keyboard", "touch", "gamepad" or "pen", all devices are listed if not
+                given.
+        """
+    def capabilities(self) -> Capabilities:
+        """What the session's devices can do: the device types, the screen size and the buttons and
+        keys each device accepts.
+        """
+    def state(self) -> InputState:
+        """The keys, mouse buttons and touch contacts that are down and where the cursor is.
+
+        With the "dry-run" backend these are the backend's own state, with "enigo" they are what
+        the session pressed and has not released (`reconciled` is False).
+        """
+    def release_all(self) -> None:
+        """Release every key, button and touch contact that is currently held, centre the gamepad's
+        sticks, release its triggers and lift the pen.
+
+        This also happens automatically when the session is garbage collected.
+        """
+    @property
+    def interrupt_policy(self) -> InterruptPolicy:
+        """The policy used to interrupt timed actions, individual calls can override it with `interrupt=`."""
+    @interrupt_policy.setter
+    def interrupt_policy(self, policy: InterruptPolicy) -> None: ...
+    def enable_failsafe(
+        self,
+        corners: list[str] = ["top_left"],
+        hotkey: list[Key | str] | None = None,
+        margin: int = 0,
+    ) -> None:
+        """Enable a kill switch for the whole session.
+
+        When the user moves the cursor into one of the corners, or holds every key of the hotkey on a
+        physical keyboard, everything held is released, running actions raise `ActionInterrupt` and
+        further actions raise `ActionInterrupt` until `rearm` is called.
 
         Args:
-            device: The type of device to get.
+            corners: Any of "top_left", "top_right", "bottom_left" and "bottom_right".
+            hotkey: Keys that trip the fail-safe when held together, e.g. ["ctrl", "alt", "esc"]
+                (Linux only).
+            margin: How close to a corner, in pixels, the cursor has to be.
+        """
+    def disable_failsafe(self) -> None: ...
+    def rearm(self) -> None:
+        """Allow actions again after the fail-safe was tripped."""
+    @property
+    def failsafe_tripped(self) -> bool:
+        """Whether the fail-safe has been tripped and not re-armed."""
+    def enable_humanize(
+        self,
+        seed: int | None = None,
+        click_jitter: int = 3,
+        press_time: tuple[float, float] = (0.04, 0.12),
+        overshoot_chance: float = 0.3,
+        overshoot: float = 0.05,
+        typing_interval: float = 0.08,
+        typing_variation: float = 0.4,
+    ) -> None:
+        """Add human-like random variation to the mouse and keyboard of the session.
 
-        Returns:
-                The device of the given type.
+        Method signatures do not change, clicks, taps, timed moves and typing are varied internally.
+
+        Args:
+            seed: Seed of the random number generator, runs with the same seed and calls are
+                reproducible.
+            click_jitter: Clicks land up to this many pixels away from the cursor.
+            press_time: Clicked buttons and tapped keys are held for a random time in this range, in
+                seconds.
+            overshoot_chance: The probability that a timed move overshoots its target and corrects.
+            overshoot: How far an overshoot goes past the target, as a fraction of the distance.
+            typing_interval: The average time between typed characters, in seconds.
+            typing_variation: The standard deviation of the time between characters, as a fraction
+                of the average.
 
         Raises:
-            ValueError: If the device type was not found, this can happen if the device is not supported on the current platform, or is otherwise not available.
+            ValueError: If a time, fraction or probability is negative or not finite.
         """
+    def disable_humanize(self) -> None: ...
+    def __enter__(self) -> Session: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool:
+        """Release everything that is held when leaving a `with` block, exceptions are not suppressed."""
+    def record(self, path: str, duration: float, devices: list[str] | None = None) -> None:
+        """Record user input from evdev devices and save it to a file (Linux only).
 
-class Device:
-    """Base class for all devices."""
+        Only evdev is supported, X11 capture is not. Input that does not pass through evdev, such
+        as input injected into the X server by another program or a remote desktop, is missed.
 
-class MouseDevice(Device):
-    def release(self, button: str):
-        """Release the given mouse button.
+        Args:
+            path: The file to save the recording to.
+            duration: How long to record for, in seconds.
+            devices: Paths of the evdev devices to record, all keyboards and mice are recorded if not
+                given.
+
+        Raises:
+            ValueError: If `duration` is negative or not a number, or a device can not be opened.
+        """
+    def replay(self, path: str, speed: float = 1.0, coordinates: str = "relative") -> None:
+        """Play back a recording.
 
         Args:
-            button: The mouse button to release.
+            path: The recording file.
+            speed: Playback speed multiplier.
+            coordinates: "relative" or "absolute", absolute playback moves the cursor to the recorded
+                positions.
+        """
+
+class MouseDevice:
+    """A mouse, buttons are given by name or as members of the `MouseButton` enum."""
+    def press(self, button: MouseButton | str) -> Receipt | None: ...
+    def release(self, button: MouseButton | str) -> Receipt | None: ...
+    def click(self, button: MouseButton | str) -> Receipt | None: ...
+    def hold(
+        self,
+        button: MouseButton | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def drag(
+        self,
+        button: MouseButton | str,
+        dx: int,
+        dy: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def move_abs(
+        self,
+        x: int,
+        y: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def move_rel(
+        self,
+        dx: int,
+        dy: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def hold_async(
+        self,
+        button: MouseButton | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `hold`, but runs in the background and returns a handle that can cancel the action."""
+    def drag_async(
+        self,
+        button: MouseButton | str,
+        dx: int,
+        dy: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle: ...
+    def move_abs_async(
+        self,
+        x: int,
+        y: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle: ...
+    def move_rel_async(
+        self,
+        dx: int,
+        dy: int,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle: ...
+    def play_path(
+        self,
+        path: Any,
+        duration: float | None = None,
+        button: MouseButton | str | None = None,
+        down: Any | None = None,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None:
+        """Move the cursor along a whole trajectory, timed in Rust rather than one call per point.
+
+        Args:
+            path: An (N, 2) array of x and y positions spread evenly over `duration`, or an (N, 3)
+                array of x, y and a timestamp in seconds (relative to the first point). NumPy
+                arrays and lists of rows are accepted.
+            duration: The length of an (N, 2) path in seconds, not allowed with timestamps.
+            button: The button held by `down`, "left" if not given.
+            down: N flags, the button is held down through the runs of flagged points. Nothing is
+                held if not given.
+            interrupt: Overrides the session's interrupt policy for this call.
+
+        Raises:
+            ValueError: If the path is not (N, 2) or (N, 3), its timestamps decrease or `down`
+                does not have a flag for each point.
+
+        Example:
+            path = numpy.array([[100, 100, 0.0], [150, 120, 0.05], [200, 180, 0.1]])
+            mouse.play_path(path, down=[False, True, True])
+        """
+    def play_path_async(
+        self,
+        path: Any,
+        duration: float | None = None,
+        button: MouseButton | str | None = None,
+        down: Any | None = None,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `play_path`, but runs in the background and returns a handle that can cancel the
+        action.
+        """
+    def scroll(self, dx: int, dy: int) -> Receipt | None: ...
+    def location(self) -> tuple[int, int]: ...
+    def release_all(self) -> None:
+        """Release all mouse buttons that were pressed and not yet released."""
+    def held(self, button: MouseButton | str) -> HeldButton:
+        """Hold a mouse button for the duration of a `with` block.
+
+        Example:
+            with mouse.held("left"):
+                mouse.move_rel(100, 0, 0.5)
         """
-    def press(self, button: str): ...
-    def click(self, button: str): ...
-    def hold(self, button: str, duration: float): ...
-    def drag(self, button: str, dx: int, dy: int, duration: float): ...
-    def move_abs(self, x: int, y: int, duration: float): ...
-    def move_rel(self, dx: int, dy: int, duration: float): ...
+    def __enter__(self) -> MouseDevice: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class KeyboardDevice:
+    """A keyboard.
+
+    Keys are a single character, a key name such as "ctrl" or "enter" (see the `Key` enum), or a
+    physical key that is pressed whatever the layout: "code:KeyW" (named like browser key codes),
+    "evdev:17", "hid:26" or "x11:25".
+    """
+    def press(self, key: Key | str) -> Receipt | None: ...
+    def release(self, key: Key | str) -> Receipt | None: ...
+    def tap(self, key: Key | str) -> Receipt | None: ...
+    def hold(
+        self,
+        key: Key | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def text(
+        self,
+        text: str,
+        duration: float = 0.0,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None: ...
+    def hold_async(
+        self,
+        key: Key | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `hold`, but runs in the background and returns a handle that can cancel the action."""
+    def text_async(
+        self,
+        text: str,
+        duration: float = 0.0,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle: ...
+    def enable_typos(
+        self,
+        rate: float = 0.03,
+        seed: int | None = None,
+        layout: Layout | None = None,
+    ) -> None:
+        """Make `text` mistype characters as a neighbouring key, then backspace and correct them.
+
+        Args:
+            rate: The probability that a character is mistyped, between 0 and 1.
+            seed: Seed of the random number generator, runs with the same seed and calls are
+                reproducible.
+            layout: The layout whose neighbouring keys are mistyped, US if not given.
+        """
+    def disable_typos(self) -> None: ...
+    def release_all(self) -> None:
+        """Release all keys that were pressed and not yet released."""
+    def held(self, key: Key | str) -> HeldKey:
+        """Hold a key for the duration of a `with` block.
+
+        Example:
+            with keyboard.held("shift"):
+                keyboard.tap("a")
+        """
+    def __enter__(self) -> KeyboardDevice: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class TouchDevice:
+    """A touch screen, contacts are identified by an id so that several can be down at once."""
+    def down(self, id: int, x: int, y: int) -> Receipt | None: ...
+    def move_to(self, id: int, x: int, y: int) -> Receipt | None: ...
+    def up(self, id: int) -> Receipt | None: ...
+    def tap(self, id: int, x: int, y: int) -> Receipt | None: ...
+    def release_all(self) -> None:
+        """Lift all touch contacts that were put down and not yet lifted."""
+
+class GamepadDevice:
+    """A gamepad with the buttons, sticks and triggers of an Xbox controller.
+
+    Sticks range from -1.0 (left, up) to 1.0 (right, down) on each axis, triggers from 0.0
+    (released) to 1.0 (fully pulled).
+    """
+    def press(self, button: GamepadButton | str) -> Receipt | None: ...
+    def release(self, button: GamepadButton | str) -> Receipt | None: ...
+    def tap(self, button: GamepadButton | str) -> Receipt | None: ...
+    def hold(
+        self,
+        button: GamepadButton | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None:
+        """Hold a button for a duration, then release it.
+
+        Args:
+            button: The button to hold.
+            duration: How long to hold it for, in seconds.
+            interrupt: Overrides the session's interrupt policy for this call.
+        """
+    def hold_async(
+        self,
+        button: GamepadButton | str,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `hold`, but runs in the background and returns a handle that can cancel the action."""
+    def move_stick(
+        self,
+        stick: Stick | str,
+        x: float,
+        y: float,
+        duration: float = 0.0,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None:
+        """Move a stick to a position, where it stays until it is moved again.
+
+        Args:
+            stick: "left" or "right".
+            x: From -1.0 (left) to 1.0 (right).
+            y: From -1.0 (up) to 1.0 (down).
+            duration: Move the stick in a straight line over this many seconds, rather than at once.
+            interrupt: Overrides the session's interrupt policy for this call.
+
+        Raises:
+            ValueError: If the position is out of range.
+        """
+    def move_stick_async(
+        self,
+        stick: Stick | str,
+        x: float,
+        y: float,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `move_stick`, but runs in the background and returns a handle that can cancel the action."""
+    def hold_stick(
+        self,
+        stick: Stick | str,
+        x: float,
+        y: float,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None:
+        """Hold a stick at a position for a duration, then let it return to the centre.
+
+        Example:
+            gamepad.hold_stick("left", 0.0, -1.0, 2.0)  # walk forward for two seconds
+        """
+    def hold_stick_async(
+        self,
+        stick: Stick | str,
+        x: float,
+        y: float,
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `hold_stick`, but runs in the background and returns a handle that can cancel the action."""
+    def stick(self, stick: Stick | str) -> tuple[float, float]:
+        """The current position of a stick, as `(x, y)`."""
+    def set_trigger(self, trigger: Trigger | str, value: float) -> Receipt | None:
+        """Pull a trigger, it stays at the value until it is set again.
+
+        Args:
+            trigger: "left" or "right".
+            value: From 0.0 (released) to 1.0 (fully pulled).
+
+        Raises:
+            ValueError: If the value is out of range.
+        """
+    def release_all(self) -> None:
+        """Release all buttons and reset all sticks and triggers that were moved and not yet reset."""
+    def __enter__(self) -> GamepadDevice: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class PenDevice:
+    """A pen with pressure, tilt, barrel buttons and an eraser, on a screen tablet.
+
+    Positions are in pixels, pressure is from 0.0 to 1.0 and tilt from -90.0 to 90.0 degrees.
+    """
+    def hover(self, x: int, y: int, tilt_x: float = 0.0, tilt_y: float = 0.0) -> Receipt | None:
+        """Bring the pen into range above a position, or move it there while it hovers. The tip is
+        lifted if it is down.
+        """
+    def down(
+        self,
+        x: int,
+        y: int,
+        pressure: float = 1.0,
+        tilt_x: float = 0.0,
+        tilt_y: float = 0.0,
+    ) -> Receipt | None:
+        """Touch the tip down at a position.
+
+        Args:
+            x: Position on the screen in pixels.
+            y: Position on the screen in pixels.
+            pressure: From 0.0 to 1.0.
+            tilt_x: From -90.0 to 90.0 degrees, positive when the top of the pen leans to the
+                right.
+            tilt_y: From -90.0 to 90.0 degrees, positive when the top of the pen leans towards the
+                user.
+
+        Raises:
+            ValueError: If the pressure or tilt is out of range.
+        """
+    def move_to(
+        self,
+        x: int,
+        y: int,
+        pressure: float = 1.0,
+        tilt_x: float = 0.0,
+        tilt_y: float = 0.0,
+    ) -> Receipt | None:
+        """Move the pen to a position, keeping the tip down or up. The pressure only applies while
+        the tip is down.
+        """
+    def up(self) -> Receipt | None:
+        """Lift the tip, the pen keeps hovering."""
+    def leave(self) -> Receipt | None:
+        """Take the pen out of range of the tablet."""
+    def press(self, button: PenButton | str) -> Receipt | None: ...
+    def release(self, button: PenButton | str) -> Receipt | None: ...
+    def set_tool(self, tool: PenTool | str) -> Receipt | None:
+        """Switch between the tip ("pen") and the eraser ("eraser"), the tip is lifted if it is down."""
+    def stroke(
+        self,
+        points: list[list[float]],
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> Receipt | None:
+        """Draw a stroke: touch down at the first point, move through the others and lift the tip.
+
+        Args:
+            points: Each point is `(x, y)`, `(x, y, pressure)` or
+                `(x, y, pressure, tilt_x, tilt_y)`.
+            duration: The points are spread evenly over this many seconds.
+            interrupt: Overrides the session's interrupt policy for this call.
+
+        Example:
+            pen.stroke([(100, 100, 0.2), (150, 120, 0.8), (200, 100, 0.3)], 0.5)
+        """
+    def stroke_async(
+        self,
+        points: list[list[float]],
+        duration: float,
+        interrupt: InterruptPolicy | None = None,
+    ) -> ActionHandle:
+        """Like `stroke`, but runs in the background and returns a handle that can cancel the action."""
+    def release_all(self) -> None:
+        """Release the barrel buttons, lift the tip and take the pen out of range."""
+    def __enter__(self) -> PenDevice: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class HeldKey:
+    """A key that is pressed on entering a `with` block and released on exit."""
+    def __enter__(self) -> HeldKey: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class HeldButton:
+    """A mouse button that is pressed on entering a `with` block and released on exit."""
+    def __enter__(self) -> HeldButton: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+class InterruptPolicy:
+    """Decides when a timed action should be interrupted, the action is interrupted as soon as any of
+    the conditions are met. Interrupted actions release any inputs they hold and fail with
+    `InputError::Interrupt`.
+    """
+    def __init__(
+        self,
+        cursor_moved: int | None = None,
+        key_pressed: bool = False,
+        predicate: Any | None = None,
+        poll_interval: float = 0.005,
+    ) -> None:
+        """With no arguments the policy never interrupts. The default policy of a session interrupts
+        when the cursor is moved at all.
+
+        Args:
+            cursor_moved: Interrupt when the cursor is moved by more than this many pixels.
+            key_pressed: Interrupt when a key is pressed on a physical keyboard (Linux only).
+            predicate: Interrupt when calling this returns true.
+            poll_interval: How often the conditions are checked, in seconds.
+
+        Raises:
+            ValueError: If `poll_interval` is negative or not a number.
+        """
+    def __repr__(self) -> str: ...
+
+class ActionInterrupt(Exception):
+    """Raised when a timed action is interrupted according to its `InterruptPolicy`, or by the fail-safe."""
+    def __init__(self) -> None: ...
+    def __str__(self) -> str: ...
+
+class ActionCancelled(Exception):
+    """Raised when an action is cancelled from code, e.g. with `ActionHandle.cancel()`."""
+    def __init__(self) -> None: ...
+    def __str__(self) -> str: ...
+
+class ActionHandle:
+    """An action running on a background thread, which can be cancelled or waited for."""
+    def cancel(self) -> None:
+        """Cancel the action, it raises `ActionCancelled` from `wait`."""
+    def done(self) -> bool: ...
+    def wait(self, timeout: float | None = None) -> Receipt | None:
+        """Wait for the action to finish and return its receipt.
+
+        Raises:
+            ActionCancelled: If the action was cancelled.
+            ActionInterrupt: If the action was interrupted.
+            TimeoutError: If the action is still running after `timeout` seconds.
+            ValueError: If `timeout` is negative or not a number.
+        """
+    @property
+    def receipt(self) -> Receipt | None:
+        """The receipt of the action, `None` until it has finished."""
+
+class Receipt:
+    """What an action actually did: when it started and ended, how many primitives it emitted and
+    where the cursor ended up.
+    """
+    @property
+    def requested_start(self) -> float:
+        """When the action was called, in seconds since the epoch (like `time.time()`)."""
+    @property
+    def requested_end(self) -> float:
+        """When the action was supposed to end, the call time plus the requested duration."""
+    @property
+    def actual_start(self) -> float | None:
+        """When the first primitive event was emitted, `None` if there were none."""
+    @property
+    def actual_end(self) -> float:
+        """When the action returned."""
+    @property
+    def primitives(self) -> int:
+        """The number of primitive events emitted by the backend."""
+    @property
+    def cursor(self) -> tuple[int, int] | None:
+        """The cursor position after the action, if known."""
+    @property
+    def truncated(self) -> bool:
+        """Whether the action was stopped before it finished (interrupted, cancelled or by the fail-safe)."""
+    @property
+    def timing_error(self) -> float | None:
+        """The most any of the action's scheduled events was late by in seconds, `None` if the
+        action had no deadlines.
+        """
+    def __repr__(self) -> str: ...
+
+class Layout:
+    """A keyboard layout, translating characters into the physical keys that type them.
+
+    Keys are identified by their evdev code, the modifiers are Shift (42) and AltGr (100).
+    """
+    def __init__(self, name: str = "us") -> None:
+        """One of the bundled layouts: "us", "uk", "de", "fr" or "dvorak"."""
+    @staticmethod
+    def load(path: str) -> Layout:
+        """Load a custom layout from a JSON file.
+
+        The file looks like `{"name": "custom", "keys": [[16, "qQ@"], [17, "wW"]]}`, each key is an
+        evdev code and the characters it types without modifiers, with Shift, with AltGr and with
+        Shift+AltGr. A space means the key types nothing at that level.
+        """
+    @property
+    def name(self) -> str: ...
+    def translate(self, text: str) -> list[tuple[int, list[int]]]:
+        """The evdev codes of the keys that type `text`, each with the codes of the modifiers to hold.
+
+        Raises:
+            ValueError: If the layout can not type some of the characters, they are listed in the
+                message.
+        """
+    def untypeable(self, text: str) -> list[str]:
+        """The characters of `text` that the layout can not type, each listed once."""
+    def __repr__(self) -> str: ...
+
+class Capabilities:
+    """What a session can do: its devices, its screen and the inputs each device accepts."""
+    @property
+    def devices(self) -> list[str]:
+        """The device types of the session, e.g. ["mouse", "keyboard", "touch"]."""
+    @property
+    def screen(self) -> tuple[int, int]:
+        """Width and height of the screen in pixels."""
+    @property
+    def mouse_buttons(self) -> list[str]: ...
+    @property
+    def keys(self) -> list[str]:
+        """The named keys, letters and digits, other characters and physical keys can be pressed too."""
+    @property
+    def gamepad_buttons(self) -> list[str]:
+        """Empty if the session has no gamepad."""
+    @property
+    def pen_buttons(self) -> list[str]:
+        """Empty if the session has no pen."""
+    def __repr__(self) -> str: ...
+
+class InputState:
+    """The keys, mouse buttons and touch contacts that are down and where the cursor is, see
+    `Session::state`.
+    """
+    @property
+    def keys(self) -> list[str]:
+        """The keys that are down, by name."""
+    @property
+    def mouse_buttons(self) -> list[str]: ...
+    @property
+    def touches(self) -> list[int]:
+        """Ids of the touch contacts that are down."""
+    @property
+    def cursor(self) -> tuple[int, int] | None:
+        """The cursor position, None if the backend can not tell."""
+    @property
+    def reconciled(self) -> bool:
+        """Whether the keys, buttons and contacts were reported by the system rather than tracked by
+        the session, tracking misses input from the user and other programs.
+        """
+    def vector(self, keys: list[Key | str] | None = None) -> list[float]:
+        """The state as a list of floats of a fixed length, for an observation space.
+
+        The list is the cursor position (-1 if unknown), then 1.0 or 0.0 for each of `keys` and
+        for the "left", "right" and "middle" mouse buttons, then the number of touch contacts.
+
+        Args:
+            keys: The keys to include, by default `Capabilities.keys` (the named keys, letters
+                and digits).
+
+        Example:
+            observation = numpy.asarray(session.state().vector(), dtype=numpy.float32)
+        """
+    def __repr__(self) -> str: ...
+
+class ActionSpace:
+    """The devices of a session as a Gymnasium action space.
+
+    The space is a `Dict` of:
+
+    - `"key"`: `Discrete(len(keys) + 1)`, a key to tap this step, 0 taps nothing. Held keys are
+      not tapped.
+    - `"held_keys"`: `MultiBinary(len(keys))`, the keys to hold down.
+    - `"mouse_buttons"`: `MultiBinary(len(mouse_buttons))`, the mouse buttons to hold down.
+    - `"cursor"`: `Box(-max_delta, max_delta, (2,))`, how far to move the cursor in pixels.
+
+    and with `gamepad=True`:
+
+    - `"gamepad_buttons"`: `MultiBinary(len(gamepad_buttons))`, the gamepad buttons to hold down.
+    - `"sticks"`: `Box(-1, 1, (2, 2))`, the left and right stick positions.
+    - `"triggers"`: `Box(0, 1, (2,))`, the left and right trigger values.
+
+    Held keys and buttons stay down across steps until an action releases them.
+    """
+    def __init__(
+        self,
+        session: Session,
+        keys: list[Key | str] | None = None,
+        max_delta: float = 100.0,
+        gamepad: bool = False,
+    ) -> None:
+        """Describe a session's devices as an action space.
+
+        Args:
+            session: The session the actions are applied to.
+            keys: The keys the agent can tap and hold, by default those of
+                `session.capabilities()` (the named keys, letters and digits).
+            max_delta: The largest cursor movement per step, in pixels.
+            gamepad: Include the session's gamepad.
+
+        Raises:
+            ValueError: If `gamepad` is true and the session has no gamepad.
+        """
+    @property
+    def space(self) -> Any:
+        """The `gymnasium.spaces.Dict` to use as an environment's `action_space`.
+
+        Raises:
+            ImportError: If gymnasium is not installed.
+        """
+    @property
+    def keys(self) -> list[str]:
+        """The keys of the `"key"` and `"held_keys"` entries, `"key"` value `i` taps `keys[i - 1]`."""
+    @property
+    def mouse_buttons(self) -> list[str]:
+        """The buttons of the `"mouse_buttons"` entry."""
+    @property
+    def gamepad_buttons(self) -> list[str]:
+        """The buttons of the `"gamepad_buttons"` entry, empty without the gamepad."""
+    def apply(self, action: Any) -> None:
+        """Perform one step's action.
+
+        Keys and buttons are released or pressed to match the held entries, then the cursor is
+        moved, the key is tapped (unless it is held) and the gamepad's sticks and triggers are set.
+        Entries left out of the action change nothing.
+
+        Args:
+            action: A sample of `space`, or a dict with some of its entries.
+
+        Raises:
+            ValueError: If an entry does not match the space.
+
+        Example:
+            actions = ActionSpace(session)
+            actions.apply(actions.space.sample())
+        """
+    def release_all(self) -> None:
+        """Release everything held by earlier actions and centre the gamepad, e.g. when an episode
+        ends.
+        """
+
+class MouseButton(str, Enum):
+    """The `MouseButton` enum for python, with a member for each button. Members are strings and
+    can be used wherever a button name is accepted.
+    """
+
+    LEFT = "left"
+    RIGHT = "right"
+    MIDDLE = "middle"
+
+class Key(str, Enum):
+    """The `Key` enum for python, with a member for each named key. Members are strings and can be
+    used wherever a key is accepted.
+    """
+
+    CONTROL = "control"
+    SHIFT = "shift"
+    ALT = "alt"
+    ENTER = "enter"
+    ESCAPE = "escape"
+    BACKSPACE = "backspace"
+    TAB = "tab"
+    SPACE = "space"
+    ARROW_UP = "arrow_up"
+    ARROW_DOWN = "arrow_down"
+    ARROW_LEFT = "arrow_left"
+    ARROW_RIGHT = "arrow_right"
+    HOME = "home"
+    END = "end"
+    PAGE_UP = "page_up"
+    PAGE_DOWN = "page_down"
+
+class GamepadButton(str, Enum):
+    """The `GamepadButton` enum for python, with a member for each button. Members are strings and
+    can be used wherever a button name is accepted.
+    """
+
+    A = "a"
+    B = "b"
+    X = "x"
+    Y = "y"
+    LB = "lb"
+    RB = "rb"
+    BACK = "back"
+    START = "start"
+    GUIDE = "guide"
+    LS = "ls"
+    RS = "rs"
+    DPAD_UP = "dpad_up"
+    DPAD_DOWN = "dpad_down"
+    DPAD_LEFT = "dpad_left"
+    DPAD_RIGHT = "dpad_right"
+
+class Stick(str, Enum):
+    """The `Stick` enum for python, members are strings and can be used wherever a stick name is
+    accepted.
+    """
+
+    LEFT = "left"
+    RIGHT = "right"
+
+class Trigger(str, Enum):
+    """The `Trigger` enum for python, members are strings and can be used wherever a trigger name
+    is accepted.
+    """
+
+    LEFT = "left"
+    RIGHT = "right"
+
+class PenButton(str, Enum):
+    """The `PenButton` enum for python, members are strings and can be used wherever a button name
+    is accepted.
+    """
+
+    PRIMARY = "primary"
+    SECONDARY = "secondary"
+
+class PenTool(str, Enum):
+    """The `PenTool` enum for python, members are strings and can be used wherever a tool name is
+    accepted.
+    """
+
+    PEN = "pen"
+    ERASER = "eraser"
+
+def enable_logging(level: str = "info", logger: str = "anyinput") -> None:
+    """Forward a structured log of every action to the `logging` module.
+
+    Device calls are logged at INFO with the device, action, arguments, duration and result.
+    Backend primitives are logged at level 5 (below DEBUG) with the backend's name, use
+    `level="trace"` to include them.
+
+    Args:
+        level: The lowest level forwarded, one of "trace", "debug", "info", "warning" and "error".
+        logger: Name of the python logger that records are sent to.
+    """
+
+def disable_logging() -> None:
+    """Stop forwarding actions to the `logging` module."""
+
+def set_timing_tolerance(seconds: float) -> None:
+    """Set how long before each deadline of a timed action waiting switches from sleeping to
+    spinning. Sleeping alone usually overshoots by a millisecond or more.
+
+    Args:
+        seconds: The tolerance, larger is more precise but keeps a core busy for longer and
+            0 only sleeps. Defaults to 0.002.
 
-class KeyboardDevice(Device): ...
-class TouchDevice(Device): ...
+    Raises:
+        ValueError: If `seconds` is negative or not finite.
+    """
 
-# others...
+def timing_tolerance() -> float:
+    """How long before each deadline of a timed action waiting switches from sleeping to
+    spinning, in seconds.
+    """
@@ -0,0 +1,35 @@
+//! Generates the python stub `anyinput.pyi` from the bindings, see `build/stub.rs`.
+//!
+//! The stub is written to `OUT_DIR`, a test checks that the committed `anyinput.pyi` matches it.
+//! Build with `ANYINPUT_WRITE_STUB=1` to update the committed stub.
+#[cfg(feature = "python")]
+#[path = "build/stub.rs"]
+mod stub;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "python")]
+    write_stub();
+}
+
+#[cfg(feature = "python")]
+fn write_stub() {
+    use std::{env, fs, path::Path};
+
+    println!("cargo:rerun-if-changed=build");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-env-changed=ANYINPUT_WRITE_STUB");
+    let stub = stub::generate(Path::new("src"))
+        .unwrap_or_else(|e| panic!("Failed to generate anyinput.pyi: {}", e));
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("anyinput.pyi"), &stub).unwrap();
+    if env::var_os("ANYINPUT_WRITE_STUB").is_some() {
+        // keep the line endings of the committed stub
+        let committed = fs::read_to_string("anyinput.pyi").unwrap_or_default();
+        let stub = match committed.contains("\r\n") {
+            true => stub.replace('\n', "\r\n"),
+            false => stub,
+        };
+        fs::write("anyinput.pyi", stub).unwrap();
+    }
+}
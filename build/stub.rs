@@ -0,0 +1,739 @@
+//! Generates the python stub `anyinput.pyi` from the bindings.
+//!
+//! The classes, functions and enums registered by the `#[pymodule]` in `src/lib.rs` are looked up
+//! in the sources. Their `#[pymethods]` become python signatures with the doc comments as
+//! docstrings, Rust types are translated by [`Stub::py_type`].
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Expr, FnArg, ImplItem, Item, Lit, Meta, Pat, ReturnType, Signature, Stmt, Token,
+    Type,
+};
+
+/// Lines of the stub are wrapped after this many characters.
+const LINE_LENGTH: usize = 100;
+
+const HEADER: &str = "\
+# Generated from the Rust bindings by build.rs, do not edit.
+from enum import Enum
+from types import TracebackType
+from typing import Any
+";
+
+/// Generate the stub for the crate whose sources are in `src`.
+pub fn generate(src: &Path) -> Result<String, String> {
+    let mut items = Vec::new();
+    read_items(src, &mut items)?;
+    let mut stub = Stub::new(&items);
+    let module = items
+        .iter()
+        .find_map(|item| match item {
+            Item::Fn(f) if has_attr(&f.attrs, "pymodule") => Some(f),
+            _ => None,
+        })
+        .ok_or("No #[pymodule] found")?;
+    for stmt in &module.block.stmts {
+        stub.register_enum(stmt);
+    }
+    let mut sections = vec![HEADER.to_string()];
+    for stmt in &module.block.stmts {
+        if let Some(section) = stub.registration(stmt)? {
+            sections.push(section);
+        }
+    }
+    Ok(sections.join("\n"))
+}
+
+/// Parse every source file under `dir`, flattening inline modules.
+fn read_items(dir: &Path, items: &mut Vec<Item>) -> Result<(), String> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            read_items(&path, items)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let file = syn::parse_file(&source)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+            flatten(file.items, items);
+        }
+    }
+    Ok(())
+}
+
+fn flatten(from: Vec<Item>, items: &mut Vec<Item>) {
+    for item in from {
+        match item {
+            Item::Mod(module) => flatten(module.content.map(|c| c.1).unwrap_or_default(), items),
+            item => items.push(item),
+        }
+    }
+}
+
+/// The method called by a statement such as `m.add_class::<Session>()?;`.
+fn method_call(stmt: &Stmt) -> Option<&syn::ExprMethodCall> {
+    let Stmt::Expr(expr, _) = stmt else {
+        return None;
+    };
+    let expr = match expr {
+        Expr::Try(e) => &*e.expr,
+        expr => expr,
+    };
+    match expr {
+        Expr::MethodCall(call) => Some(call),
+        _ => None,
+    }
+}
+
+/// The python name and Rust type of `m.add("Name", Type::py_enum(py)?)`.
+fn enum_registration(call: &syn::ExprMethodCall) -> Result<(String, String), String> {
+    let tokens = call.to_token_stream().to_string();
+    let args: Vec<&Expr> = call.args.iter().collect();
+    let [name, value] = args[..] else {
+        return Err(format!("Unexpected registration {}", tokens));
+    };
+    let value = value.to_token_stream().to_string();
+    match value.split_once(" :: py_enum") {
+        Some((ty, _)) => Ok((lit_str(name)?, ty.to_string())),
+        None => Err(format!("Unexpected registration {}", tokens)),
+    }
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// The name of the type an `impl` block is for.
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => Some(path.path.segments.last()?.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// The arguments of `#[pyclass(...)]`, also when it is behind `#[cfg_attr(feature = "python", ...)]`.
+fn pyclass_args(attrs: &[Attribute]) -> Option<Vec<Meta>> {
+    let parse = Punctuated::<Meta, Token![,]>::parse_terminated;
+    for attr in attrs {
+        let metas = if attr.path().is_ident("cfg_attr") {
+            attr.parse_args_with(parse)
+                .ok()?
+                .into_iter()
+                .skip(1)
+                .collect()
+        } else {
+            vec![attr.meta.clone()]
+        };
+        for meta in metas {
+            match meta {
+                Meta::Path(path) if path.is_ident("pyclass") => return Some(Vec::new()),
+                Meta::List(list) if list.path.is_ident("pyclass") => {
+                    return Some(list.parse_args_with(parse).ok()?.into_iter().collect())
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+struct Class<'a> {
+    name: String,
+    base: Option<String>,
+    attrs: &'a [Attribute],
+    methods: Vec<(&'a [Attribute], &'a Signature)>,
+}
+
+struct Stub<'a> {
+    classes: HashMap<String, Class<'a>>,
+    functions: HashMap<String, (&'a [Attribute], &'a Signature)>,
+    /// Associated functions and constants of every type, by type.
+    impls: HashMap<String, Vec<&'a ImplItem>>,
+    /// `Display` implementations by type.
+    displays: HashMap<String, &'a syn::ItemImpl>,
+    enums: HashMap<String, &'a syn::ItemEnum>,
+    /// Python annotations of the Rust types that are neither builtin nor a class.
+    types: HashMap<String, String>,
+}
+
+impl<'a> Stub<'a> {
+    fn new(items: &'a [Item]) -> Self {
+        let mut stub = Stub {
+            classes: HashMap::new(),
+            functions: HashMap::new(),
+            impls: HashMap::new(),
+            displays: HashMap::new(),
+            enums: HashMap::new(),
+            types: HashMap::new(),
+        };
+        for item in items {
+            match item {
+                Item::Struct(s) => {
+                    let Some(args) = pyclass_args(&s.attrs) else {
+                        continue;
+                    };
+                    let mut class = Class {
+                        name: s.ident.to_string(),
+                        base: None,
+                        attrs: &s.attrs,
+                        methods: Vec::new(),
+                    };
+                    for arg in args {
+                        let Meta::NameValue(arg) = arg else {
+                            continue;
+                        };
+                        let value = arg.value.to_token_stream().to_string();
+                        if arg.path.is_ident("name") {
+                            class.name = value.trim_matches('"').to_string();
+                        } else if arg.path.is_ident("extends") {
+                            // python exceptions are exposed to Rust as e.g. `PyValueError`
+                            class.base = Some(value.trim_start_matches("Py").to_string());
+                        }
+                    }
+                    let existing = stub.classes.insert(s.ident.to_string(), class);
+                    if let Some(existing) = existing {
+                        stub.classes.get_mut(&s.ident.to_string()).unwrap().methods =
+                            existing.methods;
+                    }
+                }
+                Item::Impl(block) => {
+                    let Some(name) = type_name(&block.self_ty) else {
+                        continue;
+                    };
+                    let trait_name = block.trait_.as_ref().and_then(|t| t.1.segments.last());
+                    if trait_name.is_some_and(|t| t.ident == "Display") {
+                        stub.displays.insert(name, block);
+                    } else if has_attr(&block.attrs, "pymethods") {
+                        let methods = block.items.iter().filter_map(|item| match item {
+                            ImplItem::Fn(f) => Some((f.attrs.as_slice(), &f.sig)),
+                            _ => None,
+                        });
+                        let class = stub.classes.entry(name.clone()).or_insert(Class {
+                            name,
+                            base: None,
+                            attrs: &[],
+                            methods: Vec::new(),
+                        });
+                        class.methods.extend(methods);
+                    } else if trait_name.is_none() {
+                        stub.impls.entry(name).or_default().extend(&block.items);
+                    }
+                }
+                Item::Enum(e) => {
+                    stub.enums.insert(e.ident.to_string(), e);
+                }
+                Item::Fn(f) if has_attr(&f.attrs, "pyfunction") => {
+                    stub.functions
+                        .insert(f.sig.ident.to_string(), (&f.attrs, &f.sig));
+                }
+                _ => {}
+            }
+        }
+        for (rust_name, class) in &stub.classes {
+            stub.types.insert(rust_name.clone(), class.name.clone());
+        }
+        stub
+    }
+
+    /// Rust types that are registered as an enum with `m.add("Name", Type::py_enum(py)?)` are
+    /// passed from python by name, members of the enum are names too.
+    fn register_enum(&mut self, stmt: &Stmt) {
+        let Some(call) = method_call(stmt) else {
+            return;
+        };
+        if let Some(Ok((name, ty))) = (call.method == "add").then(|| enum_registration(call)) {
+            self.types.insert(ty, format!("{} | str", name));
+        }
+    }
+
+    /// The stub for a statement of the module function, such as `m.add_class::<Session>()?;`.
+    fn registration(&self, stmt: &Stmt) -> Result<Option<String>, String> {
+        let Some(call) = method_call(stmt) else {
+            return Ok(None);
+        };
+        let args: Vec<&Expr> = call.args.iter().collect();
+        match call.method.to_string().as_str() {
+            "add_class" => {
+                let ty = call
+                    .turbofish
+                    .as_ref()
+                    .and_then(|t| t.args.first())
+                    .map(|arg| arg.to_token_stream().to_string())
+                    .ok_or("add_class without a type")?;
+                let class = self.classes.get(&ty).ok_or(format!("No class {}", ty))?;
+                self.class(class).map(Some)
+            }
+            "add_function" => {
+                // m.add_function(wrap_pyfunction!(name, m)?)
+                let tokens = args[0].to_token_stream().to_string();
+                let name = tokens
+                    .split(['(', '!', ','])
+                    .nth(2)
+                    .map(str::trim)
+                    .ok_or(format!("Unexpected function registration {}", tokens))?;
+                let (attrs, sig) = self
+                    .functions
+                    .get(name)
+                    .ok_or(format!("No #[pyfunction] {}", name))?;
+                self.function(attrs, sig, None, "").map(Some)
+            }
+            "add" => self.str_enum(call).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn class(&self, class: &Class) -> Result<String, String> {
+        let base = match &class.base {
+            Some(base) => format!("({})", base),
+            None => String::new(),
+        };
+        let mut out = format!("class {}{}:\n", class.name, base);
+        let mut body = String::new();
+        if let Some(doc) = docstring(class.attrs, "    ") {
+            body += &doc;
+        }
+        for (attrs, sig) in &class.methods {
+            body += &self.function(attrs, sig, Some(class), "    ")?;
+        }
+        if body.is_empty() {
+            out.truncate(out.len() - 1);
+            body = " ...\n".to_string();
+        }
+        Ok(out + &body)
+    }
+
+    /// A `str` enum added with `m.add("Name", Type::py_enum(py)?)`. The members are the elements
+    /// of the constant that `py_enum` iterates, named after their `Display` strings.
+    fn str_enum(&self, call: &syn::ExprMethodCall) -> Result<String, String> {
+        let (name, ty) = enum_registration(call)?;
+        let items = self.impls.get(&ty).map(Vec::as_slice).unwrap_or_default();
+        let py_enum = items
+            .iter()
+            .find_map(|item| match item {
+                ImplItem::Fn(f) if f.sig.ident == "py_enum" => Some(f),
+                _ => None,
+            })
+            .ok_or(format!("No {}::py_enum", ty))?;
+        let body = py_enum.block.to_token_stream().to_string();
+        let members = items
+            .iter()
+            .find_map(|item| match item {
+                ImplItem::Const(c) if body.contains(&format!("Self :: {} ", c.ident)) => {
+                    Some(&c.expr)
+                }
+                _ => None,
+            })
+            .ok_or(format!("No constant used by {}::py_enum", ty))?;
+        let Expr::Array(members) = members else {
+            return Err(format!("The members of {} are not an array", name));
+        };
+        let names = self.display_names(&ty)?;
+        let mut out = format!("class {}(str, Enum):\n", name);
+        if let Some(doc) = docstring(&py_enum.attrs, "    ") {
+            out += &doc;
+            out += "\n";
+        }
+        for member in &members.elems {
+            let member = member.to_token_stream().to_string();
+            let value = names
+                .get(&member)
+                .ok_or(format!("{} has no Display string", member))?;
+            out += &format!("    {} = {:?}\n", value.to_uppercase(), value);
+        }
+        Ok(out)
+    }
+
+    /// The strings of the arms of the `match` in `Display::fmt` that are literals, by pattern.
+    fn display_names(&self, ty: &str) -> Result<HashMap<String, String>, String> {
+        let display = self
+            .displays
+            .get(ty)
+            .ok_or(format!("No Display for {}", ty))?;
+        let fmt = display
+            .items
+            .iter()
+            .find_map(|item| match item {
+                ImplItem::Fn(f) => Some(f),
+                _ => None,
+            })
+            .ok_or(format!("No Display::fmt for {}", ty))?;
+        let matched = fmt.block.stmts.iter().find_map(|stmt| match stmt {
+            Stmt::Local(local) => match local.init.as_ref().map(|init| &*init.expr) {
+                Some(Expr::Match(m)) => Some(m),
+                _ => None,
+            },
+            Stmt::Expr(Expr::Match(m), _) => Some(m),
+            _ => None,
+        });
+        let matched = matched.ok_or(format!("No match in Display::fmt for {}", ty))?;
+        Ok(matched
+            .arms
+            .iter()
+            .filter_map(|arm| match &*arm.body {
+                Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some((arm.pat.to_token_stream().to_string(), s.value())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// A function or method, `class` is `None` for module functions.
+    fn function(
+        &self,
+        attrs: &[Attribute],
+        sig: &Signature,
+        class: Option<&Class>,
+        indent: &str,
+    ) -> Result<String, String> {
+        let options = Pyo3Options::parse(attrs)?;
+        let is = |name| has_attr(attrs, name);
+        let rust_name = sig.ident.to_string();
+        let attr_name = |kind| {
+            attrs
+                .iter()
+                .find(|attr| attr.path().is_ident(kind))
+                .and_then(|attr| attr.parse_args::<syn::Ident>().ok())
+                .map(|ident| ident.to_string())
+        };
+        let (name, decorator) = if is("new") {
+            ("__init__".to_string(), None)
+        } else if is("getter") {
+            let name = attr_name("getter")
+                .unwrap_or_else(|| rust_name.trim_start_matches("get_").to_string());
+            (name, Some("@property".to_string()))
+        } else if is("setter") {
+            let name = attr_name("setter")
+                .unwrap_or_else(|| rust_name.trim_start_matches("set_").to_string());
+            let decorator = format!("@{}.setter", name);
+            (name, Some(decorator))
+        } else if is("staticmethod") {
+            (
+                options.name.unwrap_or(rust_name),
+                Some("@staticmethod".into()),
+            )
+        } else if is("classmethod") {
+            (
+                options.name.unwrap_or(rust_name),
+                Some("@classmethod".into()),
+            )
+        } else {
+            (options.name.unwrap_or(rust_name), None)
+        };
+
+        let mut params = Vec::new();
+        if class.is_some() && !is("staticmethod") {
+            params.push(if is("classmethod") { "cls" } else { "self" }.to_string());
+        }
+        let typed = sig.inputs.iter().filter_map(|arg| match arg {
+            FnArg::Typed(arg) => Some(arg),
+            FnArg::Receiver(_) => None,
+        });
+        let receiver = sig.receiver().is_some();
+        let mut skip_first = class.is_some() && !receiver && !is("staticmethod") && !is("new");
+        let class_name = class.map(|c| c.name.as_str()).unwrap_or_default();
+        let mut rust_params = Vec::new();
+        for arg in typed {
+            let Pat::Ident(ident) = &*arg.pat else {
+                return Err(format!("Unsupported parameter in {}", sig.ident));
+            };
+            if type_name(&arg.ty).is_some_and(|ty| ty == "Python") {
+                continue;
+            }
+            if std::mem::take(&mut skip_first) {
+                // `slf: PyRef<'_, Self>` or `cls`
+                continue;
+            }
+            rust_params.push((ident.ident.to_string(), &*arg.ty));
+        }
+        if name == "__exit__" {
+            params.extend([
+                "exc_type: type[BaseException] | None".to_string(),
+                "exc: BaseException | None".to_string(),
+                "traceback: TracebackType | None".to_string(),
+            ]);
+        } else {
+            let defaults = match &options.signature {
+                Some(signature) => signature.clone(),
+                // pyo3 gives trailing optional parameters a default of `None`
+                None => {
+                    let mut defaults: Vec<(String, Option<String>)> = Vec::new();
+                    let mut optional = true;
+                    for (name, ty) in rust_params.iter().rev() {
+                        optional &= type_name(ty).is_some_and(|ty| ty == "Option");
+                        defaults.push((name.clone(), optional.then(|| "None".to_string())));
+                    }
+                    defaults.reverse();
+                    defaults
+                }
+            };
+            for (name, default) in defaults {
+                let (_, ty) = rust_params
+                    .iter()
+                    .find(|(rust_name, _)| *rust_name == name)
+                    .ok_or(format!("{} is not a parameter of {}", name, sig.ident))?;
+                let ty = self.py_type(ty, class_name)?;
+                params.push(match default {
+                    Some(default) => format!("{}: {} = {}", name, ty, default),
+                    None => format!("{}: {}", name, ty),
+                });
+            }
+        }
+
+        let returns = match &sig.output {
+            _ if is("new") || is("setter") => "None".to_string(),
+            ReturnType::Default => "None".to_string(),
+            ReturnType::Type(_, ty) => self.py_type(ty, class_name)?,
+        };
+
+        let mut out = String::new();
+        if let Some(decorator) = decorator {
+            out += &format!("{}{}\n", indent, decorator);
+        }
+        let line = format!(
+            "{}def {}({}) -> {}:",
+            indent,
+            name,
+            params.join(", "),
+            returns
+        );
+        if line.len() <= LINE_LENGTH {
+            out += &line;
+        } else {
+            out += &format!("{}def {}(\n", indent, name);
+            for param in params {
+                out += &format!("{}    {},\n", indent, param);
+            }
+            out += &format!("{}) -> {}:", indent, returns);
+        }
+        let body_indent = format!("{}    ", indent);
+        match docstring(attrs, &body_indent) {
+            Some(doc) => out += &format!("\n{}", doc),
+            None => out += " ...\n",
+        }
+        Ok(out)
+    }
+
+    /// The python annotation of a Rust type, `class` is the name of the class for `Self`.
+    fn py_type(&self, ty: &Type, class: &str) -> Result<String, String> {
+        let path = match ty {
+            Type::Reference(r) => return self.py_type(&r.elem, class),
+            Type::Paren(p) => return self.py_type(&p.elem, class),
+            Type::Tuple(t) if t.elems.is_empty() => return Ok("None".to_string()),
+            Type::Tuple(t) => {
+                let elems = t
+                    .elems
+                    .iter()
+                    .map(|ty| self.py_type(ty, class))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(format!("tuple[{}]", elems.join(", ")));
+            }
+            Type::Path(path) => path.path.segments.last().unwrap(),
+            ty => return Err(format!("No python type for {}", ty.to_token_stream())),
+        };
+        let args: Vec<String> = match &path.arguments {
+            syn::PathArguments::AngleBracketed(args) => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(self.py_type(ty, class)),
+                    _ => None,
+                })
+                .collect::<Result<_, _>>()?,
+            _ => Vec::new(),
+        };
+        let arg = |i: usize| {
+            args.get(i)
+                .cloned()
+                .ok_or(format!("{} without type parameters", path.ident))
+        };
+        let ty = match path.ident.to_string().as_str() {
+            "str" | "String" | "char" | "Path" | "PathBuf" => "str".to_string(),
+            "bool" => "bool".to_string(),
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64"
+            | "u128" | "usize" => "int".to_string(),
+            "f32" | "f64" => "float".to_string(),
+            "Option" => format!("{} | None", arg(0)?),
+            "Vec" => format!("list[{}]", arg(0)?),
+            "HashSet" | "BTreeSet" => format!("set[{}]", arg(0)?),
+            "HashMap" | "BTreeMap" => format!("dict[{}, {}]", arg(0)?, arg(1)?),
+            "PyResult" | "Result" | "PyRef" | "PyRefMut" | "Py" | "Bound" => arg(0)?,
+            "PyObject" | "PyAny" => "Any".to_string(),
+            "Self" => class.to_string(),
+            name => match self.types.get(name) {
+                Some(ty) => ty.clone(),
+                None => self.union(name, class)?,
+            },
+        };
+        Ok(ty)
+    }
+
+    /// An enum whose variants each hold a value of another type, such as `Device`, is converted to
+    /// the value it holds.
+    fn union(&self, name: &str, class: &str) -> Result<String, String> {
+        let no_type = || format!("No python type for {}", name);
+        let e = self.enums.get(name).ok_or_else(no_type)?;
+        let variants = e.variants.iter().map(|variant| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                self.py_type(&fields.unnamed[0].ty, class)
+            }
+            _ => Err(no_type()),
+        });
+        Ok(variants.collect::<Result<Vec<_>, _>>()?.join(" | "))
+    }
+}
+
+/// The options of `#[pyo3(name = "...", signature = (...))]`.
+#[derive(Default)]
+struct Pyo3Options {
+    name: Option<String>,
+    /// The parameters in order, with their defaults as python expressions.
+    signature: Option<Vec<(String, Option<String>)>>,
+}
+
+impl Pyo3Options {
+    fn parse(attrs: &[Attribute]) -> Result<Self, String> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("pyo3")) {
+            let args = attr
+                .parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .map_err(|e| format!("Unsupported #[pyo3] options: {}", e))?;
+            for arg in args {
+                // `name = "..."` parses as an assignment
+                let Expr::Assign(arg) = arg else {
+                    continue;
+                };
+                match arg.left.to_token_stream().to_string().as_str() {
+                    "name" => options.name = Some(lit_str(&arg.right)?),
+                    "signature" => {
+                        let params: Vec<&Expr> = match &*arg.right {
+                            Expr::Tuple(t) => t.elems.iter().collect(),
+                            Expr::Paren(p) => vec![&p.expr],
+                            e => {
+                                return Err(format!(
+                                    "Unsupported signature {}",
+                                    e.to_token_stream()
+                                ))
+                            }
+                        };
+                        let params = params.into_iter().map(|param| match param {
+                            Expr::Assign(a) => Ok((
+                                a.left.to_token_stream().to_string(),
+                                Some(py_default(&a.right)?),
+                            )),
+                            Expr::Path(p) => Ok((p.to_token_stream().to_string(), None)),
+                            e => Err(format!("Unsupported parameter {}", e.to_token_stream())),
+                        });
+                        options.signature = Some(params.collect::<Result<_, String>>()?);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn lit_str(expr: &Expr) -> Result<String, String> {
+    match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.value()),
+        e => Err(format!("Expected a string, found {}", e.to_token_stream())),
+    }
+}
+
+/// A default value of a `#[pyo3(signature = ...)]` as a python expression.
+fn py_default(expr: &Expr) -> Result<String, String> {
+    let list = |elems: Vec<&Expr>| {
+        elems
+            .into_iter()
+            .map(py_default)
+            .collect::<Result<Vec<_>, _>>()
+            .map(|elems| elems.join(", "))
+    };
+    Ok(match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => format!("{:?}", s.value()),
+            Lit::Int(i) => i.base10_digits().to_string(),
+            Lit::Float(f) => f.base10_digits().to_string(),
+            Lit::Bool(b) if b.value => "True".to_string(),
+            Lit::Bool(_) => "False".to_string(),
+            lit => return Err(format!("Unsupported default {}", lit.to_token_stream())),
+        },
+        Expr::Path(p) if p.path.is_ident("None") => "None".to_string(),
+        Expr::Unary(u) if matches!(u.op, syn::UnOp::Neg(_)) => format!("-{}", py_default(&u.expr)?),
+        Expr::Tuple(t) => format!("({})", list(t.elems.iter().collect())?),
+        Expr::Array(a) => format!("[{}]", list(a.elems.iter().collect())?),
+        Expr::Macro(m) if m.mac.path.is_ident("vec") => {
+            let elems = m
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                .map_err(|e| e.to_string())?;
+            format!("[{}]", list(elems.iter().collect())?)
+        }
+        // `"top_left".to_string()` and the like are the value they convert
+        Expr::MethodCall(call) if call.args.is_empty() => py_default(&call.receiver)?,
+        e => return Err(format!("Unsupported default {}", e.to_token_stream())),
+    })
+}
+
+/// The doc comments as a python docstring, indented by `indent`.
+fn docstring(attrs: &[Attribute], indent: &str) -> Option<String> {
+    let mut lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(doc) if doc.path.is_ident("doc") => lit_str(&doc.value).ok(),
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .map(|line| strip_links(&line).replace('\\', "\\\\"))
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let (first, rest) = lines.split_first()?;
+    if rest.is_empty() {
+        return Some(format!("{}\"\"\"{}\"\"\"\n", indent, first));
+    }
+    let mut doc = format!("{}\"\"\"{}\n", indent, first);
+    for line in rest {
+        match line.is_empty() {
+            true => doc += "\n",
+            false => doc += &format!("{}{}\n", indent, line),
+        }
+    }
+    Some(doc + indent + "\"\"\"\n")
+}
+
+/// Replace Rust doc links such as ``[`Session`](crate::Session)`` with the code they show.
+fn strip_links(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("[`") {
+        let Some(end) = rest[start..].find("`]").map(|end| start + end) else {
+            break;
+        };
+        out += &rest[..start];
+        out += &rest[start + 1..end + 1];
+        rest = &rest[end + 2..];
+        if rest.starts_with('(') {
+            if let Some(close) = rest.find(')') {
+                rest = &rest[close + 1..];
+            }
+        }
+    }
+    out + rest
+}
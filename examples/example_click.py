@@ -2,7 +2,7 @@ from anyinput import Session
 import time
 
 session = Session()
-mouse = session.get_device("mouse")
+mouse = session.mouse
 mouse.hold("left", 5)
 
 # use anyinput::{
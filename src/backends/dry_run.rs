@@ -0,0 +1,479 @@
+//! A backend that checks and prints actions instead of performing them.
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::core::interrupt::InterruptPolicy;
+use crate::core::result::{InputError, InputResult};
+use crate::core::timed;
+use crate::core::TouchHandler;
+use crate::device::gamepad::{GamepadButton, GamepadHandler, Stick, Trigger};
+use crate::device::keyboard::{KeyboardHandler, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseHandler};
+use crate::device::pen::{PenButton, PenHandler, PenPoint, PenTool};
+use crate::layout::Layout;
+
+use super::backend::Backend;
+
+/// The keys that the dry-run backend accepts.
+#[derive(Debug, Clone, Default)]
+pub enum KeySet {
+    /// Any key, text may contain any printable character.
+    #[default]
+    Any,
+    /// Only these keys, text may only contain characters that are in the set.
+    Only(Vec<KeyboardKey>),
+    /// Any non-character key, and characters that can be typed on the layout.
+    Layout(Layout),
+}
+
+impl KeySet {
+    fn contains(&self, key: KeyboardKey) -> bool {
+        match self {
+            KeySet::Any => true,
+            KeySet::Only(keys) => keys.contains(&key),
+            KeySet::Layout(layout) => match key {
+                KeyboardKey::Unicode(c) => layout.stroke(c).is_some(),
+                _ => true,
+            },
+        }
+    }
+}
+
+pub struct DryRunConfig {
+    /// Size of the virtual screen, the cursor and touch contacts must stay on it.
+    pub screen: (i32, i32),
+    pub keys: KeySet,
+    /// Where the trace is written.
+    pub output: Box<dyn Write + Send>,
+}
+
+impl Default for DryRunConfig {
+    /// A 1920x1080 screen accepting any key, tracing to stdout.
+    fn default() -> Self {
+        Self {
+            screen: (1920, 1080),
+            keys: KeySet::Any,
+            output: Box::new(std::io::stdout()),
+        }
+    }
+}
+
+struct State {
+    output: Box<dyn Write + Send>,
+    cursor: (i32, i32),
+    buttons: Vec<MouseButton>,
+    keys: Vec<KeyboardKey>,
+    contacts: Vec<u32>,
+    gamepad_buttons: Vec<GamepadButton>,
+    sticks: [(f64, f64); 2],
+    pen: PenState,
+}
+
+#[derive(Default)]
+struct PenState {
+    tool: PenTool,
+    in_range: bool,
+    down: bool,
+    buttons: Vec<PenButton>,
+}
+
+/// Validates actions against a virtual screen and key set and prints a timestamped trace of them,
+/// without injecting any input.
+///
+/// The cursor starts in the middle of the screen.
+pub struct DryRunBackend {
+    screen: (i32, i32),
+    keys: KeySet,
+    start_time: Instant,
+    state: Mutex<State>,
+}
+
+impl std::fmt::Debug for DryRunBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DryRunBackend")
+            .field("screen", &self.screen)
+            .field("keys", &self.keys)
+            .finish()
+    }
+}
+
+impl Backend for DryRunBackend {}
+
+impl Default for DryRunBackend {
+    fn default() -> Self {
+        Self::new(DryRunConfig::default())
+    }
+}
+
+impl DryRunBackend {
+    pub fn new(config: DryRunConfig) -> Self {
+        let (width, height) = config.screen;
+        Self {
+            screen: config.screen,
+            keys: config.keys,
+            start_time: Instant::now(),
+            state: Mutex::new(State {
+                output: config.output,
+                cursor: (width / 2, height / 2),
+                buttons: Vec::new(),
+                keys: Vec::new(),
+                contacts: Vec::new(),
+                gamepad_buttons: Vec::new(),
+                sticks: [(0.0, 0.0); 2],
+                pen: PenState::default(),
+            }),
+        }
+    }
+
+    /// Write a line of the trace, prefixed with the time since the backend was created.
+    fn trace(&self, state: &mut State, message: &str) -> InputResult {
+        let elapsed = self.start_time.elapsed().as_secs_f64();
+        writeln!(state.output, "[{:>9.3}s] {}", elapsed, message)?;
+        Ok(())
+    }
+
+    fn check_position(&self, (x, y): (i32, i32)) -> InputResult {
+        let (width, height) = self.screen;
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return Err(InputError::error(format!(
+                "Position ({}, {}) is outside of the {}x{} screen.",
+                x, y, width, height
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_key(&self, key: KeyboardKey) -> InputResult {
+        if !self.keys.contains(key) {
+            return Err(InputError::error(format!(
+                "{} is not in the key set.",
+                describe_key(key)
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn describe_key(key: KeyboardKey) -> String {
+    match key {
+        KeyboardKey::Unicode(c) => format!("{:?}", c),
+        KeyboardKey::Physical(key) => format!("physical {}", key),
+        key => format!("{:?}", key),
+    }
+}
+
+impl MouseHandler for DryRunBackend {
+    fn press(&self, button: MouseButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        if !state.buttons.contains(&button) {
+            state.buttons.push(button);
+        }
+        let message = format!("mouse press {}", button);
+        self.trace(&mut state, &message)
+    }
+
+    fn release(&self, button: MouseButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let held = state.buttons.contains(&button);
+        state.buttons.retain(|b| *b != button);
+        let message = match held {
+            true => format!("mouse release {}", button),
+            false => format!("mouse release {} (was not held)", button),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn click(&self, button: MouseButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let message = format!("mouse click {}", button);
+        self.trace(&mut state, &message)
+    }
+
+    fn move_abs(&self, x: i32, y: i32, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::move_abs(self, x, y, duration, &mut policy.watch(None)?);
+        }
+        self.check_position((x, y))?;
+        let mut state = self.state.lock().unwrap();
+        state.cursor = (x, y);
+        self.trace(&mut state, &format!("mouse move to ({}, {})", x, y))
+    }
+
+    fn move_rel(&self, dx: i32, dy: i32, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::move_rel(self, dx, dy, duration, &mut policy.watch(None)?);
+        }
+        let mut state = self.state.lock().unwrap();
+        let (x, y) = (state.cursor.0 + dx, state.cursor.1 + dy);
+        self.check_position((x, y))?;
+        state.cursor = (x, y);
+        let message = format!("mouse move by ({}, {}) to ({}, {})", dx, dy, x, y);
+        self.trace(&mut state, &message)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        self.trace(&mut state, &format!("mouse scroll ({}, {})", dx, dy))
+    }
+
+    fn location(&self) -> Result<(i32, i32), InputError> {
+        Ok(self.state.lock().unwrap().cursor)
+    }
+
+    fn display_size(&self) -> Result<(i32, i32), InputError> {
+        Ok(self.screen)
+    }
+
+    fn pressed_buttons(&self) -> Result<Vec<MouseButton>, InputError> {
+        Ok(self.state.lock().unwrap().buttons.clone())
+    }
+}
+
+impl KeyboardHandler for DryRunBackend {
+    fn press(&self, key: KeyboardKey) -> InputResult {
+        self.check_key(key)?;
+        let mut state = self.state.lock().unwrap();
+        if !state.keys.contains(&key) {
+            state.keys.push(key);
+        }
+        self.trace(&mut state, &format!("key press {}", describe_key(key)))
+    }
+
+    fn release(&self, key: KeyboardKey) -> InputResult {
+        self.check_key(key)?;
+        let mut state = self.state.lock().unwrap();
+        let held = state.keys.contains(&key);
+        state.keys.retain(|k| *k != key);
+        let message = match held {
+            true => format!("key release {}", describe_key(key)),
+            false => format!("key release {} (was not held)", describe_key(key)),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn text(&self, text: &str, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::text(self, text, duration, &policy.watch(None)?);
+        }
+        for c in text.chars() {
+            match &self.keys {
+                KeySet::Any if c.is_control() && c != '\n' && c != '\t' => {
+                    return Err(InputError::error(format!("{:?} can not be typed.", c)));
+                }
+                KeySet::Any => {}
+                KeySet::Only(_) | KeySet::Layout(_) => self.check_key(KeyboardKey::Unicode(c))?,
+            }
+        }
+        let mut state = self.state.lock().unwrap();
+        self.trace(&mut state, &format!("type {:?}", text))
+    }
+
+    fn pressed_keys(&self) -> Result<Vec<KeyboardKey>, InputError> {
+        Ok(self.state.lock().unwrap().keys.clone())
+    }
+}
+
+impl TouchHandler for DryRunBackend {
+    fn down(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.check_position((x, y))?;
+        let mut state = self.state.lock().unwrap();
+        if state.contacts.contains(&id) {
+            return Err(InputError::error(format!(
+                "Touch contact {} is already down.",
+                id
+            )));
+        }
+        state.contacts.push(id);
+        self.trace(&mut state, &format!("touch {} down at ({}, {})", id, x, y))
+    }
+
+    fn move_to(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.check_position((x, y))?;
+        let mut state = self.state.lock().unwrap();
+        if !state.contacts.contains(&id) {
+            return Err(InputError::error(format!(
+                "Touch contact {} is not down.",
+                id
+            )));
+        }
+        self.trace(&mut state, &format!("touch {} move to ({}, {})", id, x, y))
+    }
+
+    fn up(&self, id: u32) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        if !state.contacts.contains(&id) {
+            return Err(InputError::error(format!(
+                "Touch contact {} is not down.",
+                id
+            )));
+        }
+        state.contacts.retain(|c| *c != id);
+        self.trace(&mut state, &format!("touch {} up", id))
+    }
+
+    fn active_contacts(&self) -> Result<Vec<u32>, InputError> {
+        Ok(self.state.lock().unwrap().contacts.clone())
+    }
+}
+
+fn stick_index(stick: Stick) -> usize {
+    match stick {
+        Stick::Left => 0,
+        Stick::Right => 1,
+    }
+}
+
+impl GamepadHandler for DryRunBackend {
+    fn press(&self, button: GamepadButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        if !state.gamepad_buttons.contains(&button) {
+            state.gamepad_buttons.push(button);
+        }
+        self.trace(&mut state, &format!("gamepad press {}", button))
+    }
+
+    fn release(&self, button: GamepadButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let held = state.gamepad_buttons.contains(&button);
+        state.gamepad_buttons.retain(|b| *b != button);
+        let message = match held {
+            true => format!("gamepad release {}", button),
+            false => format!("gamepad release {} (was not held)", button),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+        if !(-1.0..=1.0).contains(&x) || !(-1.0..=1.0).contains(&y) {
+            return Err(InputError::error(format!(
+                "Stick position ({}, {}) is outside of -1.0 to 1.0.",
+                x, y
+            )));
+        }
+        let mut state = self.state.lock().unwrap();
+        state.sticks[stick_index(stick)] = (x, y);
+        let message = format!("gamepad {} stick to ({:.2}, {:.2})", stick, x, y);
+        self.trace(&mut state, &message)
+    }
+
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+        Ok(self.state.lock().unwrap().sticks[stick_index(stick)])
+    }
+
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(InputError::error(format!(
+                "Trigger value {} is outside of 0.0 to 1.0.",
+                value
+            )));
+        }
+        let mut state = self.state.lock().unwrap();
+        let message = format!("gamepad {} trigger to {:.2}", trigger, value);
+        self.trace(&mut state, &message)
+    }
+}
+
+fn describe_point(point: &PenPoint) -> String {
+    let mut description = format!("({}, {})", point.x, point.y);
+    if point.tilt_x != 0.0 || point.tilt_y != 0.0 {
+        description += &format!(" tilted ({:.1}, {:.1})", point.tilt_x, point.tilt_y);
+    }
+    description
+}
+
+// the pen is a screen tablet, so it moves the cursor like touch input does on most systems
+impl PenHandler for DryRunBackend {
+    fn hover(&self, point: PenPoint) -> InputResult {
+        point.check()?;
+        self.check_position((point.x, point.y))?;
+        let mut state = self.state.lock().unwrap();
+        state.cursor = (point.x, point.y);
+        state.pen.in_range = true;
+        state.pen.down = false;
+        let message = format!("pen {} hover at {}", state.pen.tool, describe_point(&point));
+        self.trace(&mut state, &message)
+    }
+
+    fn down(&self, point: PenPoint) -> InputResult {
+        point.check()?;
+        self.check_position((point.x, point.y))?;
+        let mut state = self.state.lock().unwrap();
+        state.cursor = (point.x, point.y);
+        state.pen.in_range = true;
+        state.pen.down = true;
+        let message = format!(
+            "pen {} down at {} pressure {:.2}",
+            state.pen.tool,
+            describe_point(&point),
+            point.pressure
+        );
+        self.trace(&mut state, &message)
+    }
+
+    fn move_to(&self, point: PenPoint) -> InputResult {
+        point.check()?;
+        self.check_position((point.x, point.y))?;
+        let mut state = self.state.lock().unwrap();
+        state.cursor = (point.x, point.y);
+        state.pen.in_range = true;
+        let message = match state.pen.down {
+            true => format!(
+                "pen {} move to {} pressure {:.2}",
+                state.pen.tool,
+                describe_point(&point),
+                point.pressure
+            ),
+            false => format!("pen {} hover at {}", state.pen.tool, describe_point(&point)),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn up(&self) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let message = match std::mem::take(&mut state.pen.down) {
+            true => format!("pen {} up", state.pen.tool),
+            false => format!("pen {} up (was not down)", state.pen.tool),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn leave(&self) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        state.pen.down = false;
+        let message = match std::mem::take(&mut state.pen.in_range) {
+            true => format!("pen {} leave", state.pen.tool),
+            false => format!("pen {} leave (was not in range)", state.pen.tool),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn press(&self, button: PenButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        if !state.pen.buttons.contains(&button) {
+            state.pen.buttons.push(button);
+        }
+        self.trace(&mut state, &format!("pen press {}", button))
+    }
+
+    fn release(&self, button: PenButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let held = state.pen.buttons.contains(&button);
+        state.pen.buttons.retain(|b| *b != button);
+        let message = match held {
+            true => format!("pen release {}", button),
+            false => format!("pen release {} (was not held)", button),
+        };
+        self.trace(&mut state, &message)
+    }
+
+    fn set_tool(&self, tool: PenTool) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        state.pen.tool = tool;
+        state.pen.down = false;
+        self.trace(&mut state, &format!("pen tool {}", tool))
+    }
+}
@@ -1,2 +1,116 @@
 pub mod backend;
+pub mod dry_run;
 pub mod enigo_backend;
+pub mod traced;
+pub mod uinput;
+
+use std::sync::Arc;
+
+use crate::core::result::InputError;
+use crate::core::{GamepadHandler, KeyboardHandler, MouseHandler, PenHandler, TouchHandler};
+use dry_run::DryRunBackend;
+use enigo_backend::EnigoBackend;
+use traced::Traced;
+
+/// Names of the backends that can be created with [`create`], the first is the default.
+pub const BACKENDS: &[&str] = &["enigo", "dry-run"];
+
+/// The device handlers provided by a backend.
+#[derive(Clone)]
+pub struct Handlers {
+    pub mouse: Arc<dyn MouseHandler + Send + Sync>,
+    pub keyboard: Arc<dyn KeyboardHandler + Send + Sync>,
+    pub touch: Arc<dyn TouchHandler + Send + Sync>,
+    /// `None` if the backend has no gamepad.
+    pub gamepad: Option<Arc<dyn GamepadHandler + Send + Sync>>,
+    /// `None` if the backend has no pen tablet.
+    pub pen: Option<Arc<dyn PenHandler + Send + Sync>>,
+}
+
+impl Handlers {
+    /// Use a single backend for every device except the gamepad and pen, see
+    /// [`Handlers::with_gamepad`] and [`Handlers::with_pen`].
+    pub fn from_backend<B>(backend: B) -> Self
+    where
+        B: MouseHandler + KeyboardHandler + TouchHandler + Send + Sync + 'static,
+    {
+        Self::from_shared(Arc::new(backend))
+    }
+
+    /// Like [`Handlers::from_backend`], for a backend that is also used elsewhere.
+    pub fn from_shared<B>(backend: Arc<B>) -> Self
+    where
+        B: MouseHandler + KeyboardHandler + TouchHandler + Send + Sync + 'static,
+    {
+        Self {
+            mouse: Arc::clone(&backend) as Arc<dyn MouseHandler + Send + Sync>,
+            keyboard: Arc::clone(&backend) as Arc<dyn KeyboardHandler + Send + Sync>,
+            // this is unlikely to use the same backend?
+            touch: backend as Arc<dyn TouchHandler + Send + Sync>,
+            gamepad: None,
+            pen: None,
+        }
+    }
+
+    pub fn with_gamepad(mut self, gamepad: Arc<dyn GamepadHandler + Send + Sync>) -> Self {
+        self.gamepad = Some(gamepad);
+        self
+    }
+
+    pub fn with_pen(mut self, pen: Arc<dyn PenHandler + Send + Sync>) -> Self {
+        self.pen = Some(pen);
+        self
+    }
+}
+
+/// The gamepad that is used with backends that only simulate a mouse and keyboard, a virtual
+/// Xbox controller created with uinput on Linux.
+fn system_gamepad() -> Option<Arc<dyn GamepadHandler + Send + Sync>> {
+    #[cfg(target_os = "linux")]
+    return Some(Arc::new(Traced::new(
+        "uinput",
+        uinput::Lazy::new(uinput::UinputGamepad::open),
+    )));
+    #[cfg(not(target_os = "linux"))]
+    return None;
+}
+
+/// The pen that is used with backends that only simulate a mouse and keyboard, a virtual screen
+/// tablet created with uinput on Linux. The tablet covers the display of `mouse`.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+fn system_pen(
+    mouse: Arc<dyn MouseHandler + Send + Sync>,
+) -> Option<Arc<dyn PenHandler + Send + Sync>> {
+    #[cfg(target_os = "linux")]
+    return Some(Arc::new(Traced::new(
+        "uinput",
+        uinput::Lazy::new(move || uinput::UinputPen::open(mouse.display_size()?)),
+    )));
+    #[cfg(not(target_os = "linux"))]
+    return None;
+}
+
+/// Create the backend with the given name, see [`BACKENDS`].
+pub fn create(name: &str) -> Result<Handlers, InputError> {
+    match name.to_lowercase().as_str() {
+        "enigo" => {
+            let handlers = Handlers::from_backend(Traced::new("enigo", EnigoBackend::default()));
+            Ok(Handlers {
+                gamepad: system_gamepad(),
+                pen: system_pen(Arc::clone(&handlers.mouse)),
+                ..handlers
+            })
+        }
+        "dry-run" | "dry_run" | "dryrun" => {
+            let backend = Arc::new(Traced::new("dry-run", DryRunBackend::default()));
+            Ok(Handlers::from_shared(Arc::clone(&backend))
+                .with_gamepad(Arc::clone(&backend) as Arc<dyn GamepadHandler + Send + Sync>)
+                .with_pen(backend))
+        }
+        _ => Err(InputError::error(format!(
+            "Unknown backend '{}', available backends: {}",
+            name,
+            BACKENDS.join(", ")
+        ))),
+    }
+}
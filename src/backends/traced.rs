@@ -0,0 +1,225 @@
+//! A wrapper that emits a `tracing` event for every primitive of a backend.
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+use crate::core::interrupt::InterruptPolicy;
+use crate::core::result::{InputError, InputResult};
+use crate::core::{receipt, timed, trace};
+use crate::core::{GamepadHandler, KeyboardHandler, MouseHandler, TouchHandler};
+use crate::device::gamepad::{GamepadButton, Stick, Trigger};
+use crate::device::keyboard::KeyboardKey;
+use crate::device::mouse::MouseButton;
+use crate::device::pen::{PenButton, PenHandler, PenPoint, PenTool};
+
+/// Emits a `TRACE` event for each primitive performed by `backend`, with the backend's name, the
+/// device, the primitive, its arguments, duration and result.
+///
+/// Timed actions are split into primitives here, so that each step is traced. Queries (the
+/// cursor location and display size) are not traced.
+pub struct Traced<B> {
+    name: &'static str,
+    backend: B,
+}
+
+impl<B> Traced<B> {
+    pub fn new(name: &'static str, backend: B) -> Self {
+        Self { name, backend }
+    }
+
+    fn primitive(
+        &self,
+        device: &'static str,
+        action: &'static str,
+        args: impl Debug,
+        primitive: impl FnOnce(&B) -> InputResult,
+    ) -> InputResult {
+        self.primitives(1, device, action, args, primitive)
+    }
+
+    /// Like `primitive`, for a backend call that emits `count` primitives, e.g. a tap is a press
+    /// and a release.
+    fn primitives(
+        &self,
+        count: u32,
+        device: &'static str,
+        action: &'static str,
+        args: impl Debug,
+        primitive: impl FnOnce(&B) -> InputResult,
+    ) -> InputResult {
+        receipt::record_primitives(count);
+        let start_time = Instant::now();
+        let result = primitive(&self.backend);
+        tracing::trace!(
+            backend = self.name,
+            device,
+            action,
+            ?args,
+            duration = ?start_time.elapsed(),
+            result = trace::outcome(&result),
+            "primitive"
+        );
+        result
+    }
+}
+
+impl<B: MouseHandler> MouseHandler for Traced<B> {
+    fn press(&self, button: MouseButton) -> InputResult {
+        self.primitive("mouse", "press", button, |b| b.press(button))
+    }
+
+    fn release(&self, button: MouseButton) -> InputResult {
+        self.primitive("mouse", "release", button, |b| b.release(button))
+    }
+
+    fn click(&self, button: MouseButton) -> InputResult {
+        self.primitives(2, "mouse", "click", button, |b| b.click(button))
+    }
+
+    fn move_abs(&self, x: i32, y: i32, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::move_abs(self, x, y, duration, &mut policy.watch(None)?);
+        }
+        self.primitive("mouse", "move_abs", (x, y), |b| {
+            b.move_abs(x, y, Duration::ZERO)
+        })
+    }
+
+    fn move_rel(&self, dx: i32, dy: i32, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::move_rel(self, dx, dy, duration, &mut policy.watch(None)?);
+        }
+        self.primitive("mouse", "move_rel", (dx, dy), |b| {
+            b.move_rel(dx, dy, Duration::ZERO)
+        })
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult {
+        self.primitive("mouse", "scroll", (dx, dy), |b| b.scroll(dx, dy))
+    }
+
+    fn location(&self) -> Result<(i32, i32), InputError> {
+        self.backend.location()
+    }
+
+    fn display_size(&self) -> Result<(i32, i32), InputError> {
+        self.backend.display_size()
+    }
+
+    fn pressed_buttons(&self) -> Result<Vec<MouseButton>, InputError> {
+        self.backend.pressed_buttons()
+    }
+}
+
+impl<B: KeyboardHandler> KeyboardHandler for Traced<B> {
+    fn press(&self, key: KeyboardKey) -> InputResult {
+        self.primitive("keyboard", "press", key, |b| b.press(key))
+    }
+
+    fn release(&self, key: KeyboardKey) -> InputResult {
+        self.primitive("keyboard", "release", key, |b| b.release(key))
+    }
+
+    fn tap(&self, key: KeyboardKey) -> InputResult {
+        self.primitives(2, "keyboard", "tap", key, |b| b.tap(key))
+    }
+
+    fn text(&self, text: &str, duration: Duration) -> InputResult {
+        if !duration.is_zero() {
+            let policy = InterruptPolicy::none();
+            return timed::text(self, text, duration, &policy.watch(None)?);
+        }
+        self.primitive("keyboard", "text", text, |b| b.text(text, Duration::ZERO))
+    }
+
+    fn pressed_keys(&self) -> Result<Vec<KeyboardKey>, InputError> {
+        self.backend.pressed_keys()
+    }
+}
+
+impl<B: TouchHandler> TouchHandler for Traced<B> {
+    fn down(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.primitive("touch", "down", (id, x, y), |b| b.down(id, x, y))
+    }
+
+    fn move_to(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.primitive("touch", "move_to", (id, x, y), |b| b.move_to(id, x, y))
+    }
+
+    fn up(&self, id: u32) -> InputResult {
+        self.primitive("touch", "up", id, |b| b.up(id))
+    }
+
+    fn tap(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.primitives(2, "touch", "tap", (id, x, y), |b| b.tap(id, x, y))
+    }
+
+    fn active_contacts(&self) -> Result<Vec<u32>, InputError> {
+        self.backend.active_contacts()
+    }
+}
+
+impl<B: GamepadHandler> GamepadHandler for Traced<B> {
+    fn press(&self, button: GamepadButton) -> InputResult {
+        self.primitive("gamepad", "press", button, |b| b.press(button))
+    }
+
+    fn release(&self, button: GamepadButton) -> InputResult {
+        self.primitive("gamepad", "release", button, |b| b.release(button))
+    }
+
+    fn tap(&self, button: GamepadButton) -> InputResult {
+        self.primitives(2, "gamepad", "tap", button, |b| b.tap(button))
+    }
+
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+        self.primitive("gamepad", "move_stick", (stick, x, y), |b| {
+            b.move_stick(stick, x, y)
+        })
+    }
+
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+        self.backend.stick(stick)
+    }
+
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+        self.primitive("gamepad", "set_trigger", (trigger, value), |b| {
+            b.set_trigger(trigger, value)
+        })
+    }
+}
+
+impl<B: PenHandler> PenHandler for Traced<B> {
+    fn hover(&self, point: PenPoint) -> InputResult {
+        self.primitive("pen", "hover", point, |b| b.hover(point))
+    }
+
+    fn down(&self, point: PenPoint) -> InputResult {
+        self.primitive("pen", "down", point, |b| b.down(point))
+    }
+
+    fn move_to(&self, point: PenPoint) -> InputResult {
+        self.primitive("pen", "move_to", point, |b| b.move_to(point))
+    }
+
+    fn up(&self) -> InputResult {
+        self.primitive("pen", "up", (), |b| b.up())
+    }
+
+    fn leave(&self) -> InputResult {
+        self.primitive("pen", "leave", (), |b| b.leave())
+    }
+
+    fn press(&self, button: PenButton) -> InputResult {
+        self.primitive("pen", "press", button, |b| b.press(button))
+    }
+
+    fn release(&self, button: PenButton) -> InputResult {
+        self.primitive("pen", "release", button, |b| b.release(button))
+    }
+
+    fn set_tool(&self, tool: PenTool) -> InputResult {
+        self.primitive("pen", "set_tool", tool, |b| b.set_tool(tool))
+    }
+}
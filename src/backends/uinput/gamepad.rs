@@ -0,0 +1,209 @@
+//! A virtual Xbox 360 controller.
+use std::io::Write;
+use std::sync::Mutex;
+
+use crate::core::result::{InputError, InputResult};
+use crate::device::gamepad::{GamepadButton, GamepadHandler, Stick, Trigger};
+use crate::evdev::{self, codes};
+
+use super::emit;
+
+/// USB ids of the Xbox 360 controller, which games and the xpad driver recognise.
+pub const VENDOR: u16 = 0x045e;
+pub const PRODUCT: u16 = 0x028e;
+pub const NAME: &str = "Microsoft X-Box 360 pad";
+
+/// Sticks report -32768 to 32767 on each axis, triggers 0 to 255, like xpad.
+const STICK_MAX: i32 = 32767;
+const TRIGGER_MAX: i32 = 255;
+
+struct State<W> {
+    writer: W,
+    sticks: [(f64, f64); 2],
+    // the d-pad directions that are held, the d-pad is reported as the hat axes
+    dpad: Vec<GamepadButton>,
+}
+
+/// An Xbox 360 controller that writes its events to `W`.
+pub struct UinputGamepad<W> {
+    state: Mutex<State<W>>,
+}
+
+impl<W: Write> UinputGamepad<W> {
+    /// A gamepad whose events are written to `writer`, e.g. a file to record an event stream.
+    pub fn new(writer: W) -> Self {
+        Self {
+            state: Mutex::new(State {
+                writer,
+                sticks: [(0.0, 0.0); 2],
+                dpad: Vec::new(),
+            }),
+        }
+    }
+
+    /// The writer, e.g. to read back the recorded events.
+    pub fn into_inner(self) -> W {
+        self.state.into_inner().unwrap().writer
+    }
+
+    fn set_dpad(&self, button: GamepadButton, pressed: bool) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        state.dpad.retain(|b| *b != button);
+        if pressed {
+            state.dpad.push(button);
+        }
+        // opposite directions that are both held cancel out
+        let (x, y) = state
+            .dpad
+            .iter()
+            .filter_map(|b| b.dpad())
+            .fold((0, 0), |(x, y), (dx, dy)| (x + dx, y + dy));
+        let events = [
+            (evdev::EV_ABS, evdev::ABS_HAT0X, x),
+            (evdev::EV_ABS, evdev::ABS_HAT0Y, y),
+        ];
+        emit(&mut state.writer, &events)
+    }
+}
+
+fn stick_index(stick: Stick) -> usize {
+    match stick {
+        Stick::Left => 0,
+        Stick::Right => 1,
+    }
+}
+
+impl<W: Write> GamepadHandler for UinputGamepad<W> {
+    fn press(&self, button: GamepadButton) -> InputResult {
+        match codes::gamepad_button_to_code(button) {
+            Some(code) => {
+                let mut state = self.state.lock().unwrap();
+                emit(&mut state.writer, &[(evdev::EV_KEY, code, 1)])
+            }
+            None => self.set_dpad(button, true),
+        }
+    }
+
+    fn release(&self, button: GamepadButton) -> InputResult {
+        match codes::gamepad_button_to_code(button) {
+            Some(code) => {
+                let mut state = self.state.lock().unwrap();
+                emit(&mut state.writer, &[(evdev::EV_KEY, code, 0)])
+            }
+            None => self.set_dpad(button, false),
+        }
+    }
+
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+        let (x, y) = (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0));
+        let (code_x, code_y) = match stick {
+            Stick::Left => (evdev::ABS_X, evdev::ABS_Y),
+            Stick::Right => (evdev::ABS_RX, evdev::ABS_RY),
+        };
+        let mut state = self.state.lock().unwrap();
+        let events = [
+            (evdev::EV_ABS, code_x, (x * STICK_MAX as f64).round() as i32),
+            (evdev::EV_ABS, code_y, (y * STICK_MAX as f64).round() as i32),
+        ];
+        emit(&mut state.writer, &events)?;
+        state.sticks[stick_index(stick)] = (x, y);
+        Ok(())
+    }
+
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+        Ok(self.state.lock().unwrap().sticks[stick_index(stick)])
+    }
+
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+        let code = match trigger {
+            Trigger::Left => evdev::ABS_Z,
+            Trigger::Right => evdev::ABS_RZ,
+        };
+        let value = (value.clamp(0.0, 1.0) * TRIGGER_MAX as f64).round() as i32;
+        let mut state = self.state.lock().unwrap();
+        emit(&mut state.writer, &[(evdev::EV_ABS, code, value)])
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod device {
+    use std::fs::File;
+
+    use super::*;
+    use crate::backends::uinput::device::{create, DeviceSpec};
+    use crate::backends::uinput::{Axis, Lazy};
+
+    const BUS_USB: u16 = 0x03;
+
+    /// The absolute axes, with the dead zone and noise filter of xpad on the sticks.
+    const AXES: &[Axis] = &[
+        stick_axis(evdev::ABS_X),
+        stick_axis(evdev::ABS_Y),
+        stick_axis(evdev::ABS_RX),
+        stick_axis(evdev::ABS_RY),
+        Axis::new(evdev::ABS_Z, 0, TRIGGER_MAX),
+        Axis::new(evdev::ABS_RZ, 0, TRIGGER_MAX),
+        Axis::new(evdev::ABS_HAT0X, -1, 1),
+        Axis::new(evdev::ABS_HAT0Y, -1, 1),
+    ];
+
+    const fn stick_axis(code: u16) -> Axis {
+        Axis {
+            fuzz: 16,
+            flat: 128,
+            ..Axis::new(code, -STICK_MAX - 1, STICK_MAX)
+        }
+    }
+
+    impl UinputGamepad<File> {
+        /// Create a virtual Xbox 360 controller, it is removed when the gamepad is dropped.
+        ///
+        /// This needs write access to `/dev/uinput`, usually root or membership of the `input`
+        /// group.
+        pub fn open() -> Result<Self, InputError> {
+            let keys: Vec<u16> = GamepadButton::ALL
+                .into_iter()
+                .filter_map(codes::gamepad_button_to_code)
+                .collect();
+            let file = create(&DeviceSpec {
+                name: NAME,
+                id: libc::input_id {
+                    bustype: BUS_USB,
+                    vendor: VENDOR,
+                    product: PRODUCT,
+                    version: 0x0110,
+                },
+                keys: &keys,
+                axes: AXES,
+                properties: &[],
+            })?;
+            Ok(Self::new(file))
+        }
+    }
+
+    impl GamepadHandler for Lazy<UinputGamepad<File>> {
+        fn press(&self, button: GamepadButton) -> InputResult {
+            self.get()?.press(button)
+        }
+
+        fn release(&self, button: GamepadButton) -> InputResult {
+            self.get()?.release(button)
+        }
+
+        fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+            self.get()?.move_stick(stick, x, y)
+        }
+
+        fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+            // a device that does not exist yet has its sticks centred
+            match self.created() {
+                Some(gamepad) => gamepad.stick(stick),
+                None => Ok((0.0, 0.0)),
+            }
+        }
+
+        fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+            self.get()?.set_trigger(trigger, value)
+        }
+    }
+}
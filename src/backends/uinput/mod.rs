@@ -0,0 +1,184 @@
+//! Virtual devices created through Linux uinput.
+//!
+//! A device is a stream of evdev events. They are written to `/dev/uinput` for a real device, or
+//! to any writer as a recorded event stream in the format of `/dev/input/event*` (see
+//! [`RawEvent`]), so that the events can be checked without creating a device.
+pub mod gamepad;
+pub mod pen;
+
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::result::InputResult;
+use crate::evdev::{self, RawEvent};
+
+pub use gamepad::UinputGamepad;
+pub use pen::UinputPen;
+
+#[cfg(target_os = "linux")]
+pub use device::Lazy;
+
+/// Write `events` followed by a `SYN_REPORT`, timestamped with the current time.
+fn emit(writer: &mut impl Write, events: &[(u16, u16, i32)]) -> InputResult {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let sync = (evdev::EV_SYN, evdev::SYN_REPORT, 0);
+    for &(kind, code, value) in events.iter().chain([&sync]) {
+        let event = RawEvent {
+            time,
+            ..RawEvent::new(kind, code, value)
+        };
+        event.write_to(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// An absolute axis that a device reports, see `struct input_absinfo`.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    code: u16,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    /// Units per millimetre, or per radian for tilt.
+    resolution: i32,
+}
+
+#[cfg(target_os = "linux")]
+impl Axis {
+    const fn new(code: u16, minimum: i32, maximum: i32) -> Self {
+        Self {
+            code,
+            minimum,
+            maximum,
+            fuzz: 0,
+            flat: 0,
+            resolution: 0,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod device {
+    use std::fs::{File, OpenOptions};
+    use std::os::fd::{AsRawFd, RawFd};
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::core::result::InputError;
+
+    // ioctl requests from linux/uinput.h
+    const UI_DEV_CREATE: u64 = 0x5501;
+    const UI_DEV_SETUP: u64 = 0x405c5503;
+    const UI_ABS_SETUP: u64 = 0x401c5504;
+    const UI_SET_EVBIT: u64 = 0x40045564;
+    const UI_SET_KEYBIT: u64 = 0x40045565;
+    const UI_SET_ABSBIT: u64 = 0x40045567;
+    const UI_SET_PROPBIT: u64 = 0x4004556e;
+
+    /// What a uinput device is called and which events it reports.
+    pub(super) struct DeviceSpec<'a> {
+        pub name: &'a str,
+        pub id: libc::input_id,
+        pub keys: &'a [u16],
+        pub axes: &'a [Axis],
+        /// `INPUT_PROP_*` flags, e.g. whether a tablet is a screen.
+        pub properties: &'a [u16],
+    }
+
+    /// `arg` is an int, or a pointer to the struct that the request expects.
+    fn ioctl(fd: RawFd, request: u64, arg: libc::c_ulong) -> InputResult {
+        // SAFETY: only called with uinput requests and the arguments they expect
+        if unsafe { libc::ioctl(fd, request as _, arg) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Create a uinput device, it is removed when the file is closed.
+    ///
+    /// This needs write access to `/dev/uinput`, usually root or membership of the `input` group.
+    pub(super) fn create(spec: &DeviceSpec) -> Result<File, InputError> {
+        let file = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")
+            .map_err(|e| InputError::error(format!("Failed to open /dev/uinput: {}", e)))?;
+        let fd = file.as_raw_fd();
+        ioctl(fd, UI_SET_EVBIT, evdev::EV_KEY as libc::c_ulong)?;
+        for &code in spec.keys {
+            ioctl(fd, UI_SET_KEYBIT, code as libc::c_ulong)?;
+        }
+        ioctl(fd, UI_SET_EVBIT, evdev::EV_ABS as libc::c_ulong)?;
+        for axis in spec.axes {
+            ioctl(fd, UI_SET_ABSBIT, axis.code as libc::c_ulong)?;
+            let setup = libc::uinput_abs_setup {
+                code: axis.code,
+                absinfo: libc::input_absinfo {
+                    value: 0,
+                    minimum: axis.minimum,
+                    maximum: axis.maximum,
+                    fuzz: axis.fuzz,
+                    flat: axis.flat,
+                    resolution: axis.resolution,
+                },
+            };
+            ioctl(fd, UI_ABS_SETUP, &setup as *const _ as libc::c_ulong)?;
+        }
+        for &property in spec.properties {
+            ioctl(fd, UI_SET_PROPBIT, property as libc::c_ulong)?;
+        }
+        // SAFETY: uinput_setup is plain data, all zeroes is valid
+        let mut setup: libc::uinput_setup = unsafe { std::mem::zeroed() };
+        setup.id = spec.id;
+        // the name stays nul terminated
+        let name = setup.name.len() - 1;
+        for (c, b) in setup.name[..name].iter_mut().zip(spec.name.bytes()) {
+            *c = b as libc::c_char;
+        }
+        ioctl(fd, UI_DEV_SETUP, &setup as *const _ as libc::c_ulong)?;
+        ioctl(fd, UI_DEV_CREATE, 0)?;
+        Ok(file)
+    }
+
+    type Open<T> = Box<dyn Fn() -> Result<T, InputError> + Send + Sync>;
+
+    /// A uinput device that is created the first time it is used, so that sessions that never use
+    /// it do not add a device to the system.
+    ///
+    /// Creating the device is retried on the next action if it fails.
+    pub struct Lazy<T> {
+        device: Mutex<Option<Arc<T>>>,
+        open: Open<T>,
+    }
+
+    impl<T> Lazy<T> {
+        pub fn new(open: impl Fn() -> Result<T, InputError> + Send + Sync + 'static) -> Self {
+            Self {
+                device: Mutex::new(None),
+                open: Box::new(open),
+            }
+        }
+
+        /// The device, it is created if it does not exist yet.
+        pub fn get(&self) -> Result<Arc<T>, InputError> {
+            let mut device = self.device.lock().unwrap();
+            if let Some(device) = &*device {
+                return Ok(Arc::clone(device));
+            }
+            let created = Arc::new((self.open)()?);
+            *device = Some(Arc::clone(&created));
+            Ok(created)
+        }
+
+        /// The device, `None` if it has not been created yet.
+        pub fn created(&self) -> Option<Arc<T>> {
+            self.device.lock().unwrap().clone()
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! A virtual pen on a screen tablet.
+use std::io::Write;
+use std::sync::Mutex;
+
+use crate::core::result::InputResult;
+use crate::device::pen::{PenButton, PenHandler, PenPoint, PenTool};
+use crate::evdev::{self, codes};
+
+use super::emit;
+
+pub const NAME: &str = "anyinput virtual pen";
+
+/// Pressure is reported from 0 to 4095, like most Wacom pens.
+const PRESSURE_MAX: i32 = 4095;
+
+fn tool_code(tool: PenTool) -> u16 {
+    match tool {
+        PenTool::Pen => codes::BTN_TOOL_PEN,
+        PenTool::Eraser => codes::BTN_TOOL_RUBBER,
+    }
+}
+
+fn button_code(button: PenButton) -> u16 {
+    match button {
+        PenButton::Primary => codes::BTN_STYLUS,
+        PenButton::Secondary => codes::BTN_STYLUS2,
+    }
+}
+
+struct State<W> {
+    writer: W,
+    tool: PenTool,
+    in_range: bool,
+    down: bool,
+}
+
+/// A pen on a tablet that covers the screen, whose events are written to `W`.
+///
+/// The tablet reports positions in pixels, so that a point on the tablet is the same point on the
+/// screen.
+pub struct UinputPen<W> {
+    state: Mutex<State<W>>,
+    // the largest position on each axis
+    max: (i32, i32),
+}
+
+impl<W: Write> UinputPen<W> {
+    /// A pen on a screen of `width` by `height` pixels, whose events are written to `writer`.
+    pub fn new(writer: W, (width, height): (i32, i32)) -> Self {
+        Self {
+            state: Mutex::new(State {
+                writer,
+                tool: PenTool::default(),
+                in_range: false,
+                down: false,
+            }),
+            max: ((width - 1).max(0), (height - 1).max(0)),
+        }
+    }
+
+    /// The writer, e.g. to read back the recorded events.
+    pub fn into_inner(self) -> W {
+        self.state.into_inner().unwrap().writer
+    }
+
+    /// Move to `point`, bringing the pen into range if it is not, and touch the tip down or lift
+    /// it if `down` is given.
+    fn report(&self, point: PenPoint, down: Option<bool>) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let mut events = Vec::new();
+        if !state.in_range {
+            events.push((evdev::EV_KEY, tool_code(state.tool), 1));
+        }
+        events.extend([
+            (evdev::EV_ABS, evdev::ABS_X, point.x.clamp(0, self.max.0)),
+            (evdev::EV_ABS, evdev::ABS_Y, point.y.clamp(0, self.max.1)),
+            (
+                evdev::EV_ABS,
+                evdev::ABS_TILT_X,
+                point.tilt_x.round() as i32,
+            ),
+            (
+                evdev::EV_ABS,
+                evdev::ABS_TILT_Y,
+                point.tilt_y.round() as i32,
+            ),
+        ]);
+        let down = down.unwrap_or(state.down);
+        let pressure = (point.pressure.clamp(0.0, 1.0) * PRESSURE_MAX as f64).round() as i32;
+        if down {
+            events.push((evdev::EV_ABS, evdev::ABS_PRESSURE, pressure));
+        } else if state.down {
+            events.push((evdev::EV_ABS, evdev::ABS_PRESSURE, 0));
+        }
+        if down != state.down {
+            events.push((evdev::EV_KEY, codes::BTN_TOUCH, down as i32));
+        }
+        emit(&mut state.writer, &events)?;
+        state.in_range = true;
+        state.down = down;
+        Ok(())
+    }
+
+    // the events that lift the tip, none if it is up
+    fn lift(state: &mut State<W>) -> Vec<(u16, u16, i32)> {
+        if !std::mem::take(&mut state.down) {
+            return Vec::new();
+        }
+        vec![
+            (evdev::EV_ABS, evdev::ABS_PRESSURE, 0),
+            (evdev::EV_KEY, codes::BTN_TOUCH, 0),
+        ]
+    }
+}
+
+impl<W: Write> PenHandler for UinputPen<W> {
+    fn hover(&self, point: PenPoint) -> InputResult {
+        self.report(point, Some(false))
+    }
+
+    fn down(&self, point: PenPoint) -> InputResult {
+        self.report(point, Some(true))
+    }
+
+    fn move_to(&self, point: PenPoint) -> InputResult {
+        self.report(point, None)
+    }
+
+    fn up(&self) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let events = Self::lift(&mut state);
+        if events.is_empty() {
+            return Ok(());
+        }
+        emit(&mut state.writer, &events)
+    }
+
+    fn leave(&self) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        let mut events = Self::lift(&mut state);
+        if std::mem::take(&mut state.in_range) {
+            events.push((evdev::EV_KEY, tool_code(state.tool), 0));
+        }
+        if events.is_empty() {
+            return Ok(());
+        }
+        emit(&mut state.writer, &events)
+    }
+
+    fn press(&self, button: PenButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        emit(
+            &mut state.writer,
+            &[(evdev::EV_KEY, button_code(button), 1)],
+        )
+    }
+
+    fn release(&self, button: PenButton) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        emit(
+            &mut state.writer,
+            &[(evdev::EV_KEY, button_code(button), 0)],
+        )
+    }
+
+    fn set_tool(&self, tool: PenTool) -> InputResult {
+        let mut state = self.state.lock().unwrap();
+        if state.tool == tool {
+            return Ok(());
+        }
+        let old = std::mem::replace(&mut state.tool, tool);
+        if state.in_range {
+            // the pen is turned over: the old end leaves range before the new one enters it
+            let mut events = Self::lift(&mut state);
+            events.push((evdev::EV_KEY, tool_code(old), 0));
+            emit(&mut state.writer, &events)?;
+            emit(&mut state.writer, &[(evdev::EV_KEY, tool_code(tool), 1)])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod device {
+    use std::fs::File;
+
+    use super::*;
+    use crate::backends::uinput::device::{create, DeviceSpec};
+    use crate::backends::uinput::{Axis, Lazy};
+    use crate::core::result::InputError;
+
+    const BUS_VIRTUAL: u16 = 0x06;
+    /// The tablet is a screen, so the pen moves the cursor to where it is rather than by how much
+    /// it moved.
+    const INPUT_PROP_DIRECT: u16 = 0x01;
+
+    const KEYS: &[u16] = &[
+        codes::BTN_TOOL_PEN,
+        codes::BTN_TOOL_RUBBER,
+        codes::BTN_TOUCH,
+        codes::BTN_STYLUS,
+        codes::BTN_STYLUS2,
+    ];
+
+    impl UinputPen<File> {
+        /// Create a virtual pen tablet covering a screen of `width` by `height` pixels, it is
+        /// removed when the pen is dropped.
+        ///
+        /// This needs write access to `/dev/uinput`, usually root or membership of the `input`
+        /// group.
+        pub fn open((width, height): (i32, i32)) -> Result<Self, InputError> {
+            let axes = [
+                // about 96 dpi, so that the tablet has a plausible physical size
+                Axis {
+                    resolution: 4,
+                    ..Axis::new(evdev::ABS_X, 0, (width - 1).max(0))
+                },
+                Axis {
+                    resolution: 4,
+                    ..Axis::new(evdev::ABS_Y, 0, (height - 1).max(0))
+                },
+                Axis::new(evdev::ABS_PRESSURE, 0, PRESSURE_MAX),
+                // tilt is in degrees, about 57 per radian
+                Axis {
+                    resolution: 57,
+                    ..Axis::new(evdev::ABS_TILT_X, -90, 90)
+                },
+                Axis {
+                    resolution: 57,
+                    ..Axis::new(evdev::ABS_TILT_Y, -90, 90)
+                },
+            ];
+            let file = create(&DeviceSpec {
+                name: NAME,
+                id: libc::input_id {
+                    bustype: BUS_VIRTUAL,
+                    vendor: 0,
+                    product: 0,
+                    version: 1,
+                },
+                keys: KEYS,
+                axes: &axes,
+                properties: &[INPUT_PROP_DIRECT],
+            })?;
+            Ok(Self::new(file, (width, height)))
+        }
+    }
+
+    impl PenHandler for Lazy<UinputPen<File>> {
+        fn hover(&self, point: PenPoint) -> InputResult {
+            self.get()?.hover(point)
+        }
+
+        fn down(&self, point: PenPoint) -> InputResult {
+            self.get()?.down(point)
+        }
+
+        fn move_to(&self, point: PenPoint) -> InputResult {
+            self.get()?.move_to(point)
+        }
+
+        // a device that does not exist yet has its pen out of range, there is nothing to undo
+
+        fn up(&self) -> InputResult {
+            match self.created() {
+                Some(pen) => pen.up(),
+                None => Ok(()),
+            }
+        }
+
+        fn leave(&self) -> InputResult {
+            match self.created() {
+                Some(pen) => pen.leave(),
+                None => Ok(()),
+            }
+        }
+
+        fn press(&self, button: PenButton) -> InputResult {
+            self.get()?.press(button)
+        }
+
+        fn release(&self, button: PenButton) -> InputResult {
+            match self.created() {
+                Some(pen) => pen.release(button),
+                None => Ok(()),
+            }
+        }
+
+        fn set_tool(&self, tool: PenTool) -> InputResult {
+            self.get()?.set_tool(tool)
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::result::{InputError, InputResult};
+use crate::core::TouchHandler;
+use crate::device::gamepad::{GamepadButton, GamepadHandler, Stick, Trigger};
+use crate::device::keyboard::{KeyboardHandler, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseHandler};
+use crate::device::pen::{PenButton, PenHandler, PenPoint, PenTool};
+
+/// A primitive device action, this is the format in which input is recorded and played back.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    MousePress { button: MouseButton },
+    MouseRelease { button: MouseButton },
+    MouseMoveAbs { x: i32, y: i32 },
+    MouseMoveRel { dx: i32, dy: i32 },
+    MouseScroll { dx: i32, dy: i32 },
+    KeyPress { key: KeyboardKey },
+    KeyRelease { key: KeyboardKey },
+}
+
+impl Action {
+    /// Perform the action immediately using the given devices.
+    pub fn apply(&self, mouse: &dyn MouseHandler, keyboard: &dyn KeyboardHandler) -> InputResult {
+        let instant = std::time::Duration::ZERO;
+        match *self {
+            Action::MousePress { button } => mouse.press(button),
+            Action::MouseRelease { button } => mouse.release(button),
+            Action::MouseMoveAbs { x, y } => mouse.move_abs(x, y, instant),
+            Action::MouseMoveRel { dx, dy } => mouse.move_rel(dx, dy, instant),
+            Action::MouseScroll { dx, dy } => mouse.scroll(dx, dy),
+            Action::KeyPress { key } => keyboard.press(key),
+            Action::KeyRelease { key } => keyboard.release(key),
+        }
+    }
+}
+
+/// An action along with the time (in seconds) at which it happened, relative to the start of a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimedAction {
+    pub time: f64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// A call to one of the device handlers, with its arguments.
+///
+/// The JSON form is an object with the action's name as `"action"` and its arguments as fields,
+/// keys and buttons in their string form and durations in seconds (zero if left out), e.g.
+/// `{"action": "mouse_hold", "button": "left", "duration": 0.5}`. The pen actions that take a point
+/// have its fields inline, e.g. `{"action": "pen_down", "x": 10, "y": 20, "pressure": 0.5}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeviceAction {
+    MousePress {
+        button: MouseButton,
+    },
+    MouseRelease {
+        button: MouseButton,
+    },
+    MouseClick {
+        button: MouseButton,
+    },
+    MouseHold {
+        button: MouseButton,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    MouseDrag {
+        button: MouseButton,
+        dx: i32,
+        dy: i32,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    MouseMoveAbs {
+        x: i32,
+        y: i32,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    MouseMoveRel {
+        dx: i32,
+        dy: i32,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    MouseScroll {
+        dx: i32,
+        dy: i32,
+    },
+    KeyPress {
+        key: KeyboardKey,
+    },
+    KeyRelease {
+        key: KeyboardKey,
+    },
+    KeyTap {
+        key: KeyboardKey,
+    },
+    KeyHold {
+        key: KeyboardKey,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    KeyText {
+        text: String,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    TouchDown {
+        id: u32,
+        x: i32,
+        y: i32,
+    },
+    TouchMove {
+        id: u32,
+        x: i32,
+        y: i32,
+    },
+    TouchUp {
+        id: u32,
+    },
+    TouchTap {
+        id: u32,
+        x: i32,
+        y: i32,
+    },
+    GamepadPress {
+        button: GamepadButton,
+    },
+    GamepadRelease {
+        button: GamepadButton,
+    },
+    GamepadTap {
+        button: GamepadButton,
+    },
+    GamepadHold {
+        button: GamepadButton,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+    GamepadStick {
+        stick: Stick,
+        x: f64,
+        y: f64,
+    },
+    GamepadTrigger {
+        trigger: Trigger,
+        value: f64,
+    },
+    PenHover {
+        #[serde(flatten)]
+        point: PenPoint,
+    },
+    PenDown {
+        #[serde(flatten)]
+        point: PenPoint,
+    },
+    PenMove {
+        #[serde(flatten)]
+        point: PenPoint,
+    },
+    PenUp,
+    PenLeave,
+    PenPress {
+        button: PenButton,
+    },
+    PenRelease {
+        button: PenButton,
+    },
+    PenTool {
+        tool: PenTool,
+    },
+    PenStroke {
+        points: Vec<PenPoint>,
+        #[serde(default, with = "seconds")]
+        duration: Duration,
+    },
+}
+
+impl DeviceAction {
+    /// Perform the action with the handler of its device.
+    ///
+    /// Gamepad and pen actions are errors if there is no handler for their device.
+    pub fn apply(
+        &self,
+        mouse: &dyn MouseHandler,
+        keyboard: &dyn KeyboardHandler,
+        touch: &dyn TouchHandler,
+        gamepad: Option<&dyn GamepadHandler>,
+        pen: Option<&dyn PenHandler>,
+    ) -> InputResult {
+        let gamepad =
+            || gamepad.ok_or_else(|| InputError::error("The action needs a gamepad.".to_string()));
+        let pen = || pen.ok_or_else(|| InputError::error("The action needs a pen.".to_string()));
+        match *self {
+            DeviceAction::MousePress { button } => mouse.press(button),
+            DeviceAction::MouseRelease { button } => mouse.release(button),
+            DeviceAction::MouseClick { button } => mouse.click(button),
+            DeviceAction::MouseHold { button, duration } => mouse.hold(button, duration),
+            DeviceAction::MouseDrag {
+                button,
+                dx,
+                dy,
+                duration,
+            } => mouse.drag(button, dx, dy, duration),
+            DeviceAction::MouseMoveAbs { x, y, duration } => mouse.move_abs(x, y, duration),
+            DeviceAction::MouseMoveRel { dx, dy, duration } => mouse.move_rel(dx, dy, duration),
+            DeviceAction::MouseScroll { dx, dy } => mouse.scroll(dx, dy),
+            DeviceAction::KeyPress { key } => keyboard.press(key),
+            DeviceAction::KeyRelease { key } => keyboard.release(key),
+            DeviceAction::KeyTap { key } => keyboard.tap(key),
+            DeviceAction::KeyHold { key, duration } => keyboard.hold(key, duration),
+            DeviceAction::KeyText { ref text, duration } => keyboard.text(text, duration),
+            DeviceAction::TouchDown { id, x, y } => touch.down(id, x, y),
+            DeviceAction::TouchMove { id, x, y } => touch.move_to(id, x, y),
+            DeviceAction::TouchUp { id } => touch.up(id),
+            DeviceAction::TouchTap { id, x, y } => touch.tap(id, x, y),
+            DeviceAction::GamepadPress { button } => gamepad()?.press(button),
+            DeviceAction::GamepadRelease { button } => gamepad()?.release(button),
+            DeviceAction::GamepadTap { button } => gamepad()?.tap(button),
+            DeviceAction::GamepadHold { button, duration } => gamepad()?.hold(button, duration),
+            DeviceAction::GamepadStick { stick, x, y } => gamepad()?.move_stick(stick, x, y),
+            DeviceAction::GamepadTrigger { trigger, value } => {
+                gamepad()?.set_trigger(trigger, value)
+            }
+            DeviceAction::PenHover { point } => pen()?.hover(point),
+            DeviceAction::PenDown { point } => pen()?.down(point),
+            DeviceAction::PenMove { point } => pen()?.move_to(point),
+            DeviceAction::PenUp => pen()?.up(),
+            DeviceAction::PenLeave => pen()?.leave(),
+            DeviceAction::PenPress { button } => pen()?.press(button),
+            DeviceAction::PenRelease { button } => pen()?.release(button),
+            DeviceAction::PenTool { tool } => pen()?.set_tool(tool),
+            DeviceAction::PenStroke {
+                ref points,
+                duration,
+            } => pen()?.stroke(points, duration),
+        }
+    }
+}
+
+impl From<Action> for DeviceAction {
+    fn from(action: Action) -> Self {
+        let duration = Duration::ZERO;
+        match action {
+            Action::MousePress { button } => DeviceAction::MousePress { button },
+            Action::MouseRelease { button } => DeviceAction::MouseRelease { button },
+            Action::MouseMoveAbs { x, y } => DeviceAction::MouseMoveAbs { x, y, duration },
+            Action::MouseMoveRel { dx, dy } => DeviceAction::MouseMoveRel { dx, dy, duration },
+            Action::MouseScroll { dx, dy } => DeviceAction::MouseScroll { dx, dy },
+            Action::KeyPress { key } => DeviceAction::KeyPress { key },
+            Action::KeyRelease { key } => DeviceAction::KeyRelease { key },
+        }
+    }
+}
+
+/// Durations as a number of seconds.
+mod seconds {
+    use std::time::Duration;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let seconds = f64::deserialize(deserializer)?;
+        Duration::try_from_secs_f64(seconds).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,144 @@
+//! Cancelling actions from other threads.
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "python")]
+use {
+    crate::core::result::py_duration,
+    pyo3::{exceptions::PyTimeoutError, pyclass, pymethods, PyResult, Python},
+};
+
+use crate::core::receipt::{self, Receipt};
+use crate::core::result::{InputError, InputResult};
+
+/// Cancels the actions of the devices it is given to (see e.g.
+/// [`MouseDevice::with_cancel_token`](crate::device::mouse::MouseDevice::with_cancel_token)).
+///
+/// Clones share the same state, so a token can be cancelled from any thread. Cancelled actions
+/// release the inputs they hold and fail with [`InputError::Cancelled`].
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fails with [`InputError::Cancelled`] if the token was cancelled.
+    pub fn check(&self) -> InputResult {
+        match self.is_cancelled() {
+            true => Err(InputError::cancelled()),
+            false => Ok(()),
+        }
+    }
+}
+
+type Finished = (InputResult, Option<Receipt>);
+
+/// An action running on a background thread, which can be cancelled or waited for.
+#[cfg_attr(feature = "python", pyclass)]
+pub struct ActionHandle {
+    token: CancelToken,
+    // set by the thread when the action finishes
+    result: Arc<(Mutex<Option<Finished>>, Condvar)>,
+}
+
+impl ActionHandle {
+    /// Run `action` on a new thread, `token` should be the one that the action's device was given.
+    pub fn spawn(
+        token: CancelToken,
+        action: impl FnOnce() -> InputResult + Send + 'static,
+    ) -> Self {
+        let result = Arc::new((Mutex::new(None), Condvar::new()));
+        let finished = Arc::clone(&result);
+        std::thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(action))
+                .unwrap_or_else(|_| Err(InputError::error("The action panicked.".to_string())));
+            let (lock, condvar) = &*finished;
+            *lock.lock().unwrap() = Some((result, receipt::take_last()));
+            condvar.notify_all();
+        });
+        Self { token, result }
+    }
+
+    /// Cancel the action, this does not wait for it to stop.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.result.0.lock().unwrap().is_some()
+    }
+
+    /// The receipt of the action once it has finished.
+    pub fn receipt(&self) -> Option<Receipt> {
+        let finished = self.result.0.lock().unwrap();
+        finished.as_ref().and_then(|(_, receipt)| receipt.clone())
+    }
+
+    /// Wait for the action to finish and get its result, `None` if it is still running after `timeout`.
+    pub fn wait(&self, timeout: Option<Duration>) -> Option<InputResult> {
+        let (lock, condvar) = &*self.result;
+        let result = lock.lock().unwrap();
+        let result = match timeout {
+            Some(timeout) => {
+                condvar
+                    .wait_timeout_while(result, timeout, |result| result.is_none())
+                    .unwrap()
+                    .0
+            }
+            None => condvar
+                .wait_while(result, |result| result.is_none())
+                .unwrap(),
+        };
+        result.as_ref().map(|(result, _)| result.clone())
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ActionHandle {
+    /// Cancel the action, it raises `ActionCancelled` from `wait`.
+    #[pyo3(name = "cancel")]
+    fn py_cancel(&self) {
+        self.cancel();
+    }
+
+    fn done(&self) -> bool {
+        self.is_done()
+    }
+
+    /// Wait for the action to finish and return its receipt.
+    ///
+    /// Raises:
+    ///     ActionCancelled: If the action was cancelled.
+    ///     ActionInterrupt: If the action was interrupted.
+    ///     TimeoutError: If the action is still running after `timeout` seconds.
+    ///     ValueError: If `timeout` is negative or not a number.
+    #[pyo3(name = "wait", signature = (timeout=None))]
+    fn py_wait(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<Receipt>> {
+        let timeout = timeout.map(py_duration).transpose()?;
+        match py.allow_threads(|| self.wait(timeout)) {
+            Some(result) => receipt::py_result(result, self.receipt()),
+            None => Err(PyTimeoutError::new_err("The action is still running.")),
+        }
+    }
+
+    /// The receipt of the action, `None` until it has finished.
+    #[getter(receipt)]
+    fn py_receipt(&self) -> Option<Receipt> {
+        self.receipt()
+    }
+}
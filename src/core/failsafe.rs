@@ -0,0 +1,219 @@
+//! A kill switch that lets the user stop a session that is misbehaving.
+//!
+//! The fail-safe is tripped when the cursor is moved into one of the configured screen corners,
+//! or when a hotkey is held on a physical keyboard. Once tripped, everything held by the
+//! session's devices is released, running timed actions are interrupted and any further actions
+//! fail with [`InputError::Interrupt`] until the fail-safe is re-armed.
+//!
+//! There is no queue of pending actions to abort: actions started in the background (see
+//! [`ActionHandle`](crate::core::cancel::ActionHandle)) run straight away on their own threads,
+//! so tripping interrupts them like any other running action and they finish with
+//! [`InputError::Interrupt`] rather than being cancelled.
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use crate::core::held::ReleaseAll;
+use crate::core::result::{InputError, InputResult};
+use crate::device::keyboard::KeyboardKey;
+use crate::device::mouse::MouseHandler;
+use crate::evdev::codes;
+
+/// A corner of the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl FromStr for Corner {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "top_left" => Ok(Self::TopLeft),
+            "top_right" => Ok(Self::TopRight),
+            "bottom_left" => Ok(Self::BottomLeft),
+            "bottom_right" => Ok(Self::BottomRight),
+            _ => Err(InputError::error(format!("Invalid corner: {:?}", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FailSafeConfig {
+    /// Trip when the cursor is moved into any of these corners.
+    pub corners: Vec<Corner>,
+    /// How close to a corner (in pixels) the cursor has to be.
+    pub margin: u32,
+    /// Trip when all of these keys are held on a physical keyboard (Linux only), ignored if empty.
+    pub hotkey: Vec<KeyboardKey>,
+    /// How often the corners and the hotkey are checked in the background.
+    pub poll_interval: Duration,
+}
+
+impl Default for FailSafeConfig {
+    /// Trip when the cursor is moved into the top left corner.
+    fn default() -> Self {
+        Self {
+            corners: vec![Corner::TopLeft],
+            margin: 0,
+            hotkey: Vec::new(),
+            poll_interval: Duration::from_millis(10),
+        }
+    }
+}
+
+pub struct FailSafe {
+    config: FailSafeConfig,
+    cursor: Arc<dyn MouseHandler + Send + Sync>,
+    tripped: AtomicBool,
+    // the inputs held by the session's devices, weak so that the devices can still be dropped
+    held: Mutex<Vec<Weak<dyn ReleaseAll>>>,
+}
+
+impl FailSafe {
+    /// Start watching `cursor` (and the physical keyboards, if a hotkey is set) on a background thread.
+    ///
+    /// The thread stops when the fail-safe is dropped.
+    pub fn start(
+        config: FailSafeConfig,
+        cursor: Arc<dyn MouseHandler + Send + Sync>,
+    ) -> Result<Arc<Self>, InputError> {
+        if let Some(key) = config
+            .hotkey
+            .iter()
+            .find(|key| codes::key_to_code(**key).is_none())
+        {
+            return Err(InputError::error(format!(
+                "{:?} can not be used in the fail-safe hotkey.",
+                key
+            )));
+        }
+        if !config.hotkey.is_empty() {
+            hotkey_pressed(&config.hotkey)?;
+        }
+        let poll_interval = config.poll_interval;
+        let failsafe = Arc::new(Self {
+            config,
+            cursor,
+            tripped: AtomicBool::new(false),
+            held: Mutex::new(Vec::new()),
+        });
+        let weak = Arc::downgrade(&failsafe);
+        std::thread::spawn(move || {
+            while let Some(failsafe) = weak.upgrade() {
+                // errors (e.g. failing to read the cursor) are retried on the next poll
+                let _ = failsafe.check();
+                drop(failsafe);
+                std::thread::sleep(poll_interval);
+            }
+        });
+        Ok(failsafe)
+    }
+
+    pub fn config(&self) -> &FailSafeConfig {
+        &self.config
+    }
+
+    /// Release `held` when the fail-safe is tripped.
+    pub fn release_on_trip(&self, held: Weak<dyn ReleaseAll>) {
+        let mut all = self.held.lock().unwrap();
+        all.retain(|held| held.strong_count() > 0);
+        all.push(held);
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.load(Ordering::SeqCst)
+    }
+
+    /// Trip the fail-safe and release everything that is held, as if the user had triggered it.
+    pub fn trip(&self) {
+        if self.tripped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let held: Vec<_> = self
+            .held
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+        for held in held {
+            let _ = held.release_all();
+        }
+    }
+
+    /// Allow actions again after the fail-safe was tripped.
+    ///
+    /// The cursor should be moved out of the corner (and the hotkey released) first, otherwise
+    /// the fail-safe trips again straight away.
+    pub fn rearm(&self) {
+        self.tripped.store(false, Ordering::SeqCst);
+    }
+
+    /// Fails with [`InputError::Interrupt`] if the fail-safe is tripped, tripping it first if the user is triggering it.
+    pub fn check(&self) -> InputResult {
+        if !self.is_tripped() && self.triggered()? {
+            self.trip();
+        }
+        match self.is_tripped() {
+            true => Err(InputError::interrupt()),
+            false => Ok(()),
+        }
+    }
+
+    fn triggered(&self) -> Result<bool, InputError> {
+        if !self.config.hotkey.is_empty() && hotkey_pressed(&self.config.hotkey)? {
+            return Ok(true);
+        }
+        if self.config.corners.is_empty() {
+            return Ok(false);
+        }
+        let (x, y) = self.cursor.location()?;
+        let margin = self.config.margin as i32;
+        let near_start = |v: i32| v <= margin;
+        // only the top left corner can be checked without knowing the size of the display
+        let size = match self.config.corners.as_slice() {
+            [Corner::TopLeft] => (i32::MAX, i32::MAX),
+            _ => self.cursor.display_size()?,
+        };
+        let near_end = |v: i32, size: i32| v >= size - 1 - margin;
+        let triggered = self.config.corners.iter().any(|corner| match corner {
+            Corner::TopLeft => near_start(x) && near_start(y),
+            Corner::TopRight => near_end(x, size.0) && near_start(y),
+            Corner::BottomLeft => near_start(x) && near_end(y, size.1),
+            Corner::BottomRight => near_end(x, size.0) && near_end(y, size.1),
+        });
+        Ok(triggered)
+    }
+}
+
+/// Whether every key of `hotkey` is held on a physical keyboard, either side of a modifier counts.
+#[cfg(target_os = "linux")]
+fn hotkey_pressed(hotkey: &[KeyboardKey]) -> Result<bool, InputError> {
+    use crate::evdev::monitor::KeyMonitor;
+
+    let pressed: Vec<KeyboardKey> = KeyMonitor::global()?
+        .pressed()
+        .into_iter()
+        .filter_map(codes::key_from_code)
+        .collect();
+    Ok(hotkey.iter().all(|key| {
+        let key = match key {
+            KeyboardKey::Unicode(c) => KeyboardKey::Unicode(c.to_ascii_lowercase()),
+            key => *key,
+        };
+        pressed.contains(&key)
+    }))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn hotkey_pressed(_hotkey: &[KeyboardKey]) -> Result<bool, InputError> {
+    Err(InputError::error(
+        "The fail-safe hotkey is only supported on Linux.".to_string(),
+    ))
+}
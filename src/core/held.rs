@@ -0,0 +1,74 @@
+use std::sync::Mutex;
+
+use crate::core::result::InputResult;
+
+/// Inputs (keys, buttons, touch contacts) that a device is currently holding down.
+///
+/// Everything still held is released when the last reference is dropped, so that a
+/// script that fails part way through does not leave inputs held down system-wide.
+pub struct HeldInputs<T: Copy + PartialEq> {
+    held: Mutex<Vec<T>>,
+    release: Box<dyn Fn(T) -> InputResult + Send + Sync>,
+}
+
+impl<T: Copy + PartialEq> HeldInputs<T> {
+    /// `release` is used to release inputs that are still held, it should not call back into the tracker.
+    pub fn new(release: impl Fn(T) -> InputResult + Send + Sync + 'static) -> Self {
+        Self {
+            held: Mutex::new(Vec::new()),
+            release: Box::new(release),
+        }
+    }
+
+    /// Record that an input was pressed.
+    pub fn insert(&self, input: T) {
+        let mut held = self.held.lock().unwrap();
+        if !held.contains(&input) {
+            held.push(input);
+        }
+    }
+
+    /// Record that an input was released, returning whether it was held.
+    pub fn remove(&self, input: T) -> bool {
+        let mut held = self.held.lock().unwrap();
+        let len = held.len();
+        held.retain(|held| *held != input);
+        held.len() != len
+    }
+
+    /// The inputs that are currently held, in the order that they were pressed.
+    pub fn held(&self) -> Vec<T> {
+        self.held.lock().unwrap().clone()
+    }
+
+    /// Release everything that is held, in reverse order of pressing.
+    ///
+    /// Every input is released even if some fail, the first error is returned.
+    pub fn release_all(&self) -> InputResult {
+        let held = std::mem::take(&mut *self.held.lock().unwrap());
+        let mut result = Ok(());
+        for input in held.into_iter().rev() {
+            if let Err(e) = (self.release)(input) {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+/// Anything that can release all of the inputs it holds, used to release inputs without knowing their type.
+pub trait ReleaseAll: Send + Sync {
+    fn release_all(&self) -> InputResult;
+}
+
+impl<T: Copy + PartialEq + Send> ReleaseAll for HeldInputs<T> {
+    fn release_all(&self) -> InputResult {
+        HeldInputs::release_all(self)
+    }
+}
+
+impl<T: Copy + PartialEq> Drop for HeldInputs<T> {
+    fn drop(&mut self) {
+        let _ = self.release_all();
+    }
+}
@@ -0,0 +1,303 @@
+//! Humanized input, adding seeded random variation to device actions.
+//!
+//! When a [`HumanizeProfile`] is enabled on a session, clicks land a few pixels away from the
+//! cursor, keys and buttons are held for a short random time, timed moves sometimes overshoot
+//! their target and correct, and text is typed with an uneven rhythm. All randomness comes from
+//! a single seeded generator, so a run can be reproduced by using the same seed.
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::core::interrupt::Watch;
+use crate::core::result::{InputError, InputResult};
+use crate::core::timed;
+use crate::device::keyboard::{KeyboardHandler, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseHandler};
+
+/// A small seedable random number generator (SplitMix64), stable across versions so that
+/// seeded runs stay reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A generator seeded from the system clock.
+    pub fn from_time() -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Self::new(now.as_nanos() as u64)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A number in `[low, high)`.
+    pub fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.unit()
+    }
+
+    /// An index in `0..len`, `len` must not be zero.
+    pub fn index(&mut self, len: usize) -> usize {
+        ((self.unit() * len as f64) as usize).min(len - 1)
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+
+    /// A normally distributed number.
+    pub fn normal(&mut self, mean: f64, deviation: f64) -> f64 {
+        // Box-Muller, 1 - unit() avoids taking the log of zero
+        let (u, v) = (1.0 - self.unit(), self.unit());
+        mean + deviation * (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HumanizeProfile {
+    /// Clicks land up to this many pixels away from the cursor.
+    pub click_jitter: u32,
+    /// How long clicked buttons and tapped keys are held, a random time in this range.
+    pub press_time: (Duration, Duration),
+    /// The probability that a timed move overshoots its target and corrects.
+    pub overshoot_chance: f64,
+    /// How far an overshoot goes past the target, as a fraction of the distance moved.
+    pub overshoot: f64,
+    /// The average time between typed characters, when no duration is given.
+    pub typing_interval: Duration,
+    /// The standard deviation of the time between typed characters, as a fraction of the average.
+    pub typing_variation: f64,
+    /// Seed of the random number generator, taken from the clock if not given.
+    pub seed: Option<u64>,
+}
+
+impl Default for HumanizeProfile {
+    fn default() -> Self {
+        Self {
+            click_jitter: 3,
+            press_time: (Duration::from_millis(40), Duration::from_millis(120)),
+            overshoot_chance: 0.3,
+            overshoot: 0.05,
+            typing_interval: Duration::from_millis(80),
+            typing_variation: 0.4,
+            seed: None,
+        }
+    }
+}
+
+impl HumanizeProfile {
+    /// Check that the fractions and probabilities are finite and not negative, the generator
+    /// would otherwise produce times that are not durations.
+    pub fn validate(&self) -> InputResult {
+        let fields = [
+            ("overshoot_chance", self.overshoot_chance),
+            ("overshoot", self.overshoot),
+            ("typing_variation", self.typing_variation),
+        ];
+        for (name, value) in fields {
+            if !value.is_finite() || value < 0.0 {
+                return Err(InputError::error(format!(
+                    "{} must be a finite number that is not negative, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A [`HumanizeProfile`] together with the state of its random number generator.
+pub struct Humanizer {
+    profile: HumanizeProfile,
+    rng: Mutex<Rng>,
+}
+
+impl Humanizer {
+    pub fn new(profile: HumanizeProfile) -> Self {
+        let rng = match profile.seed {
+            Some(seed) => Rng::new(seed),
+            None => Rng::from_time(),
+        };
+        Self {
+            profile,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn profile(&self) -> &HumanizeProfile {
+        &self.profile
+    }
+
+    /// Draw from the generator, actions that draw from it concurrently make a run non-reproducible.
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut Rng) -> T) -> T {
+        f(&mut self.rng.lock().unwrap())
+    }
+
+    fn press_time(&self) -> Duration {
+        let (low, high) = self.profile.press_time;
+        let seconds = self.with_rng(|rng| rng.range(low.as_secs_f64(), high.as_secs_f64()));
+        Duration::from_secs_f64(seconds.max(0.0))
+    }
+
+    /// A random offset within the click jitter radius.
+    fn click_offset(&self) -> (i32, i32) {
+        let radius = self.profile.click_jitter as f64;
+        self.with_rng(|rng| {
+            // uniform over the disk
+            let r = radius * rng.unit().sqrt();
+            let angle = rng.range(0.0, std::f64::consts::TAU);
+            (
+                (r * angle.cos()).round() as i32,
+                (r * angle.sin()).round() as i32,
+            )
+        })
+    }
+
+    /// How far past the end of a move of `(dx, dy)` to go, `None` if the move should not overshoot.
+    fn overshoot(&self, dx: i32, dy: i32) -> Option<(i32, i32)> {
+        let profile = &self.profile;
+        self.with_rng(|rng| {
+            if !rng.chance(profile.overshoot_chance) {
+                return None;
+            }
+            let amount = profile.overshoot * rng.range(0.5, 1.5);
+            let offset = (
+                (dx as f64 * amount).round() as i32,
+                (dy as f64 * amount).round() as i32,
+            );
+            (offset != (0, 0)).then_some(offset)
+        })
+    }
+
+    /// The pause after each of `count` typed characters. If `duration` is given the pauses are
+    /// scaled to add up to it.
+    pub fn typing_gaps(&self, count: usize, duration: Option<Duration>) -> Vec<Duration> {
+        let mean = self.profile.typing_interval.as_secs_f64();
+        let deviation = mean * self.profile.typing_variation;
+        let mut gaps: Vec<f64> = self.with_rng(|rng| {
+            (0..count)
+                .map(|_| rng.normal(mean, deviation).max(mean * 0.1))
+                .collect()
+        });
+        if let Some(duration) = duration {
+            let total: f64 = gaps.iter().sum();
+            let scale = duration.as_secs_f64() / total.max(f64::EPSILON);
+            gaps.iter_mut().for_each(|gap| *gap *= scale);
+        }
+        gaps.into_iter().map(Duration::from_secs_f64).collect()
+    }
+
+    /// When each of `count` characters should be typed, relative to the start. If `duration` is
+    /// given the times are scaled to fit it, the last character is typed before it is over.
+    pub(crate) fn typing_times(&self, count: usize, duration: Option<Duration>) -> Vec<Duration> {
+        // the first character is typed straight away, the last gap is kept for the end
+        let mut time = Duration::ZERO;
+        self.typing_gaps(count, duration)
+            .into_iter()
+            .map(|gap| {
+                let at = time;
+                time += gap;
+                at
+            })
+            .collect()
+    }
+}
+
+/// Click at a random offset from the cursor, holding the button for a random time.
+pub fn click<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    humanizer: &Humanizer,
+    button: MouseButton,
+    watch: &mut Watch,
+) -> InputResult {
+    let (ox, oy) = humanizer.click_offset();
+    if (ox, oy) != (0, 0) {
+        timed::move_rel(mouse, ox, oy, Duration::ZERO, watch)?;
+    }
+    timed::hold_button(mouse, button, humanizer.press_time(), watch)
+}
+
+/// Tap a key, holding it for a random time.
+pub fn tap<K: KeyboardHandler + ?Sized>(
+    keyboard: &K,
+    humanizer: &Humanizer,
+    key: KeyboardKey,
+    watch: &Watch,
+) -> InputResult {
+    timed::hold_key(keyboard, key, humanizer.press_time(), watch)
+}
+
+pub fn move_abs<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    humanizer: &Humanizer,
+    x: i32,
+    y: i32,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    if duration.is_zero() {
+        return timed::move_abs(mouse, x, y, duration, watch);
+    }
+    let (sx, sy) = mouse.location()?;
+    match humanizer.overshoot(x - sx, y - sy) {
+        Some((ox, oy)) => {
+            timed::move_abs(mouse, x + ox, y + oy, duration.mul_f64(0.85), watch)?;
+            timed::move_abs(mouse, x, y, duration.mul_f64(0.15), watch)
+        }
+        None => timed::move_abs(mouse, x, y, duration, watch),
+    }
+}
+
+pub fn move_rel<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    humanizer: &Humanizer,
+    dx: i32,
+    dy: i32,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    if duration.is_zero() {
+        return timed::move_rel(mouse, dx, dy, duration, watch);
+    }
+    match humanizer.overshoot(dx, dy) {
+        Some((ox, oy)) => {
+            timed::move_rel(mouse, dx + ox, dy + oy, duration.mul_f64(0.85), watch)?;
+            timed::move_rel(mouse, -ox, -oy, duration.mul_f64(0.15), watch)
+        }
+        None => timed::move_rel(mouse, dx, dy, duration, watch),
+    }
+}
+
+/// Type text one character at a time with an uneven rhythm, over `duration` if it is not zero.
+pub fn text<K: KeyboardHandler + ?Sized>(
+    keyboard: &K,
+    humanizer: &Humanizer,
+    text: &str,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    let count = text.chars().count();
+    let times = humanizer.typing_times(count, (!duration.is_zero()).then_some(duration));
+    let start_time = Instant::now();
+    for (c, time) in text.chars().zip(times) {
+        watch.sleep_until(start_time + time)?;
+        keyboard.text(c.encode_utf8(&mut [0; 4]), Duration::ZERO)?;
+    }
+    watch.sleep_until(start_time + duration)
+}
@@ -0,0 +1,236 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "python")]
+use {crate::core::result::py_duration, pyo3::prelude::*};
+
+use crate::core::result::{InputError, InputResult};
+use crate::core::scheduler;
+use crate::device::mouse::MouseHandler;
+
+/// A condition that interrupts a timed action (e.g. a hold or a timed move) when it is met.
+#[derive(Clone)]
+pub enum InterruptCondition {
+    /// The cursor was moved by more than `threshold` pixels (in either axis) from where the action left it.
+    CursorMoved { threshold: u32 },
+    /// A key was pressed on a physical keyboard (Linux only, requires read access to evdev devices).
+    KeyPressed,
+    /// A custom predicate, the action is interrupted when it returns true.
+    Custom(Arc<dyn Fn() -> bool + Send + Sync>),
+}
+
+impl Debug for InterruptCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CursorMoved { threshold } => write!(f, "CursorMoved({})", threshold),
+            Self::KeyPressed => write!(f, "KeyPressed"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Decides when a timed action should be interrupted, the action is interrupted as soon as any of
+/// the conditions are met. Interrupted actions release any inputs they hold and fail with
+/// [`InputError::Interrupt`].
+#[cfg_attr(feature = "python", pyclass)]
+#[derive(Debug, Clone)]
+pub struct InterruptPolicy {
+    pub conditions: Vec<InterruptCondition>,
+    /// How often the conditions are checked.
+    pub poll_interval: Duration,
+}
+
+impl Default for InterruptPolicy {
+    /// Interrupt when the user moves the cursor.
+    fn default() -> Self {
+        Self::none().with(InterruptCondition::CursorMoved { threshold: 0 })
+    }
+}
+
+impl InterruptPolicy {
+    /// A policy that never interrupts.
+    pub fn none() -> Self {
+        Self {
+            conditions: Vec::new(),
+            poll_interval: Duration::from_millis(5),
+        }
+    }
+
+    pub fn with(mut self, condition: InterruptCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Start watching for interruptions, this takes a snapshot of the state that the conditions are checked against.
+    ///
+    /// `cursor` is used to check [`InterruptCondition::CursorMoved`], the condition is ignored if it is not given.
+    pub fn watch<'a>(
+        &'a self,
+        cursor: Option<&'a dyn MouseHandler>,
+    ) -> Result<Watch<'a>, InputError> {
+        let mut watch = Watch {
+            policy: self,
+            cursor: None,
+            position: None,
+            key_presses: None,
+            guards: Vec::new(),
+        };
+        for condition in &self.conditions {
+            match condition {
+                InterruptCondition::CursorMoved { .. } if watch.cursor.is_none() => {
+                    if let Some(cursor) = cursor {
+                        watch.position = Some(cursor.location()?);
+                        watch.cursor = Some(cursor);
+                    }
+                }
+                InterruptCondition::KeyPressed => watch.key_presses = Some(key_presses()?),
+                _ => {}
+            }
+        }
+        Ok(watch)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl InterruptPolicy {
+    /// With no arguments the policy never interrupts. The default policy of a session interrupts
+    /// when the cursor is moved at all.
+    ///
+    /// Args:
+    ///     cursor_moved: Interrupt when the cursor is moved by more than this many pixels.
+    ///     key_pressed: Interrupt when a key is pressed on a physical keyboard (Linux only).
+    ///     predicate: Interrupt when calling this returns true.
+    ///     poll_interval: How often the conditions are checked, in seconds.
+    ///
+    /// Raises:
+    ///     ValueError: If `poll_interval` is negative or not a number.
+    #[new]
+    #[pyo3(signature = (cursor_moved=None, key_pressed=false, predicate=None, poll_interval=0.005))]
+    fn py_new(
+        cursor_moved: Option<u32>,
+        key_pressed: bool,
+        predicate: Option<PyObject>,
+        poll_interval: f64,
+    ) -> PyResult<Self> {
+        let mut policy = Self::none();
+        policy.poll_interval = py_duration(poll_interval)?;
+        if let Some(threshold) = cursor_moved {
+            policy = policy.with(InterruptCondition::CursorMoved { threshold });
+        }
+        if key_pressed {
+            policy = policy.with(InterruptCondition::KeyPressed);
+        }
+        if let Some(predicate) = predicate {
+            // an exception raised by the predicate is treated as an interrupt
+            policy = policy.with(InterruptCondition::Custom(Arc::new(move || {
+                Python::with_gil(|py| {
+                    predicate
+                        .call0(py)
+                        .and_then(|result| result.is_truthy(py))
+                        .unwrap_or(true)
+                })
+            })));
+        }
+        Ok(policy)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn key_presses() -> Result<u64, InputError> {
+    Ok(crate::evdev::monitor::KeyMonitor::global()?.presses())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn key_presses() -> Result<u64, InputError> {
+    Err(InputError::error(
+        "Interrupting on key presses is only supported on Linux.".to_string(),
+    ))
+}
+
+/// Checks an [`InterruptPolicy`] over the course of a single action.
+pub struct Watch<'a> {
+    policy: &'a InterruptPolicy,
+    cursor: Option<&'a dyn MouseHandler>,
+    // where the action expects the cursor to be
+    position: Option<(i32, i32)>,
+    key_presses: Option<u64>,
+    guards: Vec<Box<dyn Fn() -> InputResult + 'a>>,
+}
+
+impl<'a> Watch<'a> {
+    /// Also stop the action when `guard` fails, it is checked before the policy's conditions and
+    /// its error is returned as is (e.g. for the session's fail-safe).
+    pub fn guard(mut self, guard: impl Fn() -> InputResult + 'a) -> Self {
+        self.guards.push(Box::new(guard));
+        self
+    }
+
+    /// Call after the action itself moves the cursor, so that the movement is not mistaken for the user's.
+    pub fn moved(&mut self) -> InputResult {
+        if let Some(cursor) = self.cursor {
+            self.position = Some(cursor.location()?);
+        }
+        Ok(())
+    }
+
+    /// Fails with [`InputError::Interrupt`] if any of the conditions are met, or with the error of a failed guard.
+    pub fn check(&self) -> InputResult {
+        for guard in &self.guards {
+            guard()?;
+        }
+        for condition in &self.policy.conditions {
+            let interrupted = match condition {
+                InterruptCondition::CursorMoved { threshold } => {
+                    match (self.cursor, self.position) {
+                        (Some(cursor), Some((x, y))) => {
+                            let (nx, ny) = cursor.location()?;
+                            x.abs_diff(nx).max(y.abs_diff(ny)) > *threshold
+                        }
+                        _ => false,
+                    }
+                }
+                InterruptCondition::KeyPressed => self
+                    .key_presses
+                    .is_some_and(|start| key_presses().is_ok_and(|now| now > start)),
+                InterruptCondition::Custom(predicate) => predicate(),
+            };
+            if interrupted {
+                return Err(InputError::interrupt());
+            }
+        }
+        Ok(())
+    }
+
+    /// Sleep until `deadline`, checking the conditions periodically. The deadline itself is
+    /// waited for with the [`scheduler`].
+    pub fn sleep_until(&self, deadline: Instant) -> InputResult {
+        self.check()?;
+        if !(self.policy.conditions.is_empty() && self.guards.is_empty()) {
+            // polling stops short of the tolerance so that the scheduler spins for the rest
+            let tolerance = scheduler::tolerance();
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+                if remaining <= tolerance {
+                    break;
+                }
+                std::thread::sleep(Duration::min(
+                    remaining - tolerance,
+                    self.policy.poll_interval,
+                ));
+                self.check()?;
+            }
+        }
+        scheduler::wait_until(deadline);
+        self.check()
+    }
+
+    /// Sleep for `duration`, checking the conditions periodically.
+    pub fn sleep(&self, duration: Duration) -> InputResult {
+        self.sleep_until(Instant::now() + duration)
+    }
+}
@@ -1,5 +1,18 @@
+pub mod action;
+pub mod cancel;
+pub mod failsafe;
+pub mod held;
+pub mod humanize;
+pub mod interrupt;
+pub mod receipt;
 pub mod result;
+pub mod scheduler;
+pub mod timed;
+pub mod trace;
+pub mod typos;
 
+pub use crate::device::gamepad::GamepadHandler;
 pub use crate::device::keyboard::KeyboardHandler;
 pub use crate::device::mouse::MouseHandler;
+pub use crate::device::pen::PenHandler;
 pub use crate::device::touch::TouchHandler;
@@ -0,0 +1,231 @@
+//! Receipts describing what an action actually did.
+//!
+//! Every device call is measured, and its receipt can be taken with [`take_last`] on the
+//! thread that made the call, after the call returns (whether it succeeded or not). Primitives
+//! are counted by [`Traced`](crate::backends::traced::Traced) backends.
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::core::result::{InputError, InputResult};
+
+/// What an action actually did: when it started and ended, how many primitives it emitted and
+/// where the cursor ended up.
+#[cfg_attr(feature = "python", pyclass(frozen))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Receipt {
+    /// When the action was called.
+    pub requested_start: Instant,
+    /// When the action was supposed to end, the call time plus the requested duration.
+    pub requested_end: Instant,
+    /// When the first primitive was emitted, `None` if there were none.
+    pub actual_start: Option<Instant>,
+    /// When the action returned.
+    pub actual_end: Instant,
+    /// The number of primitive events emitted by the backend.
+    pub primitives: u32,
+    /// The cursor position after the action, if the device knows it.
+    pub cursor: Option<(i32, i32)>,
+    /// Whether the action was stopped before it finished (interrupted, cancelled or by the fail-safe).
+    pub truncated: bool,
+    /// The most any of the action's scheduled events was late by, `None` if it had no deadlines.
+    pub timing_error: Option<Duration>,
+}
+
+struct Measuring {
+    primitives: u32,
+    first: Option<Instant>,
+    timing_error: Option<Duration>,
+}
+
+thread_local! {
+    // the action being measured on this thread
+    static MEASURING: RefCell<Option<Measuring>> = const { RefCell::new(None) };
+    static LAST: RefCell<Option<Receipt>> = const { RefCell::new(None) };
+}
+
+/// Measure `action`, which should take `duration`, and keep its receipt for [`take_last`].
+///
+/// Actions called by an action that is already being measured are counted as part of it.
+pub fn measure(
+    duration: Duration,
+    cursor: impl FnOnce() -> Option<(i32, i32)>,
+    action: impl FnOnce() -> InputResult,
+) -> InputResult {
+    let nested = MEASURING.with_borrow(|measuring| measuring.is_some());
+    if nested {
+        return action();
+    }
+    let requested_start = Instant::now();
+    MEASURING.set(Some(Measuring {
+        primitives: 0,
+        first: None,
+        timing_error: None,
+    }));
+    let result = action();
+    let actual_end = Instant::now();
+    let measuring = MEASURING.take().unwrap();
+    let receipt = Receipt {
+        requested_start,
+        requested_end: requested_start + duration,
+        actual_start: measuring.first,
+        actual_end,
+        primitives: measuring.primitives,
+        cursor: cursor(),
+        truncated: matches!(result, Err(InputError::Interrupt | InputError::Cancelled)),
+        timing_error: measuring.timing_error,
+    };
+    LAST.set(Some(receipt));
+    result
+}
+
+/// Count primitives towards the action being measured on this thread, if any.
+pub fn record_primitives(count: u32) {
+    MEASURING.with_borrow_mut(|measuring| {
+        if let Some(measuring) = measuring {
+            measuring.primitives += count;
+            measuring.first.get_or_insert_with(Instant::now);
+        }
+    });
+}
+
+/// Record how late a deadline of the action being measured on this thread was met, if any.
+pub fn record_timing(late: Duration) {
+    MEASURING.with_borrow_mut(|measuring| {
+        if let Some(measuring) = measuring {
+            let error = measuring.timing_error.get_or_insert(late);
+            *error = late.max(*error);
+        }
+    });
+}
+
+/// Take the receipt of the last action measured on this thread.
+pub fn take_last() -> Option<Receipt> {
+    LAST.take()
+}
+
+/// Convert an instant to seconds since the Unix epoch, comparable with python's `time.time()`.
+pub fn timestamp(instant: Instant) -> f64 {
+    // anchored once so that timestamps stay monotonic even if the system clock changes
+    static ANCHOR: OnceLock<(Instant, f64)> = OnceLock::new();
+    let (anchor, anchor_time) = ANCHOR.get_or_init(|| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        (Instant::now(), now.as_secs_f64())
+    });
+    match instant.checked_duration_since(*anchor) {
+        Some(after) => anchor_time + after.as_secs_f64(),
+        None => anchor_time - anchor.duration_since(instant).as_secs_f64(),
+    }
+}
+
+/// Run a device call from python, returning its receipt or raising its error with the receipt
+/// attached as the `receipt` attribute.
+#[cfg(feature = "python")]
+pub fn py_measured(action: impl FnOnce() -> InputResult) -> PyResult<Option<Receipt>> {
+    LAST.set(None);
+    let result = action();
+    py_result(result, take_last())
+}
+
+/// Return `receipt` for a successful action, otherwise raise its error with the receipt attached.
+#[cfg(feature = "python")]
+pub fn py_result(result: InputResult, receipt: Option<Receipt>) -> PyResult<Option<Receipt>> {
+    match result {
+        Ok(()) => Ok(receipt),
+        Err(e) => {
+            let err = e.into_py();
+            if let Some(receipt) = receipt {
+                Python::with_gil(|py| {
+                    let _ = err.value_bound(py).setattr("receipt", receipt.into_py(py));
+                });
+            }
+            Err(err)
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Receipt {
+    /// When the action was called, in seconds since the epoch (like `time.time()`).
+    #[getter(requested_start)]
+    fn py_requested_start(&self) -> f64 {
+        timestamp(self.requested_start)
+    }
+
+    /// When the action was supposed to end, the call time plus the requested duration.
+    #[getter(requested_end)]
+    fn py_requested_end(&self) -> f64 {
+        timestamp(self.requested_end)
+    }
+
+    /// When the first primitive event was emitted, `None` if there were none.
+    #[getter(actual_start)]
+    fn py_actual_start(&self) -> Option<f64> {
+        self.actual_start.map(timestamp)
+    }
+
+    /// When the action returned.
+    #[getter(actual_end)]
+    fn py_actual_end(&self) -> f64 {
+        timestamp(self.actual_end)
+    }
+
+    /// The number of primitive events emitted by the backend.
+    #[getter(primitives)]
+    fn py_primitives(&self) -> u32 {
+        self.primitives
+    }
+
+    /// The cursor position after the action, if known.
+    #[getter(cursor)]
+    fn py_cursor(&self) -> Option<(i32, i32)> {
+        self.cursor
+    }
+
+    /// Whether the action was stopped before it finished (interrupted, cancelled or by the fail-safe).
+    #[getter(truncated)]
+    fn py_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// The most any of the action's scheduled events was late by in seconds, `None` if the
+    /// action had no deadlines.
+    #[getter(timing_error)]
+    fn py_timing_error(&self) -> Option<f64> {
+        self.timing_error.map(|error| error.as_secs_f64())
+    }
+
+    fn __repr__(&self) -> String {
+        let actual_start = match self.actual_start {
+            Some(start) => format!(
+                "{:.6}",
+                start.duration_since(self.requested_start).as_secs_f64()
+            ),
+            None => "None".to_string(),
+        };
+        let cursor = match self.cursor {
+            Some((x, y)) => format!("({}, {})", x, y),
+            None => "None".to_string(),
+        };
+        let timing_error = match self.timing_error {
+            Some(error) => format!("{:.6}s", error.as_secs_f64()),
+            None => "None".to_string(),
+        };
+        format!(
+            "Receipt(requested={:.6}s, actual_start=+{}s, actual_end=+{:.6}s, primitives={}, cursor={}, truncated={}, timing_error={})",
+            (self.requested_end - self.requested_start).as_secs_f64(),
+            actual_start,
+            (self.actual_end - self.requested_start).as_secs_f64(),
+            self.primitives,
+            cursor,
+            if self.truncated { "True" } else { "False" },
+            timing_error,
+        )
+    }
+}
@@ -1,8 +1,11 @@
 use std::fmt::{Debug, Display};
+#[cfg(feature = "python")]
+use std::time::Duration;
 
+#[cfg(feature = "python")]
 use pyo3::{
     exceptions::{PyException, PyValueError},
-    pyclass, pymethods, PyErr,
+    pyclass, pymethods, PyErr, PyResult,
 };
 
 pub type InputResult = Result<(), InputError>;
@@ -21,21 +24,27 @@ impl Display for Interrupt {
     }
 }
 
+/// Raised when a timed action is interrupted according to its `InterruptPolicy`, or by the fail-safe.
+#[cfg(feature = "python")]
 #[pyclass(extends=PyException)]
-struct ActionInterrupt {}
+#[derive(Default)]
+pub struct ActionInterrupt {}
 
+#[cfg(feature = "python")]
 impl Debug for ActionInterrupt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Action interrupted by the user.")
     }
 }
 
+#[cfg(feature = "python")]
 impl Display for ActionInterrupt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Action interrupted by the user.")
     }
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl ActionInterrupt {
     #[new]
@@ -48,8 +57,30 @@ impl ActionInterrupt {
     }
 }
 
+/// Raised when an action is cancelled from code, e.g. with `ActionHandle.cancel()`.
+#[cfg(feature = "python")]
+#[pyclass(extends=PyException)]
+#[derive(Default)]
+pub struct ActionCancelled {}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl ActionCancelled {
+    #[new]
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn __str__(&self) -> &str {
+        "Action cancelled."
+    }
+}
+
+#[derive(Clone)]
 pub enum InputError {
     Interrupt,
+    /// The action was cancelled through a [`CancelToken`](crate::core::cancel::CancelToken).
+    Cancelled,
     Error(String),
 }
 
@@ -62,18 +93,39 @@ impl InputError {
         InputError::Interrupt
     }
 
+    pub fn cancelled() -> Self {
+        InputError::Cancelled
+    }
+
+    #[cfg(feature = "python")]
     pub fn into_py(&self) -> PyErr {
         match self {
             InputError::Interrupt => PyErr::new::<ActionInterrupt, _>(()),
+            InputError::Cancelled => PyErr::new::<ActionCancelled, _>(()),
             InputError::Error(error) => PyValueError::new_err(error.to_string()),
         }
     }
 }
 
+/// Convert a number of seconds passed from python, raising `ValueError` if it is negative or not a
+/// number.
+#[cfg(feature = "python")]
+pub fn py_duration(seconds: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| PyValueError::new_err(format!("Invalid duration: {}", seconds)))
+}
+
+impl From<std::io::Error> for InputError {
+    fn from(err: std::io::Error) -> Self {
+        InputError::error(err.to_string())
+    }
+}
+
 impl Debug for InputError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             InputError::Interrupt => write!(f, "Action interrupted by the user."),
+            InputError::Cancelled => write!(f, "Action cancelled."),
             InputError::Error(error) => write!(f, "{}", error),
         }
     }
@@ -83,6 +135,7 @@ impl Display for InputError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             InputError::Interrupt => write!(f, "Action interrupted by the user."),
+            InputError::Cancelled => write!(f, "Action cancelled."),
             InputError::Error(error) => write!(f, "{}", error),
         }
     }
@@ -0,0 +1,81 @@
+//! Precise waiting for the deadlines of timed actions.
+//!
+//! `std::thread::sleep` typically overshoots by a millisecond or more, so timed actions plan
+//! their events against absolute deadlines and wait for them with [`wait_until`]: it sleeps
+//! until the deadline is within the [tolerance](set_tolerance), then spins for the rest. How
+//! late each deadline was met is recorded in the action's [`Receipt`](crate::core::receipt::Receipt).
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::core::receipt;
+
+/// The default tolerance, enough to absorb the usual oversleep on Linux.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_millis(2);
+
+// the tolerance in nanoseconds
+static TOLERANCE: AtomicU64 = AtomicU64::new(DEFAULT_TOLERANCE.as_nanos() as u64);
+
+/// How long before a deadline waiting switches from sleeping to spinning.
+pub fn tolerance() -> Duration {
+    Duration::from_nanos(TOLERANCE.load(Ordering::Relaxed))
+}
+
+/// Set how long before a deadline waiting switches from sleeping to spinning, for all timed
+/// actions. A larger tolerance is more precise but keeps a core busy for longer, zero only sleeps.
+pub fn set_tolerance(tolerance: Duration) {
+    let nanos = u64::try_from(tolerance.as_nanos()).unwrap_or(u64::MAX);
+    TOLERANCE.store(nanos, Ordering::Relaxed);
+}
+
+/// Wait until `deadline`, returning how late it was met.
+pub fn wait_until(deadline: Instant) -> Duration {
+    let tolerance = tolerance();
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() {
+            break;
+        }
+        if remaining > tolerance {
+            std::thread::sleep(remaining - tolerance);
+        } else {
+            std::hint::spin_loop();
+        }
+    }
+    let late = Instant::now().saturating_duration_since(deadline);
+    receipt::record_timing(late);
+    late
+}
+
+#[cfg(feature = "python")]
+pub use self::python::{set_timing_tolerance, timing_tolerance};
+
+#[cfg(feature = "python")]
+mod python {
+    use std::time::Duration;
+
+    use pyo3::exceptions::PyValueError;
+    use pyo3::prelude::*;
+
+    /// Set how long before each deadline of a timed action waiting switches from sleeping to
+    /// spinning. Sleeping alone usually overshoots by a millisecond or more.
+    ///
+    /// Args:
+    ///     seconds: The tolerance, larger is more precise but keeps a core busy for longer and
+    ///         0 only sleeps. Defaults to 0.002.
+    ///
+    /// Raises:
+    ///     ValueError: If `seconds` is negative or not finite.
+    #[pyfunction]
+    pub fn set_timing_tolerance(seconds: f64) -> PyResult<()> {
+        let tolerance = Duration::try_from_secs_f64(seconds)
+            .map_err(|_| PyValueError::new_err(format!("Invalid tolerance: {}", seconds)))?;
+        super::set_tolerance(tolerance);
+        Ok(())
+    }
+
+    /// How long before each deadline of a timed action waiting switches from sleeping to
+    /// spinning, in seconds.
+    #[pyfunction]
+    pub fn timing_tolerance() -> f64 {
+        super::tolerance().as_secs_f64()
+    }
+}
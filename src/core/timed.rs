@@ -0,0 +1,256 @@
+//! Timed actions built from primitive actions, shared by all backends and devices.
+//!
+//! Every action waits through a [`Watch`], so it is interrupted according to the watch's
+//! [`InterruptPolicy`](crate::core::interrupt::InterruptPolicy). Inputs pressed by an action
+//! are always released, even when it is interrupted.
+use std::time::{Duration, Instant};
+
+use crate::core::interrupt::Watch;
+use crate::core::result::InputResult;
+use crate::device::gamepad::{GamepadButton, GamepadHandler, Stick};
+use crate::device::keyboard::{KeyboardHandler, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseHandler, PathPoint};
+use crate::device::pen::{PenHandler, PenPoint};
+
+/// Interval between the intermediate steps of timed movements.
+pub const STEP_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Call `step` with the fraction of `duration` that has elapsed, at regular intervals until the duration is over.
+fn steps(
+    duration: Duration,
+    watch: &mut Watch,
+    mut step: impl FnMut(f64) -> InputResult,
+) -> InputResult {
+    let count = (duration.as_secs_f64() / STEP_INTERVAL.as_secs_f64())
+        .ceil()
+        .max(1.0) as u32;
+    let start_time = Instant::now();
+    for i in 1..=count {
+        watch.sleep_until(start_time + duration.mul_f64(i as f64 / count as f64))?;
+        step(i as f64 / count as f64)?;
+        watch.moved()?;
+    }
+    Ok(())
+}
+
+pub fn hold_button<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    button: MouseButton,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    mouse.press(button)?;
+    let result = watch.sleep(duration);
+    // release even if interrupted, the interrupt takes priority over a failed release
+    let released = mouse.release(button);
+    result.and(released)
+}
+
+pub fn move_abs<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    x: i32,
+    y: i32,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    if duration.is_zero() {
+        mouse.move_abs(x, y, Duration::ZERO)?;
+        return watch.moved();
+    }
+    let (sx, sy) = mouse.location()?;
+    steps(duration, watch, |t| {
+        let nx = sx + ((x - sx) as f64 * t).round() as i32;
+        let ny = sy + ((y - sy) as f64 * t).round() as i32;
+        mouse.move_abs(nx, ny, Duration::ZERO)
+    })
+}
+
+pub fn move_rel<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    dx: i32,
+    dy: i32,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    if duration.is_zero() {
+        mouse.move_rel(dx, dy, Duration::ZERO)?;
+        return watch.moved();
+    }
+    // the total movement so far, steps are rounded so that the total is exact
+    let mut moved = (0, 0);
+    steps(duration, watch, |t| {
+        let target = (
+            (dx as f64 * t).round() as i32,
+            (dy as f64 * t).round() as i32,
+        );
+        let (sx, sy) = (target.0 - moved.0, target.1 - moved.1);
+        moved = target;
+        match (sx, sy) {
+            (0, 0) => Ok(()),
+            _ => mouse.move_rel(sx, sy, Duration::ZERO),
+        }
+    })
+}
+
+pub fn drag<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    button: MouseButton,
+    dx: i32,
+    dy: i32,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    mouse.press(button)?;
+    let result = move_rel(mouse, dx, dy, duration, watch);
+    let released = mouse.release(button);
+    result.and(released)
+}
+
+/// Move through `path`, reaching each point at its time from the start. `button` is pressed at the
+/// first point of each run of points that are down, and released before moving on from the last.
+pub fn play_path<M: MouseHandler + ?Sized>(
+    mouse: &M,
+    path: &[PathPoint],
+    button: MouseButton,
+    watch: &mut Watch,
+) -> InputResult {
+    let start_time = Instant::now();
+    let mut held = false;
+    let mut played = || {
+        for point in path {
+            watch.sleep_until(start_time + point.time)?;
+            if held && !point.down {
+                mouse.release(button)?;
+                held = false;
+            }
+            mouse.move_abs(point.x, point.y, Duration::ZERO)?;
+            watch.moved()?;
+            if point.down && !held {
+                mouse.press(button)?;
+                held = true;
+            }
+        }
+        Ok(())
+    };
+    let result = played();
+    // release even if interrupted, like a drag
+    match held {
+        true => result.and(mouse.release(button)),
+        false => result,
+    }
+}
+
+pub fn hold_key<K: KeyboardHandler + ?Sized>(
+    keyboard: &K,
+    key: KeyboardKey,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    keyboard.press(key)?;
+    let result = watch.sleep(duration);
+    let released = keyboard.release(key);
+    result.and(released)
+}
+
+/// Type text one character at a time, spread evenly over `duration`.
+pub fn text<K: KeyboardHandler + ?Sized>(
+    keyboard: &K,
+    text: &str,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    let count = text.chars().count();
+    if duration.is_zero() || count <= 1 {
+        watch.check()?;
+        return keyboard.text(text, Duration::ZERO);
+    }
+    let start_time = Instant::now();
+    for (i, c) in text.chars().enumerate() {
+        watch.sleep_until(start_time + duration.mul_f64(i as f64 / count as f64))?;
+        keyboard.text(c.encode_utf8(&mut [0; 4]), Duration::ZERO)?;
+    }
+    // the last character is typed before the duration is over
+    watch.sleep_until(start_time + duration)
+}
+
+pub fn hold_gamepad_button<G: GamepadHandler + ?Sized>(
+    gamepad: &G,
+    button: GamepadButton,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    gamepad.press(button)?;
+    let result = watch.sleep(duration);
+    let released = gamepad.release(button);
+    result.and(released)
+}
+
+/// Move a stick from where it is to `(x, y)` in a straight line over `duration`.
+pub fn move_stick<G: GamepadHandler + ?Sized>(
+    gamepad: &G,
+    stick: Stick,
+    x: f64,
+    y: f64,
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    if duration.is_zero() {
+        watch.check()?;
+        return gamepad.move_stick(stick, x, y);
+    }
+    let (sx, sy) = gamepad.stick(stick)?;
+    steps(duration, watch, |t| {
+        gamepad.move_stick(stick, sx + (x - sx) * t, sy + (y - sy) * t)
+    })
+}
+
+/// Hold a stick at `(x, y)` for `duration`, then centre it.
+pub fn hold_stick<G: GamepadHandler + ?Sized>(
+    gamepad: &G,
+    stick: Stick,
+    x: f64,
+    y: f64,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    gamepad.move_stick(stick, x, y)?;
+    let result = watch.sleep(duration);
+    // centre even if interrupted, like releasing a held button
+    let centred = gamepad.move_stick(stick, 0.0, 0.0);
+    result.and(centred)
+}
+
+/// Draw a stroke: touch down at the first point, then move through the others at even intervals
+/// over `duration`. A single point is held down for `duration`.
+pub fn stroke<P: PenHandler + ?Sized>(
+    pen: &P,
+    points: &[PenPoint],
+    duration: Duration,
+    watch: &mut Watch,
+) -> InputResult {
+    let Some((first, rest)) = points.split_first() else {
+        return Ok(());
+    };
+    watch.check()?;
+    pen.hover(*first)?;
+    pen.down(*first)?;
+    let start_time = Instant::now();
+    let mut drawn = || {
+        // a screen tablet moves the cursor, so the watch must not take that for the user
+        watch.moved()?;
+        if rest.is_empty() {
+            return watch.sleep(duration);
+        }
+        for (i, point) in rest.iter().enumerate() {
+            let t = (i + 1) as f64 / rest.len() as f64;
+            watch.sleep_until(start_time + duration.mul_f64(t))?;
+            pen.move_to(*point)?;
+            watch.moved()?;
+        }
+        Ok(())
+    };
+    let result = drawn();
+    // lift even if interrupted, like releasing a held button
+    let lifted = pen.up();
+    result.and(lifted)
+}
@@ -0,0 +1,194 @@
+//! Structured logging of actions with [`tracing`].
+//!
+//! Every device call is an `INFO` span named `action`, with the device, the action and its
+//! arguments as fields. When the call finishes an event is emitted inside the span with its
+//! duration and result. Backend primitives are `TRACE` events carrying the backend's name,
+//! see [`Traced`](crate::backends::traced::Traced).
+//!
+//! From python, the events can be forwarded to the `logging` module with `anyinput.enable_logging()`.
+use std::time::Instant;
+
+use tracing::Span;
+
+use crate::core::result::InputError;
+
+/// A short description of the outcome of an action, recorded as the `result` field.
+pub fn outcome<T>(result: &Result<T, InputError>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(InputError::Interrupt) => "interrupted",
+        Err(InputError::Cancelled) => "cancelled",
+        Err(InputError::Error(_)) => "error",
+    }
+}
+
+/// Run a device action inside `span`, then emit an event with its duration and result.
+pub fn action<T>(
+    span: Span,
+    action: impl FnOnce() -> Result<T, InputError>,
+) -> Result<T, InputError> {
+    let _entered = span.enter();
+    let start_time = Instant::now();
+    let result = action();
+    let duration = start_time.elapsed();
+    match &result {
+        Err(InputError::Error(error)) => {
+            tracing::warn!(?duration, result = outcome(&result), %error, "action failed")
+        }
+        _ => tracing::info!(?duration, result = outcome(&result), "action finished"),
+    }
+    result
+}
+
+#[cfg(feature = "python")]
+pub use self::python::{disable_logging, enable_logging};
+
+#[cfg(feature = "python")]
+mod python {
+    use std::fmt::{Debug, Write};
+    use std::sync::{OnceLock, RwLock};
+
+    use pyo3::exceptions::PyValueError;
+    use pyo3::prelude::*;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::subscriber::Interest;
+    use tracing::{Event, Level, Metadata, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+
+    struct Forwarding {
+        logger: PyObject,
+        level: Level,
+    }
+
+    // where events are forwarded to, `None` while logging is disabled
+    static FORWARDING: RwLock<Option<Forwarding>> = RwLock::new(None);
+
+    /// Forwards events to a python logger.
+    struct LoggingLayer;
+
+    /// The fields of a span or event formatted as `name=value`, with the message kept apart.
+    #[derive(Default)]
+    struct Fields {
+        message: String,
+        fields: String,
+    }
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "message" {
+                let _ = write!(self.message, "{:?}", value);
+            } else {
+                let _ = write!(self.fields, " {}={:?}", field.name(), value);
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "message" {
+                self.message.push_str(value);
+            } else {
+                let _ = write!(self.fields, " {}={}", field.name(), value);
+            }
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for LoggingLayer {
+        fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+            // the level can change at any time
+            Interest::sometimes()
+        }
+
+        fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+            match &*FORWARDING.read().unwrap() {
+                Some(forwarding) => *metadata.level() <= forwarding.level,
+                None => false,
+            }
+        }
+
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let mut fields = Fields::default();
+            attrs.record(&mut fields);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(fields);
+            }
+        }
+
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            let mut message = fields.message;
+            message.push_str(&fields.fields);
+            if let Some(scope) = ctx.event_scope(event) {
+                for span in scope.from_root() {
+                    if let Some(fields) = span.extensions().get::<Fields>() {
+                        message.push_str(&fields.fields);
+                    }
+                }
+            }
+            let level = match *event.metadata().level() {
+                Level::TRACE => 5,
+                Level::DEBUG => 10,
+                Level::INFO => 20,
+                Level::WARN => 30,
+                Level::ERROR => 40,
+            };
+            if let Some(forwarding) = &*FORWARDING.read().unwrap() {
+                Python::with_gil(|py| {
+                    let _ = forwarding.logger.call_method1(py, "log", (level, message));
+                });
+            }
+        }
+    }
+
+    fn parse_level(level: &str) -> Option<Level> {
+        match level.to_lowercase().as_str() {
+            "trace" => Some(Level::TRACE),
+            "debug" => Some(Level::DEBUG),
+            "info" => Some(Level::INFO),
+            "warn" | "warning" => Some(Level::WARN),
+            "error" => Some(Level::ERROR),
+            _ => None,
+        }
+    }
+
+    /// Forward a structured log of every action to the `logging` module.
+    ///
+    /// Device calls are logged at INFO with the device, action, arguments, duration and result.
+    /// Backend primitives are logged at level 5 (below DEBUG) with the backend's name, use
+    /// `level="trace"` to include them.
+    ///
+    /// Args:
+    ///     level: The lowest level forwarded, one of "trace", "debug", "info", "warning" and "error".
+    ///     logger: Name of the python logger that records are sent to.
+    #[pyfunction]
+    #[pyo3(signature = (level="info", logger="anyinput"))]
+    pub fn enable_logging(py: Python<'_>, level: &str, logger: &str) -> PyResult<()> {
+        let level = parse_level(level).ok_or(PyValueError::new_err(
+            "Invalid level, expected 'trace', 'debug', 'info', 'warning' or 'error'",
+        ))?;
+        static INSTALLED: OnceLock<bool> = OnceLock::new();
+        let installed = INSTALLED.get_or_init(|| {
+            let subscriber = tracing_subscriber::registry().with(LoggingLayer);
+            tracing::subscriber::set_global_default(subscriber).is_ok()
+        });
+        if !installed {
+            return Err(PyValueError::new_err(
+                "Another tracing subscriber is already installed.",
+            ));
+        }
+        let logger = py
+            .import_bound("logging")?
+            .call_method1("getLogger", (logger,))?
+            .unbind();
+        *FORWARDING.write().unwrap() = Some(Forwarding { logger, level });
+        tracing::callsite::rebuild_interest_cache();
+        Ok(())
+    }
+
+    /// Stop forwarding actions to the `logging` module.
+    #[pyfunction]
+    pub fn disable_logging() {
+        *FORWARDING.write().unwrap() = None;
+    }
+}
@@ -0,0 +1,119 @@
+//! Typing with occasional typos that are noticed and corrected.
+//!
+//! A typo replaces a character with one of its neighbours on the keyboard layout. The typist notices
+//! it straight away or after up to two more characters, backspaces and types the rest again.
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::core::humanize::{Humanizer, Rng};
+use crate::core::interrupt::Watch;
+use crate::core::result::InputResult;
+use crate::device::keyboard::{KeyboardHandler, KeyboardKey};
+use crate::layout::Layout;
+
+/// The most characters typed after a typo before it is noticed.
+const MAX_LATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct TypoConfig {
+    /// The probability that a character is mistyped.
+    pub rate: f64,
+    /// Seed of the random number generator, taken from the clock if not given.
+    pub seed: Option<u64>,
+    /// The layout whose neighbouring keys are mistyped.
+    pub layout: Layout,
+}
+
+impl Default for TypoConfig {
+    fn default() -> Self {
+        Self {
+            rate: 0.03,
+            seed: None,
+            layout: Layout::default(),
+        }
+    }
+}
+
+/// A single keystroke of typed text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stroke {
+    Char(char),
+    Backspace,
+}
+
+/// A [`TypoConfig`] together with the state of its random number generator.
+pub struct Typist {
+    config: TypoConfig,
+    rng: Mutex<Rng>,
+}
+
+impl Typist {
+    pub fn new(config: TypoConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => Rng::new(seed),
+            None => Rng::from_time(),
+        };
+        Self {
+            config,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn config(&self) -> &TypoConfig {
+        &self.config
+    }
+
+    /// The keystrokes that type `text`, including typos and their corrections.
+    pub fn strokes(&self, text: &str) -> Vec<Stroke> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut rng = self.rng.lock().unwrap();
+        let mut strokes = Vec::with_capacity(chars.len());
+        for (i, &c) in chars.iter().enumerate() {
+            let neighbours = self.config.layout.neighbours(c);
+            if neighbours.is_empty() || !rng.chance(self.config.rate) {
+                strokes.push(Stroke::Char(c));
+                continue;
+            }
+            strokes.push(Stroke::Char(neighbours[rng.index(neighbours.len())]));
+            // never type past a control character (e.g. a newline could submit a form)
+            let late = chars[i + 1..]
+                .iter()
+                .take(rng.index(MAX_LATE + 1))
+                .take_while(|c| !c.is_control())
+                .count();
+            strokes.extend(chars[i + 1..=i + late].iter().map(|&c| Stroke::Char(c)));
+            strokes.extend(std::iter::repeat_n(Stroke::Backspace, late + 1));
+            strokes.push(Stroke::Char(c));
+        }
+        strokes
+    }
+}
+
+/// Type text with typos, one keystroke at a time. The rhythm comes from `humanizer` if given,
+/// otherwise the keystrokes are spread evenly over `duration`.
+pub fn text<K: KeyboardHandler + ?Sized>(
+    keyboard: &K,
+    typist: &Typist,
+    humanizer: Option<&Humanizer>,
+    text: &str,
+    duration: Duration,
+    watch: &Watch,
+) -> InputResult {
+    let strokes = typist.strokes(text);
+    let count = strokes.len();
+    let times = match humanizer {
+        Some(humanizer) => humanizer.typing_times(count, (!duration.is_zero()).then_some(duration)),
+        None => (0..count)
+            .map(|i| duration.mul_f64(i as f64 / count as f64))
+            .collect(),
+    };
+    let start_time = Instant::now();
+    for (stroke, time) in strokes.into_iter().zip(times) {
+        watch.sleep_until(start_time + time)?;
+        match stroke {
+            Stroke::Char(c) => keyboard.text(c.encode_utf8(&mut [0; 4]), Duration::ZERO)?,
+            Stroke::Backspace => keyboard.tap(KeyboardKey::Backspace)?,
+        }
+    }
+    watch.sleep_until(start_time + duration)
+}
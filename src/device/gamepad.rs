@@ -0,0 +1,883 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, RwLock, Weak},
+    time::Duration,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "python")]
+use {
+    crate::core::cancel::ActionHandle,
+    crate::core::receipt::Receipt,
+    crate::core::result::py_duration,
+    pyo3::{
+        pyclass, pymethods, types::PyAnyMethods, Bound, FromPyObject, PyAny, PyObject, PyRef,
+        PyResult, Python,
+    },
+};
+
+use crate::{
+    core::cancel::CancelToken,
+    core::failsafe::FailSafe,
+    core::held::{HeldInputs, ReleaseAll},
+    core::interrupt::{InterruptPolicy, Watch},
+    core::receipt,
+    core::result::{InputError, InputResult},
+    core::timed,
+    core::trace,
+    core::MouseHandler,
+};
+use tracing::{info_span, Span};
+
+/// The buttons of an Xbox-style gamepad, named after the Xbox layout.
+///
+/// The string and JSON form of a button is its name, e.g. `"a"`, `"lb"` or `"dpad_up"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// The bottom face button (cross on PlayStation controllers).
+    A,
+    /// The right face button (circle).
+    B,
+    /// The left face button (square).
+    X,
+    /// The top face button (triangle).
+    Y,
+    /// The left shoulder button.
+    LeftBumper,
+    /// The right shoulder button.
+    RightBumper,
+    /// The left button in the middle, also called select or view.
+    Back,
+    /// The right button in the middle, also called menu.
+    Start,
+    /// The Xbox button.
+    Guide,
+    /// Pressing the left stick in.
+    LeftThumb,
+    /// Pressing the right stick in.
+    RightThumb,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+}
+
+impl FromStr for GamepadButton {
+    type Err = InputError;
+
+    /// Parse a button name (case insensitive), aliases such as `"l1"`, `"select"` or `"up"` are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "a" | "south" => Ok(Self::A),
+            "b" | "east" => Ok(Self::B),
+            "x" | "west" => Ok(Self::X),
+            "y" | "north" => Ok(Self::Y),
+            "lb" | "l1" | "left_bumper" => Ok(Self::LeftBumper),
+            "rb" | "r1" | "right_bumper" => Ok(Self::RightBumper),
+            "back" | "select" | "view" => Ok(Self::Back),
+            "start" | "menu" => Ok(Self::Start),
+            "guide" | "home" | "xbox" | "mode" => Ok(Self::Guide),
+            "ls" | "l3" | "left_thumb" => Ok(Self::LeftThumb),
+            "rs" | "r3" | "right_thumb" => Ok(Self::RightThumb),
+            "dpad_up" | "up" => Ok(Self::DpadUp),
+            "dpad_down" | "down" => Ok(Self::DpadDown),
+            "dpad_left" | "left" => Ok(Self::DpadLeft),
+            "dpad_right" | "right" => Ok(Self::DpadRight),
+            _ => Err(InputError::error(format!(
+                "Invalid gamepad button: {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for GamepadButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::A => "a",
+            Self::B => "b",
+            Self::X => "x",
+            Self::Y => "y",
+            Self::LeftBumper => "lb",
+            Self::RightBumper => "rb",
+            Self::Back => "back",
+            Self::Start => "start",
+            Self::Guide => "guide",
+            Self::LeftThumb => "ls",
+            Self::RightThumb => "rs",
+            Self::DpadUp => "dpad_up",
+            Self::DpadDown => "dpad_down",
+            Self::DpadLeft => "dpad_left",
+            Self::DpadRight => "dpad_right",
+        };
+        f.write_str(name)
+    }
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 15] = [
+        Self::A,
+        Self::B,
+        Self::X,
+        Self::Y,
+        Self::LeftBumper,
+        Self::RightBumper,
+        Self::Back,
+        Self::Start,
+        Self::Guide,
+        Self::LeftThumb,
+        Self::RightThumb,
+        Self::DpadUp,
+        Self::DpadDown,
+        Self::DpadLeft,
+        Self::DpadRight,
+    ];
+
+    /// The d-pad direction as `(x, y)`, `None` for the other buttons.
+    pub fn dpad(&self) -> Option<(i32, i32)> {
+        match self {
+            Self::DpadUp => Some((0, -1)),
+            Self::DpadDown => Some((0, 1)),
+            Self::DpadLeft => Some((-1, 0)),
+            Self::DpadRight => Some((1, 0)),
+            _ => None,
+        }
+    }
+
+    /// The `GamepadButton` enum for python, with a member for each button. Members are strings and
+    /// can be used wherever a button name is accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|button| (button.to_string().to_uppercase(), button.to_string()))
+            .collect();
+        super::py_str_enum(py, "GamepadButton", members)
+    }
+}
+
+impl Serialize for GamepadButton {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GamepadButton {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Buttons are passed from python by name, members of the `GamepadButton` enum are names too.
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for GamepadButton {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
+/// The analog sticks of a gamepad, named `"left"` and `"right"` in their string and JSON form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+impl FromStr for Stick {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" | "l" => Ok(Self::Left),
+            "right" | "r" => Ok(Self::Right),
+            _ => Err(InputError::error(format!("Invalid stick: {:?}", s))),
+        }
+    }
+}
+
+impl Display for Stick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Stick {
+    pub const ALL: [Stick; 2] = [Self::Left, Self::Right];
+
+    /// The `Stick` enum for python, members are strings and can be used wherever a stick name is
+    /// accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|stick| (stick.to_string().to_uppercase(), stick.to_string()))
+            .collect();
+        super::py_str_enum(py, "Stick", members)
+    }
+}
+
+impl Serialize for Stick {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Stick {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for Stick {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
+/// The analog triggers of a gamepad, named `"left"` and `"right"` in their string and JSON form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    Left,
+    Right,
+}
+
+impl FromStr for Trigger {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" | "l" | "lt" | "l2" => Ok(Self::Left),
+            "right" | "r" | "rt" | "r2" => Ok(Self::Right),
+            _ => Err(InputError::error(format!("Invalid trigger: {:?}", s))),
+        }
+    }
+}
+
+impl Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Trigger {
+    pub const ALL: [Trigger; 2] = [Self::Left, Self::Right];
+
+    /// The `Trigger` enum for python, members are strings and can be used wherever a trigger name
+    /// is accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|trigger| (trigger.to_string().to_uppercase(), trigger.to_string()))
+            .collect();
+        super::py_str_enum(py, "Trigger", members)
+    }
+}
+
+impl Serialize for Trigger {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Trigger {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for Trigger {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
+/// Sticks range from -1.0 (left, up) to 1.0 (right, down) on each axis, `(0.0, 0.0)` is the
+/// centre. Triggers range from 0.0 (released) to 1.0 (fully pulled).
+pub trait GamepadHandler {
+    /// Press a button down, primitive action.
+    fn press(&self, button: GamepadButton) -> InputResult;
+
+    /// Release a button up, primitive action.
+    fn release(&self, button: GamepadButton) -> InputResult;
+
+    /// Tap a button.
+    fn tap(&self, button: GamepadButton) -> InputResult {
+        self.press(button)?;
+        self.release(button)?;
+        Ok(())
+    }
+
+    /// Hold a button for a duration, then release it.
+    fn hold(&self, button: GamepadButton, duration: Duration) -> InputResult {
+        let policy = InterruptPolicy::none();
+        let watch = policy.watch(None)?;
+        timed::hold_gamepad_button(self, button, duration, &watch)
+    }
+
+    /// Move a stick to a position, primitive action.
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult;
+
+    /// The current position of a stick.
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError>;
+
+    /// Pull a trigger to a value, primitive action.
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult;
+}
+
+/// Anything a gamepad can hold away from its resting state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GamepadInput {
+    Button(GamepadButton),
+    Stick(Stick),
+    Trigger(Trigger),
+}
+
+/// A gamepad with the buttons, sticks and triggers of an Xbox controller.
+///
+/// Sticks range from -1.0 (left, up) to 1.0 (right, down) on each axis, triggers from 0.0
+/// (released) to 1.0 (fully pulled).
+#[cfg_attr(feature = "python", pyclass)]
+pub struct GamepadDevice {
+    backend: Arc<dyn GamepadHandler + Send + Sync>,
+    // shared between clones, buttons are released and sticks and triggers are reset when the last
+    // clone is dropped
+    held: Arc<HeldInputs<GamepadInput>>,
+    interrupt: Arc<RwLock<InterruptPolicy>>,
+    failsafe: Arc<RwLock<Option<Arc<FailSafe>>>>,
+    cancel: Option<CancelToken>,
+    // used to check whether the user moved the cursor during timed actions
+    cursor: Option<Arc<dyn MouseHandler + Send + Sync>>,
+}
+
+impl GamepadDevice {
+    pub fn new(backend: Arc<dyn GamepadHandler + Send + Sync>) -> Self {
+        let release_backend = Arc::clone(&backend);
+        let held = HeldInputs::new(move |input| match input {
+            GamepadInput::Button(button) => release_backend.release(button),
+            GamepadInput::Stick(stick) => release_backend.move_stick(stick, 0.0, 0.0),
+            GamepadInput::Trigger(trigger) => release_backend.set_trigger(trigger, 0.0),
+        });
+        Self {
+            backend,
+            held: Arc::new(held),
+            interrupt: Arc::new(RwLock::new(InterruptPolicy::default())),
+            failsafe: Arc::new(RwLock::new(None)),
+            cancel: None,
+            cursor: None,
+        }
+    }
+
+    /// Use `mouse` to detect cursor movement for [`InterruptCondition::CursorMoved`](crate::core::interrupt::InterruptCondition::CursorMoved).
+    pub fn with_cursor(mut self, mouse: Arc<dyn MouseHandler + Send + Sync>) -> Self {
+        self.cursor = Some(mouse);
+        self
+    }
+
+    /// Release all buttons and reset all sticks and triggers that were moved with this device.
+    pub fn release_all(&self) -> InputResult {
+        self.held.release_all()
+    }
+
+    /// The policy used to interrupt timed actions.
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt.read().unwrap().clone()
+    }
+
+    /// Set the policy used to interrupt timed actions, this also applies to clones of the device.
+    pub fn set_interrupt_policy(&self, policy: InterruptPolicy) {
+        *self.interrupt.write().unwrap() = policy;
+    }
+
+    /// A device that shares held inputs with this one but uses a different interrupt policy, e.g. for a single call.
+    pub fn with_interrupt_policy(&self, policy: InterruptPolicy) -> Self {
+        Self {
+            interrupt: Arc::new(RwLock::new(policy)),
+            ..self.clone()
+        }
+    }
+
+    /// Stop the actions of this device (and its clones) when `failsafe` is tripped, `None` removes the fail-safe.
+    ///
+    /// The buttons, sticks and triggers held by the device are released when the fail-safe trips.
+    pub fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        if let Some(failsafe) = &failsafe {
+            failsafe.release_on_trip(Arc::downgrade(&self.held) as Weak<dyn ReleaseAll>);
+        }
+        *self.failsafe.write().unwrap() = failsafe;
+    }
+
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.failsafe.read().unwrap().clone()
+    }
+
+    /// A device that shares held inputs with this one, whose actions are cancelled by `token`.
+    pub fn with_cancel_token(&self, token: CancelToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    /// Move a stick to a position in a straight line over `duration`.
+    pub fn move_stick_over(&self, stick: Stick, x: f64, y: f64, duration: Duration) -> InputResult {
+        if duration.is_zero() {
+            return GamepadHandler::move_stick(self, stick, x, y);
+        }
+        let span = info_span!(
+            "action",
+            device = "gamepad",
+            action = "move_stick",
+            ?stick,
+            x,
+            y,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            check_stick(x, y)?;
+            let policy = self.interrupt_policy();
+            let mut watch = self.watch(&policy)?;
+            let result = timed::move_stick(&*self.backend, stick, x, y, duration, &mut watch);
+            // the stick may have stopped part way
+            self.track_stick(stick);
+            result
+        })
+    }
+
+    /// Hold a stick at a position for a duration, then let it return to the centre.
+    pub fn hold_stick(&self, stick: Stick, x: f64, y: f64, duration: Duration) -> InputResult {
+        let span = info_span!(
+            "action",
+            device = "gamepad",
+            action = "hold_stick",
+            ?stick,
+            x,
+            y,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            check_stick(x, y)?;
+            let policy = self.interrupt_policy();
+            let watch = self.watch(&policy)?;
+            let result = timed::hold_stick(&*self.backend, stick, x, y, duration, &watch);
+            self.track_stick(stick);
+            result
+        })
+    }
+
+    // a stick that is away from the centre is held, so that it is centred by `release_all`
+    fn track_stick(&self, stick: Stick) {
+        match self.backend.stick(stick) {
+            Ok((x, y)) if x != 0.0 || y != 0.0 => self.held.insert(GamepadInput::Stick(stick)),
+            _ => {
+                self.held.remove(GamepadInput::Stick(stick));
+            }
+        }
+    }
+
+    // actions are refused when cancelled or while the fail-safe is tripped, releasing inputs is always allowed
+    fn check_stopped(&self) -> InputResult {
+        if let Some(token) = &self.cancel {
+            token.check()?;
+        }
+        match &*self.failsafe.read().unwrap() {
+            Some(failsafe) => failsafe.check(),
+            None => Ok(()),
+        }
+    }
+
+    fn watch<'a>(&'a self, policy: &'a InterruptPolicy) -> Result<Watch<'a>, InputError> {
+        Ok(policy
+            .watch(self.cursor.as_deref().map(|c| c as &dyn MouseHandler))?
+            .guard(|| self.check_stopped()))
+    }
+
+    /// Run an action inside its tracing span, measuring it for its receipt.
+    fn action(
+        &self,
+        span: Span,
+        duration: Duration,
+        action: impl FnOnce() -> InputResult,
+    ) -> InputResult {
+        trace::action(span, || {
+            receipt::measure(
+                duration,
+                || self.cursor.as_ref().and_then(|c| c.location().ok()),
+                action,
+            )
+        })
+    }
+
+    // the backend for timed actions, so that the buttons they hold are tracked while they run
+    fn tracked(&self) -> Tracked<'_> {
+        Tracked {
+            backend: &*self.backend,
+            held: &self.held,
+        }
+    }
+}
+
+/// A backend that records the buttons it presses and releases in a device's held inputs.
+struct Tracked<'a> {
+    backend: &'a (dyn GamepadHandler + Send + Sync),
+    held: &'a HeldInputs<GamepadInput>,
+}
+
+impl GamepadHandler for Tracked<'_> {
+    fn press(&self, button: GamepadButton) -> InputResult {
+        self.backend.press(button)?;
+        self.held.insert(GamepadInput::Button(button));
+        Ok(())
+    }
+
+    fn release(&self, button: GamepadButton) -> InputResult {
+        // the fail-safe may have released it while the action was running
+        if !self.held.remove(GamepadInput::Button(button)) {
+            return Ok(());
+        }
+        self.backend.release(button)
+    }
+
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+        self.backend.move_stick(stick, x, y)
+    }
+
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+        self.backend.stick(stick)
+    }
+
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+        self.backend.set_trigger(trigger, value)
+    }
+}
+
+fn check_stick(x: f64, y: f64) -> InputResult {
+    if !(-1.0..=1.0).contains(&x) || !(-1.0..=1.0).contains(&y) {
+        return Err(InputError::error(format!(
+            "Stick position ({}, {}) is outside of -1.0 to 1.0.",
+            x, y
+        )));
+    }
+    Ok(())
+}
+
+fn check_trigger(value: f64) -> InputResult {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(InputError::error(format!(
+            "Trigger value {} is outside of 0.0 to 1.0.",
+            value
+        )));
+    }
+    Ok(())
+}
+
+impl Clone for GamepadDevice {
+    fn clone(&self) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            held: Arc::clone(&self.held),
+            interrupt: Arc::clone(&self.interrupt),
+            failsafe: Arc::clone(&self.failsafe),
+            cancel: self.cancel.clone(),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl GamepadDevice {
+    fn with_interrupt(&self, interrupt: Option<InterruptPolicy>) -> Self {
+        match interrupt {
+            Some(policy) => self.with_interrupt_policy(policy),
+            None => self.clone(),
+        }
+    }
+
+    /// Run `action` with a cancellable clone of the device on a background thread.
+    fn spawn(
+        &self,
+        interrupt: Option<InterruptPolicy>,
+        action: impl FnOnce(&GamepadDevice) -> InputResult + Send + 'static,
+    ) -> ActionHandle {
+        let token = CancelToken::new();
+        let device = self
+            .with_interrupt(interrupt)
+            .with_cancel_token(token.clone());
+        ActionHandle::spawn(token, move || action(&device))
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl GamepadDevice {
+    fn press(&self, button: GamepadButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| GamepadHandler::press(self, button))
+    }
+
+    fn release(&self, button: GamepadButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| GamepadHandler::release(self, button))
+    }
+
+    fn tap(&self, button: GamepadButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| GamepadHandler::tap(self, button))
+    }
+
+    /// Hold a button for a duration, then release it.
+    ///
+    /// Args:
+    ///     button: The button to hold.
+    ///     duration: How long to hold it for, in seconds.
+    ///     interrupt: Overrides the session's interrupt policy for this call.
+    #[pyo3(signature = (button, duration, interrupt=None))]
+    fn hold(
+        &self,
+        button: GamepadButton,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| GamepadHandler::hold(&device, button, duration))
+    }
+
+    /// Like `hold`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (button, duration, interrupt=None))]
+    fn hold_async(
+        &self,
+        button: GamepadButton,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            GamepadHandler::hold(device, button, duration)
+        }))
+    }
+
+    /// Move a stick to a position, where it stays until it is moved again.
+    ///
+    /// Args:
+    ///     stick: "left" or "right".
+    ///     x: From -1.0 (left) to 1.0 (right).
+    ///     y: From -1.0 (up) to 1.0 (down).
+    ///     duration: Move the stick in a straight line over this many seconds, rather than at once.
+    ///     interrupt: Overrides the session's interrupt policy for this call.
+    ///
+    /// Raises:
+    ///     ValueError: If the position is out of range.
+    #[pyo3(signature = (stick, x, y, duration=0.0, interrupt=None))]
+    fn move_stick(
+        &self,
+        stick: Stick,
+        x: f64,
+        y: f64,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| device.move_stick_over(stick, x, y, duration))
+    }
+
+    /// Like `move_stick`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (stick, x, y, duration, interrupt=None))]
+    fn move_stick_async(
+        &self,
+        stick: Stick,
+        x: f64,
+        y: f64,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            device.move_stick_over(stick, x, y, duration)
+        }))
+    }
+
+    /// Hold a stick at a position for a duration, then let it return to the centre.
+    ///
+    /// Example:
+    ///     gamepad.hold_stick("left", 0.0, -1.0, 2.0)  # walk forward for two seconds
+    #[pyo3(name = "hold_stick", signature = (stick, x, y, duration, interrupt=None))]
+    fn py_hold_stick(
+        &self,
+        stick: Stick,
+        x: f64,
+        y: f64,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| device.hold_stick(stick, x, y, duration))
+    }
+
+    /// Like `hold_stick`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (stick, x, y, duration, interrupt=None))]
+    fn hold_stick_async(
+        &self,
+        stick: Stick,
+        x: f64,
+        y: f64,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            device.hold_stick(stick, x, y, duration)
+        }))
+    }
+
+    /// The current position of a stick, as `(x, y)`.
+    #[pyo3(name = "stick")]
+    fn py_stick(&self, stick: Stick) -> PyResult<(f64, f64)> {
+        GamepadHandler::stick(self, stick).map_err(|e| e.into_py())
+    }
+
+    /// Pull a trigger, it stays at the value until it is set again.
+    ///
+    /// Args:
+    ///     trigger: "left" or "right".
+    ///     value: From 0.0 (released) to 1.0 (fully pulled).
+    ///
+    /// Raises:
+    ///     ValueError: If the value is out of range.
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| GamepadHandler::set_trigger(self, trigger, value))
+    }
+
+    /// Release all buttons and reset all sticks and triggers that were moved and not yet reset.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.py_release_all()?;
+        Ok(false)
+    }
+}
+
+impl GamepadHandler for GamepadDevice {
+    fn press(&self, button: GamepadButton) -> InputResult {
+        let span = info_span!("action", device = "gamepad", action = "press", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.press(button)?;
+            self.held.insert(GamepadInput::Button(button));
+            Ok(())
+        })
+    }
+
+    fn release(&self, button: GamepadButton) -> InputResult {
+        let span = info_span!("action", device = "gamepad", action = "release", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.backend.release(button)?;
+            self.held.remove(GamepadInput::Button(button));
+            Ok(())
+        })
+    }
+
+    fn tap(&self, button: GamepadButton) -> InputResult {
+        let span = info_span!("action", device = "gamepad", action = "tap", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.tap(button)
+        })
+    }
+
+    // timed actions are performed here rather than by the backend so that they follow the device's interrupt policy
+
+    fn hold(&self, button: GamepadButton, duration: Duration) -> InputResult {
+        let span = info_span!(
+            "action",
+            device = "gamepad",
+            action = "hold",
+            ?button,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let watch = self.watch(&policy)?;
+            timed::hold_gamepad_button(&self.tracked(), button, duration, &watch)
+        })
+    }
+
+    fn move_stick(&self, stick: Stick, x: f64, y: f64) -> InputResult {
+        let span = info_span!(
+            "action",
+            device = "gamepad",
+            action = "move_stick",
+            ?stick,
+            x,
+            y
+        );
+        self.action(span, Duration::ZERO, || {
+            check_stick(x, y)?;
+            // centring a stick is a release, which is always allowed
+            if x != 0.0 || y != 0.0 {
+                self.check_stopped()?;
+            }
+            self.backend.move_stick(stick, x, y)?;
+            self.track_stick(stick);
+            Ok(())
+        })
+    }
+
+    fn stick(&self, stick: Stick) -> Result<(f64, f64), InputError> {
+        self.backend.stick(stick)
+    }
+
+    fn set_trigger(&self, trigger: Trigger, value: f64) -> InputResult {
+        let span = info_span!(
+            "action",
+            device = "gamepad",
+            action = "set_trigger",
+            ?trigger,
+            value
+        );
+        self.action(span, Duration::ZERO, || {
+            check_trigger(value)?;
+            if value != 0.0 {
+                self.check_stopped()?;
+            }
+            self.backend.set_trigger(trigger, value)?;
+            if value == 0.0 {
+                self.held.remove(GamepadInput::Trigger(trigger));
+            } else {
+                self.held.insert(GamepadInput::Trigger(trigger));
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,9 +1,42 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+    sync::{Arc, RwLock, Weak},
+    time::Duration,
+};
 
-use pyo3::pyclass;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use tracing::{info_span, Span};
+#[cfg(feature = "python")]
+use {
+    crate::core::cancel::ActionHandle,
+    crate::core::receipt::Receipt,
+    crate::core::result::py_duration,
+    crate::layout::Layout,
+    pyo3::{
+        exceptions::PyValueError, pyclass, pymethods, types::PyAnyMethods, Bound, FromPyObject,
+        PyAny, PyObject, PyRef, PyResult, Python,
+    },
+};
 
-use crate::core::result::InputResult;
-#[derive(Debug, Clone, Copy)]
+use crate::core::cancel::CancelToken;
+use crate::core::failsafe::FailSafe;
+use crate::core::held::{HeldInputs, ReleaseAll};
+use crate::core::humanize::{self, Humanizer};
+use crate::core::interrupt::InterruptPolicy;
+use crate::core::result::{InputError, InputResult};
+use crate::core::typos::{self, Typist, TypoConfig};
+use crate::core::{receipt, timed, trace};
+use crate::device::mouse::MouseHandler;
+use crate::layout::physical::PhysicalKey;
+/// A key, identified by what it types or does rather than by its position, except for
+/// [`KeyboardKey::Physical`].
+///
+/// The string and JSON form of a key is a single character for [`KeyboardKey::Unicode`] (`"space"`
+/// for a space), the snake case name of the variant for named keys (`"control"`, `"page_up"`), and
+/// `"code:KeyW"` or `"evdev:17"` for physical keys. Parsing is case insensitive and accepts common
+/// aliases such as `"ctrl"` and `"lctrl"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum KeyboardKey {
     Unicode(char),
     Control,
@@ -11,6 +44,7 @@ pub enum KeyboardKey {
     Alt,
     Enter,
     Escape,
+    Backspace,
     Tab,
     ArrowUp,
     ArrowDown,
@@ -20,9 +54,141 @@ pub enum KeyboardKey {
     End,
     PageUp,
     PageDown,
+    /// The key at a position on the keyboard, whatever it types on the current layout.
+    Physical(PhysicalKey),
     // TODO we might add others!
 }
 
+impl FromStr for KeyboardKey {
+    type Err = InputError;
+
+    /// Parse a key name such as `"ctrl"` or `"enter"`, a single character is parsed as [`KeyboardKey::Unicode`].
+    ///
+    /// Physical keys are named with a prefix, `"code:KeyW"`, or given as a code such as `"evdev:17"`,
+    /// `"hid:26"` or `"x11:25"`, see [`PhysicalKey::from_str`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InputError::error(format!("Invalid key: {:?}", s));
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self::Unicode(c));
+        }
+        if s.contains(':') {
+            return PhysicalKey::from_str(s)
+                .map(Self::Physical)
+                .map_err(|_| invalid());
+        }
+        // "page_up", "page-up" and "PageUp" are the same key
+        let name = s.to_lowercase().replace(['_', '-'], "");
+        let key = match name.as_str() {
+            "control" | "ctrl" | "lctrl" | "rctrl" | "lcontrol" | "rcontrol" => Self::Control,
+            "shift" | "lshift" | "rshift" => Self::Shift,
+            "alt" | "lalt" | "ralt" | "option" => Self::Alt,
+            "enter" | "return" => Self::Enter,
+            "escape" | "esc" => Self::Escape,
+            "backspace" | "bksp" => Self::Backspace,
+            "tab" => Self::Tab,
+            "space" => Self::Unicode(' '),
+            "up" | "arrowup" => Self::ArrowUp,
+            "down" | "arrowdown" => Self::ArrowDown,
+            "left" | "arrowleft" => Self::ArrowLeft,
+            "right" | "arrowright" => Self::ArrowRight,
+            "home" => Self::Home,
+            "end" => Self::End,
+            "pageup" | "pgup" => Self::PageUp,
+            "pagedown" | "pgdn" => Self::PageDown,
+            _ => return Err(invalid()),
+        };
+        Ok(key)
+    }
+}
+
+impl Display for KeyboardKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Unicode(' ') => "space",
+            Self::Unicode(c) => return write!(f, "{}", c),
+            Self::Physical(key) => return write!(f, "{}", key),
+            Self::Control => "control",
+            Self::Shift => "shift",
+            Self::Alt => "alt",
+            Self::Enter => "enter",
+            Self::Escape => "escape",
+            Self::Backspace => "backspace",
+            Self::Tab => "tab",
+            Self::ArrowUp => "arrow_up",
+            Self::ArrowDown => "arrow_down",
+            Self::ArrowLeft => "arrow_left",
+            Self::ArrowRight => "arrow_right",
+            Self::Home => "home",
+            Self::End => "end",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+        };
+        f.write_str(name)
+    }
+}
+
+impl KeyboardKey {
+    /// The keys that have a name, rather than being a character or a physical key.
+    pub const NAMED: [KeyboardKey; 16] = [
+        Self::Control,
+        Self::Shift,
+        Self::Alt,
+        Self::Enter,
+        Self::Escape,
+        Self::Backspace,
+        Self::Tab,
+        Self::Unicode(' '),
+        Self::ArrowUp,
+        Self::ArrowDown,
+        Self::ArrowLeft,
+        Self::ArrowRight,
+        Self::Home,
+        Self::End,
+        Self::PageUp,
+        Self::PageDown,
+    ];
+
+    /// The named keys, letters and digits, the keys an agent would usually choose from.
+    pub fn common() -> Vec<KeyboardKey> {
+        let characters = ('a'..='z').chain('0'..='9').map(Self::Unicode);
+        Self::NAMED.into_iter().chain(characters).collect()
+    }
+
+    /// The `Key` enum for python, with a member for each named key. Members are strings and can be
+    /// used wherever a key is accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::NAMED
+            .iter()
+            .map(|key| (key.to_string().to_uppercase(), key.to_string()))
+            .collect();
+        super::py_str_enum(py, "Key", members)
+    }
+}
+
+impl Serialize for KeyboardKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyboardKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Keys are passed from python by name, members of the `Key` enum are names too.
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for KeyboardKey {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
 // defines the keyboard handler trait, this should be implemented by the backend!
 pub trait KeyboardHandler {
     /// Press a key down, primitive action.
@@ -40,24 +206,196 @@ pub trait KeyboardHandler {
 
     /// Hold a key for a duration, then release it.
     fn hold(&self, key: KeyboardKey, duration: std::time::Duration) -> InputResult {
-        self.press(key)?;
-        std::thread::sleep(duration);
-        self.release(key)?;
-        Ok(())
+        let policy = InterruptPolicy::none();
+        let watch = policy.watch(None)?;
+        timed::hold_key(self, key, duration, &watch)
     }
 
     /// Type text.
     fn text(&self, text: &str, duration: std::time::Duration) -> InputResult;
+
+    /// Get the keys that the system reports as pressed, by any program or the user.
+    fn pressed_keys(&self) -> Result<Vec<KeyboardKey>, InputError> {
+        Err(InputError::error(
+            "The pressed keys are not known to this backend.".to_string(),
+        ))
+    }
 }
 
-#[pyclass]
+/// A keyboard.
+///
+/// Keys are a single character, a key name such as "ctrl" or "enter" (see the `Key` enum), or a
+/// physical key that is pressed whatever the layout: "code:KeyW" (named like browser key codes),
+/// "evdev:17", "hid:26" or "x11:25".
+#[cfg_attr(feature = "python", pyclass)]
 pub struct KeyboardDevice {
     backend: Arc<dyn KeyboardHandler + Send + Sync>,
+    // shared between clones, keys are released when the last clone is dropped
+    held: Arc<HeldInputs<KeyboardKey>>,
+    interrupt: Arc<RwLock<InterruptPolicy>>,
+    failsafe: Arc<RwLock<Option<Arc<FailSafe>>>>,
+    humanizer: Arc<RwLock<Option<Arc<Humanizer>>>>,
+    typist: Arc<RwLock<Option<Arc<Typist>>>>,
+    cancel: Option<CancelToken>,
+    // used to check whether the user moved the cursor during timed actions
+    cursor: Option<Arc<dyn MouseHandler + Send + Sync>>,
 }
 
 impl KeyboardDevice {
     pub fn new(backend: Arc<dyn KeyboardHandler + Send + Sync>) -> Self {
-        Self { backend }
+        let release_backend = Arc::clone(&backend);
+        let held = HeldInputs::new(move |key| release_backend.release(key));
+        Self {
+            backend,
+            held: Arc::new(held),
+            interrupt: Arc::new(RwLock::new(InterruptPolicy::default())),
+            failsafe: Arc::new(RwLock::new(None)),
+            humanizer: Arc::new(RwLock::new(None)),
+            typist: Arc::new(RwLock::new(None)),
+            cancel: None,
+            cursor: None,
+        }
+    }
+
+    /// Use `mouse` to detect cursor movement for [`InterruptCondition::CursorMoved`](crate::core::interrupt::InterruptCondition::CursorMoved).
+    pub fn with_cursor(mut self, mouse: Arc<dyn MouseHandler + Send + Sync>) -> Self {
+        self.cursor = Some(mouse);
+        self
+    }
+
+    /// The keys pressed with this device and not yet released, in the order they were pressed.
+    pub fn held_keys(&self) -> Vec<KeyboardKey> {
+        self.held.held()
+    }
+
+    /// Release all keys that were pressed with this device and not yet released.
+    pub fn release_all(&self) -> InputResult {
+        self.held.release_all()
+    }
+
+    /// The policy used to interrupt timed actions.
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt.read().unwrap().clone()
+    }
+
+    /// Set the policy used to interrupt timed actions, this also applies to clones of the device.
+    pub fn set_interrupt_policy(&self, policy: InterruptPolicy) {
+        *self.interrupt.write().unwrap() = policy;
+    }
+
+    /// A device that shares held keys with this one but uses a different interrupt policy, e.g. for a single call.
+    pub fn with_interrupt_policy(&self, policy: InterruptPolicy) -> Self {
+        Self {
+            interrupt: Arc::new(RwLock::new(policy)),
+            ..self.clone()
+        }
+    }
+
+    /// Stop the actions of this device (and its clones) when `failsafe` is tripped, `None` removes the fail-safe.
+    ///
+    /// The keys held by the device are released when the fail-safe trips.
+    pub fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        if let Some(failsafe) = &failsafe {
+            failsafe.release_on_trip(Arc::downgrade(&self.held) as Weak<dyn ReleaseAll>);
+        }
+        *self.failsafe.write().unwrap() = failsafe;
+    }
+
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.failsafe.read().unwrap().clone()
+    }
+
+    /// Add random variation to taps and typing of this device (and its clones), `None` turns it off.
+    pub fn set_humanizer(&self, humanizer: Option<Arc<Humanizer>>) {
+        *self.humanizer.write().unwrap() = humanizer;
+    }
+
+    pub fn humanizer(&self) -> Option<Arc<Humanizer>> {
+        self.humanizer.read().unwrap().clone()
+    }
+
+    /// Make typed text of this device (and its clones) contain typos that are corrected with
+    /// backspace, `None` turns them off.
+    pub fn set_typos(&self, config: Option<TypoConfig>) {
+        *self.typist.write().unwrap() = config.map(|config| Arc::new(Typist::new(config)));
+    }
+
+    pub fn typos(&self) -> Option<TypoConfig> {
+        let typist = self.typist.read().unwrap();
+        typist.as_ref().map(|typist| typist.config().clone())
+    }
+
+    /// A device that shares held keys with this one, whose actions are cancelled by `token`.
+    pub fn with_cancel_token(&self, token: CancelToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    // actions are refused when cancelled or while the fail-safe is tripped, releasing inputs is always allowed
+    fn check_stopped(&self) -> InputResult {
+        if let Some(token) = &self.cancel {
+            token.check()?;
+        }
+        match &*self.failsafe.read().unwrap() {
+            Some(failsafe) => failsafe.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Run an action inside its tracing span, measuring it for its receipt.
+    fn action(
+        &self,
+        span: Span,
+        duration: Duration,
+        action: impl FnOnce() -> InputResult,
+    ) -> InputResult {
+        trace::action(span, || {
+            receipt::measure(
+                duration,
+                || self.cursor.as_ref().and_then(|c| c.location().ok()),
+                action,
+            )
+        })
+    }
+
+    // the backend for timed actions, so that the keys they hold are tracked while they run
+    fn tracked(&self) -> Tracked<'_> {
+        Tracked {
+            backend: &*self.backend,
+            held: &self.held,
+        }
+    }
+}
+
+/// A backend that records the keys it presses and releases in a device's held keys.
+struct Tracked<'a> {
+    backend: &'a (dyn KeyboardHandler + Send + Sync),
+    held: &'a HeldInputs<KeyboardKey>,
+}
+
+impl KeyboardHandler for Tracked<'_> {
+    fn press(&self, key: KeyboardKey) -> InputResult {
+        self.backend.press(key)?;
+        self.held.insert(key);
+        Ok(())
+    }
+
+    fn release(&self, key: KeyboardKey) -> InputResult {
+        // the fail-safe may have released it while the action was running
+        if !self.held.remove(key) {
+            return Ok(());
+        }
+        self.backend.release(key)
+    }
+
+    fn text(&self, text: &str, duration: Duration) -> InputResult {
+        self.backend.text(text, duration)
+    }
+
+    fn pressed_keys(&self) -> Result<Vec<KeyboardKey>, InputError> {
+        self.backend.pressed_keys()
     }
 }
 
@@ -65,27 +403,283 @@ impl Clone for KeyboardDevice {
     fn clone(&self) -> Self {
         Self {
             backend: Arc::clone(&self.backend),
+            held: Arc::clone(&self.held),
+            interrupt: Arc::clone(&self.interrupt),
+            failsafe: Arc::clone(&self.failsafe),
+            humanizer: Arc::clone(&self.humanizer),
+            typist: Arc::clone(&self.typist),
+            cancel: self.cancel.clone(),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl KeyboardDevice {
+    fn with_interrupt(&self, interrupt: Option<InterruptPolicy>) -> Self {
+        match interrupt {
+            Some(policy) => self.with_interrupt_policy(policy),
+            None => self.clone(),
+        }
+    }
+
+    /// Run `action` with a cancellable clone of the device on a background thread.
+    fn spawn(
+        &self,
+        interrupt: Option<InterruptPolicy>,
+        action: impl FnOnce(&KeyboardDevice) -> InputResult + Send + 'static,
+    ) -> ActionHandle {
+        let token = CancelToken::new();
+        let device = self
+            .with_interrupt(interrupt)
+            .with_cancel_token(token.clone());
+        ActionHandle::spawn(token, move || action(&device))
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl KeyboardDevice {
+    fn press(&self, key: KeyboardKey) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| KeyboardHandler::press(self, key))
+    }
+
+    fn release(&self, key: KeyboardKey) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| KeyboardHandler::release(self, key))
+    }
+
+    fn tap(&self, key: KeyboardKey) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| KeyboardHandler::tap(self, key))
+    }
+
+    #[pyo3(signature = (key, duration, interrupt=None))]
+    fn hold(
+        &self,
+        key: KeyboardKey,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| KeyboardHandler::hold(&device, key, duration))
+    }
+
+    #[pyo3(signature = (text, duration=0.0, interrupt=None))]
+    fn text(
+        &self,
+        text: &str,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| KeyboardHandler::text(&device, text, duration))
+    }
+
+    /// Like `hold`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (key, duration, interrupt=None))]
+    fn hold_async(
+        &self,
+        key: KeyboardKey,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            KeyboardHandler::hold(device, key, duration)
+        }))
+    }
+
+    #[pyo3(signature = (text, duration=0.0, interrupt=None))]
+    fn text_async(
+        &self,
+        text: String,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            KeyboardHandler::text(device, &text, duration)
+        }))
+    }
+
+    /// Make `text` mistype characters as a neighbouring key, then backspace and correct them.
+    ///
+    /// Args:
+    ///     rate: The probability that a character is mistyped, between 0 and 1.
+    ///     seed: Seed of the random number generator, runs with the same seed and calls are
+    ///         reproducible.
+    ///     layout: The layout whose neighbouring keys are mistyped, US if not given.
+    #[pyo3(name = "enable_typos", signature = (rate=0.03, seed=None, layout=None))]
+    fn py_enable_typos(
+        &self,
+        rate: f64,
+        seed: Option<u64>,
+        layout: Option<Layout>,
+    ) -> PyResult<()> {
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(PyValueError::new_err("The rate must be between 0 and 1"));
+        }
+        self.set_typos(Some(TypoConfig {
+            rate,
+            seed,
+            layout: layout.unwrap_or_default(),
+        }));
+        Ok(())
+    }
+
+    #[pyo3(name = "disable_typos")]
+    fn py_disable_typos(&self) {
+        self.set_typos(None);
+    }
+
+    /// Release all keys that were pressed and not yet released.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+
+    /// Hold a key for the duration of a `with` block.
+    ///
+    /// Example:
+    ///     with keyboard.held("shift"):
+    ///         keyboard.tap("a")
+    fn held(&self, key: KeyboardKey) -> HeldKey {
+        HeldKey {
+            device: self.clone(),
+            key,
         }
     }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.py_release_all()?;
+        Ok(false)
+    }
+}
+
+/// A key that is pressed on entering a `with` block and released on exit.
+#[cfg(feature = "python")]
+#[pyclass]
+pub struct HeldKey {
+    device: KeyboardDevice,
+    key: KeyboardKey,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl HeldKey {
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        KeyboardHandler::press(&slf.device, slf.key).map_err(|e| e.into_py())?;
+        Ok(slf)
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        KeyboardHandler::release(&self.device, self.key).map_err(|e| e.into_py())?;
+        Ok(false)
+    }
 }
 
 impl KeyboardHandler for KeyboardDevice {
     fn press(&self, key: KeyboardKey) -> InputResult {
-        self.backend.press(key)
+        let span = info_span!("action", device = "keyboard", action = "press", ?key);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.press(key)?;
+            self.held.insert(key);
+            Ok(())
+        })
     }
     fn release(&self, key: KeyboardKey) -> InputResult {
-        self.backend.release(key)
+        let span = info_span!("action", device = "keyboard", action = "release", ?key);
+        self.action(span, Duration::ZERO, || {
+            self.backend.release(key)?;
+            self.held.remove(key);
+            Ok(())
+        })
     }
 
     fn tap(&self, key: KeyboardKey) -> InputResult {
-        self.backend.tap(key)
+        let span = info_span!("action", device = "keyboard", action = "tap", ?key);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            let Some(humanizer) = self.humanizer() else {
+                return self.backend.tap(key);
+            };
+            let policy = self.interrupt_policy();
+            let watch = policy
+                .watch(self.cursor.as_deref().map(|c| c as &dyn MouseHandler))?
+                .guard(|| self.check_stopped());
+            humanize::tap(&self.tracked(), &humanizer, key, &watch)
+        })
     }
 
+    // timed actions are performed here rather than by the backend so that they follow the device's interrupt policy
+
     fn hold(&self, key: KeyboardKey, duration: std::time::Duration) -> InputResult {
-        self.backend.hold(key, duration)
+        let span = info_span!(
+            "action",
+            device = "keyboard",
+            action = "hold",
+            ?key,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let watch = policy
+                .watch(self.cursor.as_deref().map(|c| c as &dyn MouseHandler))?
+                .guard(|| self.check_stopped());
+            timed::hold_key(&self.tracked(), key, duration, &watch)
+        })
     }
 
     fn text(&self, text: &str, duration: std::time::Duration) -> InputResult {
-        self.backend.text(text, duration)
+        let span = info_span!(
+            "action",
+            device = "keyboard",
+            action = "text",
+            text,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let watch = policy
+                .watch(self.cursor.as_deref().map(|c| c as &dyn MouseHandler))?
+                .guard(|| self.check_stopped());
+            let typist = self.typist.read().unwrap().clone();
+            match (typist, self.humanizer()) {
+                (Some(typist), humanizer) => typos::text(
+                    &*self.backend,
+                    &typist,
+                    humanizer.as_deref(),
+                    text,
+                    duration,
+                    &watch,
+                ),
+                (None, Some(humanizer)) => {
+                    humanize::text(&*self.backend, &humanizer, text, duration, &watch)
+                }
+                (None, None) => timed::text(&*self.backend, text, duration, &watch),
+            }
+        })
+    }
+
+    fn pressed_keys(&self) -> Result<Vec<KeyboardKey>, InputError> {
+        self.backend.pressed_keys()
     }
 }
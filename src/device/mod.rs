@@ -1,3 +1,119 @@
+pub mod gamepad;
 pub mod keyboard;
 pub mod mouse;
+pub mod pen;
 pub mod touch;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[cfg(feature = "python")]
+use pyo3::{prelude::*, types::IntoPyDict, types::PyString};
+
+use crate::core::result::InputError;
+use gamepad::GamepadDevice;
+use keyboard::KeyboardDevice;
+use mouse::MouseDevice;
+use pen::PenDevice;
+use touch::TouchDevice;
+
+/// The kinds of device a session can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceKind {
+    Mouse,
+    Keyboard,
+    Touch,
+    Gamepad,
+    Pen,
+}
+
+impl DeviceKind {
+    pub const ALL: [DeviceKind; 5] = [
+        Self::Mouse,
+        Self::Keyboard,
+        Self::Touch,
+        Self::Gamepad,
+        Self::Pen,
+    ];
+}
+
+impl FromStr for DeviceKind {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let kinds: Vec<String> = Self::ALL.iter().map(|kind| kind.to_string()).collect();
+                InputError::error(format!(
+                    "Unknown device {:?}, expected one of {}",
+                    s,
+                    kinds.join(", ")
+                ))
+            })
+    }
+}
+
+impl Display for DeviceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Mouse => "mouse",
+            Self::Keyboard => "keyboard",
+            Self::Touch => "touch",
+            Self::Gamepad => "gamepad",
+            Self::Pen => "pen",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A device of any kind, converted to its own class in python.
+#[derive(Clone)]
+pub enum Device {
+    Mouse(MouseDevice),
+    Keyboard(KeyboardDevice),
+    Touch(TouchDevice),
+    Gamepad(GamepadDevice),
+    Pen(PenDevice),
+}
+
+impl Device {
+    pub fn kind(&self) -> DeviceKind {
+        match self {
+            Self::Mouse(_) => DeviceKind::Mouse,
+            Self::Keyboard(_) => DeviceKind::Keyboard,
+            Self::Touch(_) => DeviceKind::Touch,
+            Self::Gamepad(_) => DeviceKind::Gamepad,
+            Self::Pen(_) => DeviceKind::Pen,
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl IntoPy<PyObject> for Device {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        match self {
+            Self::Mouse(device) => device.into_py(py),
+            Self::Keyboard(device) => device.into_py(py),
+            Self::Touch(device) => device.into_py(py),
+            Self::Gamepad(device) => device.into_py(py),
+            Self::Pen(device) => device.into_py(py),
+        }
+    }
+}
+
+/// Create a python enum whose members are also strings, so that they are accepted wherever a name
+/// is. `members` are `(name, value)` pairs.
+#[cfg(feature = "python")]
+pub(crate) fn py_str_enum<'py>(
+    py: Python<'py>,
+    name: &str,
+    members: Vec<(String, String)>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = [("module", "anyinput")].into_py_dict_bound(py);
+    kwargs.set_item("type", py.get_type_bound::<PyString>())?;
+    py.import_bound("enum")?
+        .getattr("Enum")?
+        .call((name, members), Some(&kwargs))
+}
@@ -1,21 +1,43 @@
 //
 use std::{
-    fmt::Debug,
-    ops::{Deref, DerefMut},
-    rc::Rc,
-    sync::{Arc, Mutex},
+    fmt::{Debug, Display},
+    str::FromStr,
+    sync::{Arc, RwLock, Weak},
     time::Duration,
 };
 
-use pyo3::{exceptions::PyValueError, pyclass, pymethods, PyResult};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "python")]
+use {
+    crate::core::cancel::ActionHandle,
+    crate::core::receipt::Receipt,
+    crate::core::result::py_duration,
+    pyo3::{
+        buffer::{Element, PyBuffer},
+        exceptions::PyValueError,
+        pyclass, pymethods,
+        types::PyAnyMethods,
+        Bound, FromPyObject, PyAny, PyObject, PyRef, PyResult, Python,
+    },
+};
 
 use crate::{
-    backends::backend::Backend,
-    core::result::{InputResult, Interrupt},
+    core::cancel::CancelToken,
+    core::failsafe::FailSafe,
+    core::held::{HeldInputs, ReleaseAll},
+    core::humanize::{self, Humanizer},
+    core::interrupt::InterruptPolicy,
+    core::receipt,
+    core::result::{InputError, InputResult},
+    core::timed,
+    core::trace,
 };
+use tracing::{info_span, Span};
 
 /// The mouse buttons, more buttons may be added in the future.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// The string and JSON form of a button is its name, `"left"`, `"right"` or `"middle"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MouseButton {
     /// The left mouse button.
     Left,
@@ -25,17 +47,68 @@ pub enum MouseButton {
     Middle,
 }
 
-impl MouseButton {
-    pub fn from_str(s: &str) -> Option<Self> {
+impl FromStr for MouseButton {
+    type Err = InputError;
+
+    /// Parse a button name (case insensitive), aliases such as `"lmb"` or `"primary"` are accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "left" => Some(Self::Left),
-            "right" => Some(Self::Right),
-            "middle" => Some(Self::Middle),
-            _ => None,
+            "left" | "l" | "lmb" | "primary" => Ok(Self::Left),
+            "right" | "r" | "rmb" | "secondary" => Ok(Self::Right),
+            "middle" | "m" | "mmb" | "wheel" => Ok(Self::Middle),
+            _ => Err(InputError::error(format!("Invalid mouse button: {:?}", s))),
         }
     }
 }
 
+impl Display for MouseButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Middle => "middle",
+        };
+        f.write_str(name)
+    }
+}
+
+impl MouseButton {
+    pub const ALL: [MouseButton; 3] = [Self::Left, Self::Right, Self::Middle];
+
+    /// The `MouseButton` enum for python, with a member for each button. Members are strings and
+    /// can be used wherever a button name is accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|button| (button.to_string().to_uppercase(), button.to_string()))
+            .collect();
+        super::py_str_enum(py, "MouseButton", members)
+    }
+}
+
+impl Serialize for MouseButton {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MouseButton {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Buttons are passed from python by name, members of the `MouseButton` enum are names too.
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for MouseButton {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
 pub trait MouseHandler {
     /// Press a mouse button down, primitive action.
     fn press(&self, button: MouseButton) -> InputResult;
@@ -47,7 +120,11 @@ pub trait MouseHandler {
     fn click(&self, button: MouseButton) -> InputResult;
 
     /// Hold a mouse button for a duration, then release it.
-    fn hold(&self, button: MouseButton, duration: std::time::Duration) -> InputResult;
+    fn hold(&self, button: MouseButton, duration: std::time::Duration) -> InputResult {
+        let policy = InterruptPolicy::none();
+        let watch = policy.watch(None)?;
+        timed::hold_button(self, button, duration, &watch)
+    }
 
     /// Drag a mouse button to a relative position.
     fn drag(
@@ -56,98 +133,685 @@ pub trait MouseHandler {
         dx: i32,
         dy: i32,
         duration: std::time::Duration,
-    ) -> InputResult;
+    ) -> InputResult {
+        let policy = InterruptPolicy::none();
+        let mut watch = policy.watch(None)?;
+        timed::drag(self, button, dx, dy, duration, &mut watch)
+    }
 
     /// Move the mouse to an absolute position.
     fn move_abs(&self, x: i32, y: i32, duration: std::time::Duration) -> InputResult;
 
     /// Move the mouse by a relative amount.
     fn move_rel(&self, dx: i32, dy: i32, duration: std::time::Duration) -> InputResult;
+
+    /// Scroll the mouse wheel by a number of clicks, positive values scroll right/down.
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult;
+
+    /// Get the current position of the cursor.
+    fn location(&self) -> Result<(i32, i32), InputError>;
+
+    /// Get the size of the main display.
+    fn display_size(&self) -> Result<(i32, i32), InputError> {
+        Err(InputError::error(
+            "The display size is not known to this backend.".to_string(),
+        ))
+    }
+
+    /// Get the buttons that the system reports as pressed, by any program or the user.
+    fn pressed_buttons(&self) -> Result<Vec<MouseButton>, InputError> {
+        Err(InputError::error(
+            "The pressed buttons are not known to this backend.".to_string(),
+        ))
+    }
 }
 
-#[pyclass]
+/// A point of a cursor path, see [`MouseDevice::play_path`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPoint {
+    pub x: i32,
+    pub y: i32,
+    /// When the cursor reaches the point, from the start of the path.
+    pub time: Duration,
+    /// Whether the button is held down at the point.
+    pub down: bool,
+}
+
+/// A mouse, buttons are given by name or as members of the `MouseButton` enum.
+#[cfg_attr(feature = "python", pyclass)]
 pub struct MouseDevice {
     backend: Arc<dyn MouseHandler + Send + Sync>,
+    // shared between clones, buttons are released when the last clone is dropped
+    held: Arc<HeldInputs<MouseButton>>,
+    interrupt: Arc<RwLock<InterruptPolicy>>,
+    failsafe: Arc<RwLock<Option<Arc<FailSafe>>>>,
+    humanizer: Arc<RwLock<Option<Arc<Humanizer>>>>,
+    cancel: Option<CancelToken>,
 }
 
 impl Clone for MouseDevice {
     fn clone(&self) -> Self {
         Self {
             backend: Arc::clone(&self.backend),
+            held: Arc::clone(&self.held),
+            interrupt: Arc::clone(&self.interrupt),
+            failsafe: Arc::clone(&self.failsafe),
+            humanizer: Arc::clone(&self.humanizer),
+            cancel: self.cancel.clone(),
         }
     }
 }
 
 impl MouseDevice {
     pub fn new(backend: Arc<dyn MouseHandler + Send + Sync>) -> Self {
-        Self { backend }
+        let release_backend = Arc::clone(&backend);
+        let held = HeldInputs::new(move |button| release_backend.release(button));
+        Self {
+            backend,
+            held: Arc::new(held),
+            interrupt: Arc::new(RwLock::new(InterruptPolicy::default())),
+            failsafe: Arc::new(RwLock::new(None)),
+            humanizer: Arc::new(RwLock::new(None)),
+            cancel: None,
+        }
+    }
+
+    /// The buttons pressed with this device and not yet released, in the order they were pressed.
+    pub fn held_buttons(&self) -> Vec<MouseButton> {
+        self.held.held()
+    }
+
+    /// Release all mouse buttons that were pressed with this device and not yet released.
+    pub fn release_all(&self) -> InputResult {
+        self.held.release_all()
+    }
+
+    /// Move the cursor through `path`, reaching each point at its time from the start, and hold
+    /// `button` down through the runs of points that are down.
+    ///
+    /// The button is released at the end even if the path is interrupted. The humanize profile
+    /// does not apply, the path is followed as given.
+    pub fn play_path(&self, path: &[PathPoint], button: MouseButton) -> InputResult {
+        if path.windows(2).any(|pair| pair[1].time < pair[0].time) {
+            return Err(InputError::error(
+                "The times of a path must not decrease.".to_string(),
+            ));
+        }
+        let duration = path.last().map_or(Duration::ZERO, |point| point.time);
+        let span = info_span!(
+            "action",
+            device = "mouse",
+            action = "play_path",
+            points = path.len(),
+            ?button,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let mut watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            timed::play_path(&self.tracked(), path, button, &mut watch)
+        })
+    }
+
+    /// The policy used to interrupt timed actions.
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt.read().unwrap().clone()
+    }
+
+    /// Set the policy used to interrupt timed actions, this also applies to clones of the device.
+    pub fn set_interrupt_policy(&self, policy: InterruptPolicy) {
+        *self.interrupt.write().unwrap() = policy;
+    }
+
+    /// A device that shares held buttons with this one but uses a different interrupt policy, e.g. for a single call.
+    pub fn with_interrupt_policy(&self, policy: InterruptPolicy) -> Self {
+        Self {
+            interrupt: Arc::new(RwLock::new(policy)),
+            ..self.clone()
+        }
+    }
+
+    /// Stop the actions of this device (and its clones) when `failsafe` is tripped, `None` removes the fail-safe.
+    ///
+    /// The buttons held by the device are released when the fail-safe trips.
+    pub fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        if let Some(failsafe) = &failsafe {
+            failsafe.release_on_trip(Arc::downgrade(&self.held) as Weak<dyn ReleaseAll>);
+        }
+        *self.failsafe.write().unwrap() = failsafe;
+    }
+
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.failsafe.read().unwrap().clone()
+    }
+
+    /// Add random variation to clicks and timed moves of this device (and its clones), `None` turns it off.
+    pub fn set_humanizer(&self, humanizer: Option<Arc<Humanizer>>) {
+        *self.humanizer.write().unwrap() = humanizer;
+    }
+
+    pub fn humanizer(&self) -> Option<Arc<Humanizer>> {
+        self.humanizer.read().unwrap().clone()
+    }
+
+    /// A device that shares held buttons with this one, whose actions are cancelled by `token`.
+    pub fn with_cancel_token(&self, token: CancelToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    // actions are refused when cancelled or while the fail-safe is tripped, releasing inputs is always allowed
+    fn check_stopped(&self) -> InputResult {
+        if let Some(token) = &self.cancel {
+            token.check()?;
+        }
+        match &*self.failsafe.read().unwrap() {
+            Some(failsafe) => failsafe.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Run an action inside its tracing span, measuring it for its receipt.
+    fn action(
+        &self,
+        span: Span,
+        duration: Duration,
+        action: impl FnOnce() -> InputResult,
+    ) -> InputResult {
+        trace::action(span, || {
+            receipt::measure(duration, || self.backend.location().ok(), action)
+        })
+    }
+
+    // the backend for timed actions, so that the buttons they hold are tracked while they run
+    fn tracked(&self) -> Tracked<'_> {
+        Tracked {
+            backend: &*self.backend,
+            held: &self.held,
+        }
+    }
+}
+
+/// A backend that records the buttons it presses and releases in a device's held buttons.
+struct Tracked<'a> {
+    backend: &'a (dyn MouseHandler + Send + Sync),
+    held: &'a HeldInputs<MouseButton>,
+}
+
+impl MouseHandler for Tracked<'_> {
+    fn press(&self, button: MouseButton) -> InputResult {
+        self.backend.press(button)?;
+        self.held.insert(button);
+        Ok(())
+    }
+
+    fn release(&self, button: MouseButton) -> InputResult {
+        // the fail-safe may have released it while the action was running
+        if !self.held.remove(button) {
+            return Ok(());
+        }
+        self.backend.release(button)
+    }
+
+    fn click(&self, button: MouseButton) -> InputResult {
+        self.backend.click(button)
+    }
+
+    fn move_abs(&self, x: i32, y: i32, duration: Duration) -> InputResult {
+        self.backend.move_abs(x, y, duration)
+    }
+
+    fn move_rel(&self, dx: i32, dy: i32, duration: Duration) -> InputResult {
+        self.backend.move_rel(dx, dy, duration)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult {
+        self.backend.scroll(dx, dy)
+    }
+
+    fn location(&self) -> Result<(i32, i32), InputError> {
+        self.backend.location()
     }
 
-    fn get_mouse_button(&self, button: &str) -> PyResult<MouseButton> {
-        MouseButton::from_str(button).ok_or(PyValueError::new_err("Invalid mouse button"))
+    fn display_size(&self) -> Result<(i32, i32), InputError> {
+        self.backend.display_size()
     }
+
+    fn pressed_buttons(&self) -> Result<Vec<MouseButton>, InputError> {
+        self.backend.pressed_buttons()
+    }
+}
+
+/// The values and shape of a NumPy array (or anything with the buffer protocol), or of a list of
+/// numbers or of rows of numbers.
+#[cfg(feature = "python")]
+fn py_array(ob: &Bound<'_, PyAny>) -> PyResult<(Vec<f64>, Vec<usize>)> {
+    fn buffer<T: Element + Copy>(
+        ob: &Bound<'_, PyAny>,
+        convert: fn(T) -> f64,
+    ) -> Option<PyResult<(Vec<f64>, Vec<usize>)>> {
+        let buffer = PyBuffer::<T>::get_bound(ob).ok()?;
+        let values = buffer.to_vec(ob.py());
+        Some(values.map(|values| {
+            let values = values.into_iter().map(convert).collect();
+            (values, buffer.shape().to_vec())
+        }))
+    }
+    let buffered = buffer::<f64>(ob, |v| v)
+        .or_else(|| buffer::<f32>(ob, f64::from))
+        .or_else(|| buffer::<i64>(ob, |v| v as f64))
+        .or_else(|| buffer::<i32>(ob, f64::from))
+        .or_else(|| buffer::<u8>(ob, f64::from));
+    if let Some(array) = buffered {
+        return array;
+    }
+    // other arrays, such as booleans, are converted to lists
+    let ob = match ob.hasattr("tolist")? {
+        true => ob.call_method0("tolist")?,
+        false => ob.clone(),
+    };
+    if let Ok(rows) = ob.extract::<Vec<Vec<f64>>>() {
+        let columns = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != columns) {
+            return Err(PyValueError::new_err("The rows have different lengths"));
+        }
+        return Ok((rows.concat(), vec![rows.len(), columns]));
+    }
+    let values: Vec<f64> = ob.extract()?;
+    let len = values.len();
+    Ok((values, vec![len]))
+}
+
+/// A path from python, `(N, 2)` points spread evenly over `duration` or `(N, 3)` points with
+/// timestamps, and the `down` flags of the points.
+#[cfg(feature = "python")]
+fn py_path(
+    path: &Bound<'_, PyAny>,
+    duration: Option<f64>,
+    down: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Vec<PathPoint>> {
+    let (values, shape) = py_array(path)?;
+    let columns = match shape[..] {
+        [_, columns @ (2 | 3)] => columns,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "A path is an (N, 2) or (N, 3) array, not {:?}",
+                shape
+            )))
+        }
+    };
+    let rows: Vec<&[f64]> = values.chunks(columns).collect();
+    let down = match down {
+        Some(down) => {
+            let (flags, _) = py_array(down)?;
+            if flags.len() != rows.len() {
+                return Err(PyValueError::new_err(format!(
+                    "down has {} flags for {} points",
+                    flags.len(),
+                    rows.len()
+                )));
+            }
+            flags.into_iter().map(|flag| flag != 0.0).collect()
+        }
+        None => vec![false; rows.len()],
+    };
+    let times: Vec<f64> = match (columns, duration) {
+        (2, Some(duration)) => {
+            let intervals = rows.len().saturating_sub(1).max(1) as f64;
+            (0..rows.len())
+                .map(|i| duration * i as f64 / intervals)
+                .collect()
+        }
+        (3, None) => {
+            // timestamps are relative to the first point
+            let start = rows.first().map_or(0.0, |row| row[2]);
+            rows.iter().map(|row| row[2] - start).collect()
+        }
+        (2, None) => return Err(PyValueError::new_err("An (N, 2) path needs a duration")),
+        _ => {
+            return Err(PyValueError::new_err(
+                "An (N, 3) path has timestamps, a duration can not be given",
+            ))
+        }
+    };
+    rows.iter()
+        .zip(times)
+        .zip(down)
+        .map(|((row, time), down)| {
+            let time = Duration::try_from_secs_f64(time)
+                .map_err(|_| PyValueError::new_err("The timestamps of a path must not decrease"))?;
+            Ok(PathPoint {
+                x: row[0].round() as i32,
+                y: row[1].round() as i32,
+                time,
+                down,
+            })
+        })
+        .collect()
 }
 
+#[cfg(feature = "python")]
+impl MouseDevice {
+    fn with_interrupt(&self, interrupt: Option<InterruptPolicy>) -> Self {
+        match interrupt {
+            Some(policy) => self.with_interrupt_policy(policy),
+            None => self.clone(),
+        }
+    }
+
+    /// Run `action` with a cancellable clone of the device on a background thread.
+    fn spawn(
+        &self,
+        interrupt: Option<InterruptPolicy>,
+        action: impl FnOnce(&MouseDevice) -> InputResult + Send + 'static,
+    ) -> ActionHandle {
+        let token = CancelToken::new();
+        let device = self
+            .with_interrupt(interrupt)
+            .with_cancel_token(token.clone());
+        ActionHandle::spawn(token, move || action(&device))
+    }
+}
+
+#[cfg(feature = "python")]
 #[pymethods]
 impl MouseDevice {
-    fn press(&self, button: &str) -> PyResult<()> {
-        MouseHandler::press(self, self.get_mouse_button(button)?).map_err(|e| e.into_py())
+    fn press(&self, button: MouseButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| MouseHandler::press(self, button))
     }
 
-    fn release(&self, button: &str) -> PyResult<()> {
-        MouseHandler::release(self, self.get_mouse_button(button)?).map_err(|e| e.into_py())
+    fn release(&self, button: MouseButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| MouseHandler::release(self, button))
     }
 
-    fn click(&self, button: &str) -> PyResult<()> {
-        MouseHandler::click(self, self.get_mouse_button(button)?).map_err(|e| e.into_py())
+    fn click(&self, button: MouseButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| MouseHandler::click(self, button))
     }
 
-    fn hold(&self, button: &str, duration: f64) -> PyResult<()> {
-        MouseHandler::hold(
-            self,
-            self.get_mouse_button(button)?,
-            Duration::from_secs_f64(duration),
-        )
-        .map_err(|e| e.into_py())
+    #[pyo3(signature = (button, duration, interrupt=None))]
+    fn hold(
+        &self,
+        button: MouseButton,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| MouseHandler::hold(&device, button, duration))
     }
 
-    fn drag(&self, button: &str, dx: i32, dy: i32, duration: f64) -> PyResult<()> {
-        MouseHandler::drag(
-            self,
-            self.get_mouse_button(button)?,
-            dx,
-            dy,
-            Duration::from_secs_f64(duration),
-        )
-        .map_err(|e| e.into_py())
+    #[pyo3(signature = (button, dx, dy, duration, interrupt=None))]
+    fn drag(
+        &self,
+        button: MouseButton,
+        dx: i32,
+        dy: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| MouseHandler::drag(&device, button, dx, dy, duration))
     }
 
-    fn move_abs(&self, x: i32, y: i32, duration: f64) -> PyResult<()> {
-        MouseHandler::move_abs(self, x, y, Duration::from_secs_f64(duration))
-            .map_err(|e| e.into_py())
+    #[pyo3(signature = (x, y, duration, interrupt=None))]
+    fn move_abs(
+        &self,
+        x: i32,
+        y: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| MouseHandler::move_abs(&device, x, y, duration))
     }
 
-    fn move_rel(&self, dx: i32, dy: i32, duration: f64) -> PyResult<()> {
-        MouseHandler::move_rel(self, dx, dy, Duration::from_secs_f64(duration))
-            .map_err(|e| e.into_py())
+    #[pyo3(signature = (dx, dy, duration, interrupt=None))]
+    fn move_rel(
+        &self,
+        dx: i32,
+        dy: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| MouseHandler::move_rel(&device, dx, dy, duration))
+    }
+
+    /// Like `hold`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (button, duration, interrupt=None))]
+    fn hold_async(
+        &self,
+        button: MouseButton,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            MouseHandler::hold(device, button, duration)
+        }))
+    }
+
+    #[pyo3(signature = (button, dx, dy, duration, interrupt=None))]
+    fn drag_async(
+        &self,
+        button: MouseButton,
+        dx: i32,
+        dy: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            MouseHandler::drag(device, button, dx, dy, duration)
+        }))
+    }
+
+    #[pyo3(signature = (x, y, duration, interrupt=None))]
+    fn move_abs_async(
+        &self,
+        x: i32,
+        y: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            MouseHandler::move_abs(device, x, y, duration)
+        }))
+    }
+
+    #[pyo3(signature = (dx, dy, duration, interrupt=None))]
+    fn move_rel_async(
+        &self,
+        dx: i32,
+        dy: i32,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            MouseHandler::move_rel(device, dx, dy, duration)
+        }))
+    }
+
+    /// Move the cursor along a whole trajectory, timed in Rust rather than one call per point.
+    ///
+    /// Args:
+    ///     path: An (N, 2) array of x and y positions spread evenly over `duration`, or an (N, 3)
+    ///         array of x, y and a timestamp in seconds (relative to the first point). NumPy
+    ///         arrays and lists of rows are accepted.
+    ///     duration: The length of an (N, 2) path in seconds, not allowed with timestamps.
+    ///     button: The button held by `down`, "left" if not given.
+    ///     down: N flags, the button is held down through the runs of flagged points. Nothing is
+    ///         held if not given.
+    ///     interrupt: Overrides the session's interrupt policy for this call.
+    ///
+    /// Raises:
+    ///     ValueError: If the path is not (N, 2) or (N, 3), its timestamps decrease or `down`
+    ///         does not have a flag for each point.
+    ///
+    /// Example:
+    ///     path = numpy.array([[100, 100, 0.0], [150, 120, 0.05], [200, 180, 0.1]])
+    ///     mouse.play_path(path, down=[False, True, True])
+    #[pyo3(name = "play_path", signature = (path, duration=None, button=None, down=None, interrupt=None))]
+    fn py_play_path(
+        &self,
+        path: &Bound<'_, PyAny>,
+        duration: Option<f64>,
+        button: Option<MouseButton>,
+        down: Option<&Bound<'_, PyAny>>,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let path = py_path(path, duration, down)?;
+        let device = self.with_interrupt(interrupt);
+        receipt::py_measured(|| device.play_path(&path, button.unwrap_or(MouseButton::Left)))
+    }
+
+    /// Like `play_path`, but runs in the background and returns a handle that can cancel the
+    /// action.
+    #[pyo3(signature = (path, duration=None, button=None, down=None, interrupt=None))]
+    fn play_path_async(
+        &self,
+        path: &Bound<'_, PyAny>,
+        duration: Option<f64>,
+        button: Option<MouseButton>,
+        down: Option<&Bound<'_, PyAny>>,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let path = py_path(path, duration, down)?;
+        let button = button.unwrap_or(MouseButton::Left);
+        Ok(self.spawn(interrupt, move |device| device.play_path(&path, button)))
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| MouseHandler::scroll(self, dx, dy))
+    }
+
+    fn location(&self) -> PyResult<(i32, i32)> {
+        MouseHandler::location(self).map_err(|e| e.into_py())
+    }
+
+    /// Release all mouse buttons that were pressed and not yet released.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+
+    /// Hold a mouse button for the duration of a `with` block.
+    ///
+    /// Example:
+    ///     with mouse.held("left"):
+    ///         mouse.move_rel(100, 0, 0.5)
+    fn held(&self, button: MouseButton) -> HeldButton {
+        HeldButton {
+            device: self.clone(),
+            button,
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.py_release_all()?;
+        Ok(false)
+    }
+}
+
+/// A mouse button that is pressed on entering a `with` block and released on exit.
+#[cfg(feature = "python")]
+#[pyclass]
+pub struct HeldButton {
+    device: MouseDevice,
+    button: MouseButton,
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl HeldButton {
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        MouseHandler::press(&slf.device, slf.button).map_err(|e| e.into_py())?;
+        Ok(slf)
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        MouseHandler::release(&self.device, self.button).map_err(|e| e.into_py())?;
+        Ok(false)
     }
 }
 
 // Implement the MouseHandler trait for InputHandler
 impl MouseHandler for MouseDevice {
     fn press(&self, button: MouseButton) -> InputResult {
-        self.backend.press(button)
+        let span = info_span!("action", device = "mouse", action = "press", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.press(button)?;
+            self.held.insert(button);
+            Ok(())
+        })
     }
     fn release(&self, button: MouseButton) -> InputResult {
-        self.backend.release(button)
+        let span = info_span!("action", device = "mouse", action = "release", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.backend.release(button)?;
+            self.held.remove(button);
+            Ok(())
+        })
     }
 
     fn click(&self, button: MouseButton) -> InputResult {
-        self.backend.click(button)
+        let span = info_span!("action", device = "mouse", action = "click", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            let Some(humanizer) = self.humanizer() else {
+                return self.backend.click(button);
+            };
+            let policy = self.interrupt_policy();
+            let mut watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            humanize::click(&self.tracked(), &humanizer, button, &mut watch)
+        })
     }
 
+    // timed actions are performed here rather than by the backend so that they follow the device's interrupt policy
+
     fn hold(&self, button: MouseButton, duration: std::time::Duration) -> InputResult {
-        self.backend.hold(button, duration)
+        let span = info_span!(
+            "action",
+            device = "mouse",
+            action = "hold",
+            ?button,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            timed::hold_button(&self.tracked(), button, duration, &watch)
+        })
     }
 
     fn drag(
@@ -157,14 +821,90 @@ impl MouseHandler for MouseDevice {
         dy: i32,
         duration: std::time::Duration,
     ) -> InputResult {
-        self.backend.drag(button, dx, dy, duration)
+        let span = info_span!(
+            "action",
+            device = "mouse",
+            action = "drag",
+            ?button,
+            dx,
+            dy,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let mut watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            timed::drag(&self.tracked(), button, dx, dy, duration, &mut watch)
+        })
     }
 
     fn move_abs(&self, x: i32, y: i32, duration: std::time::Duration) -> InputResult {
-        self.backend.move_abs(x, y, duration)
+        let span = info_span!(
+            "action",
+            device = "mouse",
+            action = "move_abs",
+            x,
+            y,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let mut watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            match self.humanizer() {
+                Some(humanizer) => {
+                    humanize::move_abs(&*self.backend, &humanizer, x, y, duration, &mut watch)
+                }
+                None => timed::move_abs(&*self.backend, x, y, duration, &mut watch),
+            }
+        })
     }
 
     fn move_rel(&self, dx: i32, dy: i32, duration: std::time::Duration) -> InputResult {
-        self.backend.move_rel(dx, dy, duration)
+        let span = info_span!(
+            "action",
+            device = "mouse",
+            action = "move_rel",
+            dx,
+            dy,
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            let policy = self.interrupt_policy();
+            let mut watch = policy
+                .watch(Some(&*self.backend))?
+                .guard(|| self.check_stopped());
+            match self.humanizer() {
+                Some(humanizer) => {
+                    humanize::move_rel(&*self.backend, &humanizer, dx, dy, duration, &mut watch)
+                }
+                None => timed::move_rel(&*self.backend, dx, dy, duration, &mut watch),
+            }
+        })
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult {
+        let span = info_span!("action", device = "mouse", action = "scroll", dx, dy);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.scroll(dx, dy)
+        })
+    }
+
+    fn location(&self) -> Result<(i32, i32), InputError> {
+        self.backend.location()
+    }
+
+    fn display_size(&self) -> Result<(i32, i32), InputError> {
+        self.backend.display_size()
+    }
+
+    fn pressed_buttons(&self) -> Result<Vec<MouseButton>, InputError> {
+        self.backend.pressed_buttons()
     }
 }
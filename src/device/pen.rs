@@ -0,0 +1,707 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, RwLock, Weak},
+    time::Duration,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "python")]
+use {
+    crate::core::cancel::ActionHandle,
+    crate::core::receipt::Receipt,
+    crate::core::result::py_duration,
+    pyo3::{
+        exceptions::PyValueError, pyclass, pymethods, types::PyAnyMethods, Bound, FromPyObject,
+        PyAny, PyObject, PyRef, PyResult, Python,
+    },
+};
+
+use crate::{
+    core::cancel::CancelToken,
+    core::failsafe::FailSafe,
+    core::held::{HeldInputs, ReleaseAll},
+    core::interrupt::{InterruptPolicy, Watch},
+    core::receipt,
+    core::result::{InputError, InputResult},
+    core::timed,
+    core::trace,
+    core::MouseHandler,
+};
+use tracing::{info_span, Span};
+
+/// The buttons on the barrel of a pen.
+///
+/// The string and JSON form of a button is its name, `"primary"` or `"secondary"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PenButton {
+    /// The button nearest the tip, usually a right click.
+    Primary,
+    /// The button further from the tip.
+    Secondary,
+}
+
+impl FromStr for PenButton {
+    type Err = InputError;
+
+    /// Parse a button name (case insensitive), `"lower"` and `"upper"` are accepted too.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "primary" | "lower" | "1" => Ok(Self::Primary),
+            "secondary" | "upper" | "2" => Ok(Self::Secondary),
+            _ => Err(InputError::error(format!("Invalid pen button: {:?}", s))),
+        }
+    }
+}
+
+impl Display for PenButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Primary => "primary",
+            Self::Secondary => "secondary",
+        };
+        f.write_str(name)
+    }
+}
+
+impl PenButton {
+    pub const ALL: [PenButton; 2] = [Self::Primary, Self::Secondary];
+
+    /// The `PenButton` enum for python, members are strings and can be used wherever a button name
+    /// is accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|button| (button.to_string().to_uppercase(), button.to_string()))
+            .collect();
+        super::py_str_enum(py, "PenButton", members)
+    }
+}
+
+impl Serialize for PenButton {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PenButton {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for PenButton {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
+/// The end of the pen that is used, named `"pen"` and `"eraser"` in its string and JSON form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PenTool {
+    /// The tip.
+    #[default]
+    Pen,
+    /// The eraser at the other end, which apps use to erase.
+    Eraser,
+}
+
+impl FromStr for PenTool {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pen" | "tip" => Ok(Self::Pen),
+            "eraser" | "rubber" => Ok(Self::Eraser),
+            _ => Err(InputError::error(format!("Invalid pen tool: {:?}", s))),
+        }
+    }
+}
+
+impl Display for PenTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Pen => "pen",
+            Self::Eraser => "eraser",
+        };
+        f.write_str(name)
+    }
+}
+
+impl PenTool {
+    pub const ALL: [PenTool; 2] = [Self::Pen, Self::Eraser];
+
+    /// The `PenTool` enum for python, members are strings and can be used wherever a tool name is
+    /// accepted.
+    #[cfg(feature = "python")]
+    pub fn py_enum(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        let members = Self::ALL
+            .iter()
+            .map(|tool| (tool.to_string().to_uppercase(), tool.to_string()))
+            .collect();
+        super::py_str_enum(py, "PenTool", members)
+    }
+}
+
+impl Serialize for PenTool {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PenTool {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for PenTool {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        name.parse().map_err(|e: InputError| e.into_py())
+    }
+}
+
+/// A position of the pen, with the pressure and tilt it has there.
+///
+/// The JSON form is an object with the fields below, the pressure defaults to 1.0 and the tilt to
+/// 0.0 if they are left out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PenPoint {
+    /// Position on the screen in pixels, like the mouse and touch screen.
+    pub x: i32,
+    pub y: i32,
+    /// From 0.0 to 1.0, only applies while the tip is down.
+    #[serde(default = "full_pressure")]
+    pub pressure: f64,
+    /// From -90.0 to 90.0 degrees, positive when the top of the pen leans to the right.
+    #[serde(default)]
+    pub tilt_x: f64,
+    /// From -90.0 to 90.0 degrees, positive when the top of the pen leans towards the user.
+    #[serde(default)]
+    pub tilt_y: f64,
+}
+
+fn full_pressure() -> f64 {
+    1.0
+}
+
+impl PenPoint {
+    /// A point at full pressure, with the pen upright.
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            x,
+            y,
+            pressure: 1.0,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+        }
+    }
+
+    pub fn check(&self) -> InputResult {
+        if !(0.0..=1.0).contains(&self.pressure) {
+            return Err(InputError::error(format!(
+                "Pen pressure {} is outside of 0.0 to 1.0.",
+                self.pressure
+            )));
+        }
+        if !(-90.0..=90.0).contains(&self.tilt_x) || !(-90.0..=90.0).contains(&self.tilt_y) {
+            return Err(InputError::error(format!(
+                "Pen tilt ({}, {}) is outside of -90.0 to 90.0 degrees.",
+                self.tilt_x, self.tilt_y
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A pen on a screen tablet. The pen is in range while it hovers above the tablet or touches it.
+pub trait PenHandler {
+    /// Bring the pen into range above a point, or move it there while it hovers, primitive action.
+    ///
+    /// The tip is lifted if it is down, the pressure of `point` is ignored.
+    fn hover(&self, point: PenPoint) -> InputResult;
+
+    /// Touch the tip down at a point, primitive action.
+    fn down(&self, point: PenPoint) -> InputResult;
+
+    /// Move the pen to a point, keeping the tip down or up, primitive action.
+    fn move_to(&self, point: PenPoint) -> InputResult;
+
+    /// Lift the tip, the pen keeps hovering, primitive action.
+    fn up(&self) -> InputResult;
+
+    /// Take the pen out of range of the tablet, primitive action.
+    fn leave(&self) -> InputResult;
+
+    /// Press a barrel button down, primitive action.
+    fn press(&self, button: PenButton) -> InputResult;
+
+    /// Release a barrel button up, primitive action.
+    fn release(&self, button: PenButton) -> InputResult;
+
+    /// Switch between the tip and the eraser, primitive action. The tip is lifted if it is down.
+    fn set_tool(&self, tool: PenTool) -> InputResult;
+
+    /// Draw a stroke through `points`, spread evenly over `duration`, then lift the tip.
+    fn stroke(&self, points: &[PenPoint], duration: Duration) -> InputResult {
+        let policy = InterruptPolicy::none();
+        let mut watch = policy.watch(None)?;
+        timed::stroke(self, points, duration, &mut watch)
+    }
+}
+
+/// Anything a pen can hold, released in reverse order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PenInput {
+    InRange,
+    Tip,
+    Button(PenButton),
+}
+
+/// A pen with pressure, tilt, barrel buttons and an eraser, on a screen tablet.
+///
+/// Positions are in pixels, pressure is from 0.0 to 1.0 and tilt from -90.0 to 90.0 degrees.
+#[cfg_attr(feature = "python", pyclass)]
+pub struct PenDevice {
+    backend: Arc<dyn PenHandler + Send + Sync>,
+    // shared between clones, the pen is lifted and taken out of range when the last clone is dropped
+    held: Arc<HeldInputs<PenInput>>,
+    interrupt: Arc<RwLock<InterruptPolicy>>,
+    failsafe: Arc<RwLock<Option<Arc<FailSafe>>>>,
+    cancel: Option<CancelToken>,
+    // used to check whether the user moved the cursor during timed actions
+    cursor: Option<Arc<dyn MouseHandler + Send + Sync>>,
+}
+
+impl PenDevice {
+    pub fn new(backend: Arc<dyn PenHandler + Send + Sync>) -> Self {
+        let release_backend = Arc::clone(&backend);
+        let held = HeldInputs::new(move |input| match input {
+            PenInput::InRange => release_backend.leave(),
+            PenInput::Tip => release_backend.up(),
+            PenInput::Button(button) => release_backend.release(button),
+        });
+        Self {
+            backend,
+            held: Arc::new(held),
+            interrupt: Arc::new(RwLock::new(InterruptPolicy::default())),
+            failsafe: Arc::new(RwLock::new(None)),
+            cancel: None,
+            cursor: None,
+        }
+    }
+
+    /// Use `mouse` to detect cursor movement for [`InterruptCondition::CursorMoved`](crate::core::interrupt::InterruptCondition::CursorMoved).
+    pub fn with_cursor(mut self, mouse: Arc<dyn MouseHandler + Send + Sync>) -> Self {
+        self.cursor = Some(mouse);
+        self
+    }
+
+    /// Release the buttons, lift the tip and take the pen out of range, if this device did those.
+    pub fn release_all(&self) -> InputResult {
+        self.held.release_all()
+    }
+
+    /// The policy used to interrupt timed actions.
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt.read().unwrap().clone()
+    }
+
+    /// Set the policy used to interrupt timed actions, this also applies to clones of the device.
+    pub fn set_interrupt_policy(&self, policy: InterruptPolicy) {
+        *self.interrupt.write().unwrap() = policy;
+    }
+
+    /// A device that shares held inputs with this one but uses a different interrupt policy, e.g. for a single call.
+    pub fn with_interrupt_policy(&self, policy: InterruptPolicy) -> Self {
+        Self {
+            interrupt: Arc::new(RwLock::new(policy)),
+            ..self.clone()
+        }
+    }
+
+    /// Stop the actions of this device (and its clones) when `failsafe` is tripped, `None` removes the fail-safe.
+    ///
+    /// The pen is lifted and taken out of range when the fail-safe trips.
+    pub fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        if let Some(failsafe) = &failsafe {
+            failsafe.release_on_trip(Arc::downgrade(&self.held) as Weak<dyn ReleaseAll>);
+        }
+        *self.failsafe.write().unwrap() = failsafe;
+    }
+
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.failsafe.read().unwrap().clone()
+    }
+
+    /// A device that shares held inputs with this one, whose actions are cancelled by `token`.
+    pub fn with_cancel_token(&self, token: CancelToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    // actions are refused when cancelled or while the fail-safe is tripped, releasing inputs is always allowed
+    fn check_stopped(&self) -> InputResult {
+        if let Some(token) = &self.cancel {
+            token.check()?;
+        }
+        match &*self.failsafe.read().unwrap() {
+            Some(failsafe) => failsafe.check(),
+            None => Ok(()),
+        }
+    }
+
+    fn watch<'a>(&'a self, policy: &'a InterruptPolicy) -> Result<Watch<'a>, InputError> {
+        Ok(policy
+            .watch(self.cursor.as_deref().map(|c| c as &dyn MouseHandler))?
+            .guard(|| self.check_stopped()))
+    }
+
+    /// Run an action inside its tracing span, measuring it for its receipt.
+    fn action(
+        &self,
+        span: Span,
+        duration: Duration,
+        action: impl FnOnce() -> InputResult,
+    ) -> InputResult {
+        trace::action(span, || {
+            receipt::measure(
+                duration,
+                || self.cursor.as_ref().and_then(|c| c.location().ok()),
+                action,
+            )
+        })
+    }
+}
+
+impl Clone for PenDevice {
+    fn clone(&self) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            held: Arc::clone(&self.held),
+            interrupt: Arc::clone(&self.interrupt),
+            failsafe: Arc::clone(&self.failsafe),
+            cancel: self.cancel.clone(),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+/// A point from python, `(x, y)`, `(x, y, pressure)` or `(x, y, pressure, tilt_x, tilt_y)`.
+#[cfg(feature = "python")]
+fn py_point(values: &[f64]) -> PyResult<PenPoint> {
+    let (x, y, pressure, tilt_x, tilt_y) = match *values {
+        [x, y] => (x, y, 1.0, 0.0, 0.0),
+        [x, y, pressure] => (x, y, pressure, 0.0, 0.0),
+        [x, y, pressure, tilt_x, tilt_y] => (x, y, pressure, tilt_x, tilt_y),
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "A pen point has 2, 3 or 5 values, not {}",
+                values.len()
+            )))
+        }
+    };
+    Ok(PenPoint {
+        x: x.round() as i32,
+        y: y.round() as i32,
+        pressure,
+        tilt_x,
+        tilt_y,
+    })
+}
+
+#[cfg(feature = "python")]
+impl PenDevice {
+    fn with_interrupt(&self, interrupt: Option<InterruptPolicy>) -> Self {
+        match interrupt {
+            Some(policy) => self.with_interrupt_policy(policy),
+            None => self.clone(),
+        }
+    }
+
+    /// Run `action` with a cancellable clone of the device on a background thread.
+    fn spawn(
+        &self,
+        interrupt: Option<InterruptPolicy>,
+        action: impl FnOnce(&PenDevice) -> InputResult + Send + 'static,
+    ) -> ActionHandle {
+        let token = CancelToken::new();
+        let device = self
+            .with_interrupt(interrupt)
+            .with_cancel_token(token.clone());
+        ActionHandle::spawn(token, move || action(&device))
+    }
+
+    fn py_points(points: Vec<Vec<f64>>) -> PyResult<Vec<PenPoint>> {
+        points.iter().map(|values| py_point(values)).collect()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PenDevice {
+    /// Bring the pen into range above a position, or move it there while it hovers. The tip is
+    /// lifted if it is down.
+    #[pyo3(signature = (x, y, tilt_x=0.0, tilt_y=0.0))]
+    fn hover(&self, x: i32, y: i32, tilt_x: f64, tilt_y: f64) -> PyResult<Option<Receipt>> {
+        let point = PenPoint {
+            tilt_x,
+            tilt_y,
+            ..PenPoint::new(x, y)
+        };
+        receipt::py_measured(|| PenHandler::hover(self, point))
+    }
+
+    /// Touch the tip down at a position.
+    ///
+    /// Args:
+    ///     x: Position on the screen in pixels.
+    ///     y: Position on the screen in pixels.
+    ///     pressure: From 0.0 to 1.0.
+    ///     tilt_x: From -90.0 to 90.0 degrees, positive when the top of the pen leans to the
+    ///         right.
+    ///     tilt_y: From -90.0 to 90.0 degrees, positive when the top of the pen leans towards the
+    ///         user.
+    ///
+    /// Raises:
+    ///     ValueError: If the pressure or tilt is out of range.
+    #[pyo3(signature = (x, y, pressure=1.0, tilt_x=0.0, tilt_y=0.0))]
+    fn down(
+        &self,
+        x: i32,
+        y: i32,
+        pressure: f64,
+        tilt_x: f64,
+        tilt_y: f64,
+    ) -> PyResult<Option<Receipt>> {
+        let point = PenPoint {
+            x,
+            y,
+            pressure,
+            tilt_x,
+            tilt_y,
+        };
+        receipt::py_measured(|| PenHandler::down(self, point))
+    }
+
+    /// Move the pen to a position, keeping the tip down or up. The pressure only applies while
+    /// the tip is down.
+    #[pyo3(signature = (x, y, pressure=1.0, tilt_x=0.0, tilt_y=0.0))]
+    fn move_to(
+        &self,
+        x: i32,
+        y: i32,
+        pressure: f64,
+        tilt_x: f64,
+        tilt_y: f64,
+    ) -> PyResult<Option<Receipt>> {
+        let point = PenPoint {
+            x,
+            y,
+            pressure,
+            tilt_x,
+            tilt_y,
+        };
+        receipt::py_measured(|| PenHandler::move_to(self, point))
+    }
+
+    /// Lift the tip, the pen keeps hovering.
+    fn up(&self) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| PenHandler::up(self))
+    }
+
+    /// Take the pen out of range of the tablet.
+    fn leave(&self) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| PenHandler::leave(self))
+    }
+
+    fn press(&self, button: PenButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| PenHandler::press(self, button))
+    }
+
+    fn release(&self, button: PenButton) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| PenHandler::release(self, button))
+    }
+
+    /// Switch between the tip ("pen") and the eraser ("eraser"), the tip is lifted if it is down.
+    fn set_tool(&self, tool: PenTool) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| PenHandler::set_tool(self, tool))
+    }
+
+    /// Draw a stroke: touch down at the first point, move through the others and lift the tip.
+    ///
+    /// Args:
+    ///     points: Each point is `(x, y)`, `(x, y, pressure)` or
+    ///         `(x, y, pressure, tilt_x, tilt_y)`.
+    ///     duration: The points are spread evenly over this many seconds.
+    ///     interrupt: Overrides the session's interrupt policy for this call.
+    ///
+    /// Example:
+    ///     pen.stroke([(100, 100, 0.2), (150, 120, 0.8), (200, 100, 0.3)], 0.5)
+    #[pyo3(signature = (points, duration, interrupt=None))]
+    fn stroke(
+        &self,
+        points: Vec<Vec<f64>>,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<Option<Receipt>> {
+        let points = Self::py_points(points)?;
+        let device = self.with_interrupt(interrupt);
+        let duration = py_duration(duration)?;
+        receipt::py_measured(|| PenHandler::stroke(&device, &points, duration))
+    }
+
+    /// Like `stroke`, but runs in the background and returns a handle that can cancel the action.
+    #[pyo3(signature = (points, duration, interrupt=None))]
+    fn stroke_async(
+        &self,
+        points: Vec<Vec<f64>>,
+        duration: f64,
+        interrupt: Option<InterruptPolicy>,
+    ) -> PyResult<ActionHandle> {
+        let points = Self::py_points(points)?;
+        let duration = py_duration(duration)?;
+        Ok(self.spawn(interrupt, move |device| {
+            PenHandler::stroke(device, &points, duration)
+        }))
+    }
+
+    /// Release the barrel buttons, lift the tip and take the pen out of range.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.py_release_all()?;
+        Ok(false)
+    }
+}
+
+impl PenHandler for PenDevice {
+    fn hover(&self, point: PenPoint) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "hover", ?point);
+        self.action(span, Duration::ZERO, || {
+            point.check()?;
+            self.check_stopped()?;
+            self.backend.hover(point)?;
+            self.held.remove(PenInput::Tip);
+            self.held.insert(PenInput::InRange);
+            Ok(())
+        })
+    }
+
+    fn down(&self, point: PenPoint) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "down", ?point);
+        self.action(span, Duration::ZERO, || {
+            point.check()?;
+            self.check_stopped()?;
+            self.backend.down(point)?;
+            self.held.insert(PenInput::InRange);
+            self.held.insert(PenInput::Tip);
+            Ok(())
+        })
+    }
+
+    fn move_to(&self, point: PenPoint) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "move_to", ?point);
+        self.action(span, Duration::ZERO, || {
+            point.check()?;
+            self.check_stopped()?;
+            self.backend.move_to(point)?;
+            self.held.insert(PenInput::InRange);
+            Ok(())
+        })
+    }
+
+    fn up(&self) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "up");
+        self.action(span, Duration::ZERO, || {
+            self.backend.up()?;
+            self.held.remove(PenInput::Tip);
+            Ok(())
+        })
+    }
+
+    fn leave(&self) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "leave");
+        self.action(span, Duration::ZERO, || {
+            self.backend.leave()?;
+            self.held.remove(PenInput::Tip);
+            self.held.remove(PenInput::InRange);
+            Ok(())
+        })
+    }
+
+    fn press(&self, button: PenButton) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "press", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.press(button)?;
+            self.held.insert(PenInput::Button(button));
+            Ok(())
+        })
+    }
+
+    fn release(&self, button: PenButton) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "release", ?button);
+        self.action(span, Duration::ZERO, || {
+            self.backend.release(button)?;
+            self.held.remove(PenInput::Button(button));
+            Ok(())
+        })
+    }
+
+    fn set_tool(&self, tool: PenTool) -> InputResult {
+        let span = info_span!("action", device = "pen", action = "set_tool", ?tool);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.set_tool(tool)?;
+            self.held.remove(PenInput::Tip);
+            Ok(())
+        })
+    }
+
+    // the stroke is performed here rather than by the backend so that it follows the device's interrupt policy
+
+    fn stroke(&self, points: &[PenPoint], duration: Duration) -> InputResult {
+        let span = info_span!(
+            "action",
+            device = "pen",
+            action = "stroke",
+            points = points.len(),
+            ?duration
+        );
+        self.action(span, duration, || {
+            self.check_stopped()?;
+            points.iter().try_for_each(PenPoint::check)?;
+            let policy = self.interrupt_policy();
+            let mut watch = self.watch(&policy)?;
+            let result = timed::stroke(&*self.backend, points, duration, &mut watch);
+            // the pen hovers after the stroke, or wherever it was interrupted
+            self.held.remove(PenInput::Tip);
+            if !points.is_empty() {
+                self.held.insert(PenInput::InRange);
+            }
+            result
+        })
+    }
+}
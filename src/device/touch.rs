@@ -1,17 +1,120 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 
-use pyo3::pyclass;
+use tracing::{info_span, Span};
 
-pub trait TouchHandler {}
+#[cfg(feature = "python")]
+use {
+    crate::core::receipt::Receipt,
+    pyo3::{pyclass, pymethods, PyResult},
+};
 
-#[pyclass]
+use crate::core::cancel::CancelToken;
+use crate::core::failsafe::FailSafe;
+use crate::core::held::{HeldInputs, ReleaseAll};
+use crate::core::result::InputError;
+use crate::core::result::InputResult;
+use crate::core::{receipt, trace};
+
+/// Touch contacts are identified by an id, so that multiple contacts can be down at once.
+pub trait TouchHandler {
+    /// Put a touch contact down at a position, primitive action.
+    fn down(&self, id: u32, x: i32, y: i32) -> InputResult;
+
+    /// Move a touch contact that is down to a position, primitive action.
+    fn move_to(&self, id: u32, x: i32, y: i32) -> InputResult;
+
+    /// Lift a touch contact up, primitive action.
+    fn up(&self, id: u32) -> InputResult;
+
+    /// Tap at a position.
+    fn tap(&self, id: u32, x: i32, y: i32) -> InputResult {
+        self.down(id, x, y)?;
+        self.up(id)?;
+        Ok(())
+    }
+
+    /// Get the ids of the contacts that the system reports as down.
+    fn active_contacts(&self) -> Result<Vec<u32>, InputError> {
+        Err(InputError::error(
+            "The touch contacts are not known to this backend.".to_string(),
+        ))
+    }
+}
+
+/// A touch screen, contacts are identified by an id so that several can be down at once.
+#[cfg_attr(feature = "python", pyclass)]
 pub struct TouchDevice {
     backend: Arc<dyn TouchHandler + Send + Sync>,
+    // shared between clones, contacts are lifted when the last clone is dropped
+    held: Arc<HeldInputs<u32>>,
+    failsafe: Arc<RwLock<Option<Arc<FailSafe>>>>,
+    cancel: Option<CancelToken>,
 }
 
 impl TouchDevice {
     pub fn new(backend: Arc<dyn TouchHandler + Send + Sync>) -> Self {
-        Self { backend }
+        let release_backend = Arc::clone(&backend);
+        let held = HeldInputs::new(move |id| release_backend.up(id));
+        Self {
+            backend,
+            held: Arc::new(held),
+            failsafe: Arc::new(RwLock::new(None)),
+            cancel: None,
+        }
+    }
+
+    /// The contacts put down with this device and not yet lifted, in the order they were put down.
+    pub fn held_contacts(&self) -> Vec<u32> {
+        self.held.held()
+    }
+
+    /// Lift all touch contacts that were put down with this device and not yet lifted.
+    pub fn release_all(&self) -> InputResult {
+        self.held.release_all()
+    }
+
+    /// Stop the actions of this device (and its clones) when `failsafe` is tripped, `None` removes the fail-safe.
+    ///
+    /// The contacts held by the device are released when the fail-safe trips.
+    pub fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        if let Some(failsafe) = &failsafe {
+            failsafe.release_on_trip(Arc::downgrade(&self.held) as Weak<dyn ReleaseAll>);
+        }
+        *self.failsafe.write().unwrap() = failsafe;
+    }
+
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.failsafe.read().unwrap().clone()
+    }
+
+    /// A device that shares held contacts with this one, whose actions are cancelled by `token`.
+    pub fn with_cancel_token(&self, token: CancelToken) -> Self {
+        Self {
+            cancel: Some(token),
+            ..self.clone()
+        }
+    }
+
+    // actions are refused when cancelled or while the fail-safe is tripped, releasing inputs is always allowed
+    fn check_stopped(&self) -> InputResult {
+        if let Some(token) = &self.cancel {
+            token.check()?;
+        }
+        match &*self.failsafe.read().unwrap() {
+            Some(failsafe) => failsafe.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Run an action inside its tracing span, measuring it for its receipt.
+    fn action(
+        &self,
+        span: Span,
+        duration: Duration,
+        action: impl FnOnce() -> InputResult,
+    ) -> InputResult {
+        trace::action(span, || receipt::measure(duration, || None, action))
     }
 }
 
@@ -19,8 +122,76 @@ impl Clone for TouchDevice {
     fn clone(&self) -> Self {
         Self {
             backend: Arc::clone(&self.backend),
+            held: Arc::clone(&self.held),
+            failsafe: Arc::clone(&self.failsafe),
+            cancel: self.cancel.clone(),
         }
     }
 }
 
-impl TouchHandler for TouchDevice {}
+#[cfg(feature = "python")]
+#[pymethods]
+impl TouchDevice {
+    fn down(&self, id: u32, x: i32, y: i32) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| TouchHandler::down(self, id, x, y))
+    }
+
+    fn move_to(&self, id: u32, x: i32, y: i32) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| TouchHandler::move_to(self, id, x, y))
+    }
+
+    fn up(&self, id: u32) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| TouchHandler::up(self, id))
+    }
+
+    fn tap(&self, id: u32, x: i32, y: i32) -> PyResult<Option<Receipt>> {
+        receipt::py_measured(|| TouchHandler::tap(self, id, x, y))
+    }
+
+    /// Lift all touch contacts that were put down and not yet lifted.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+}
+
+impl TouchHandler for TouchDevice {
+    fn down(&self, id: u32, x: i32, y: i32) -> InputResult {
+        let span = info_span!("action", device = "touch", action = "down", id, x, y);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.down(id, x, y)?;
+            self.held.insert(id);
+            Ok(())
+        })
+    }
+
+    fn move_to(&self, id: u32, x: i32, y: i32) -> InputResult {
+        let span = info_span!("action", device = "touch", action = "move_to", id, x, y);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.move_to(id, x, y)
+        })
+    }
+
+    fn up(&self, id: u32) -> InputResult {
+        let span = info_span!("action", device = "touch", action = "up", id);
+        self.action(span, Duration::ZERO, || {
+            self.backend.up(id)?;
+            self.held.remove(id);
+            Ok(())
+        })
+    }
+
+    fn tap(&self, id: u32, x: i32, y: i32) -> InputResult {
+        let span = info_span!("action", device = "touch", action = "tap", id, x, y);
+        self.action(span, Duration::ZERO, || {
+            self.check_stopped()?;
+            self.backend.tap(id, x, y)
+        })
+    }
+
+    fn active_contacts(&self) -> Result<Vec<u32>, InputError> {
+        self.backend.active_contacts()
+    }
+}
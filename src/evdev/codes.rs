@@ -0,0 +1,183 @@
+//! Translation between evdev key codes and the library's keys and buttons.
+//!
+//! Character keys are mapped according to their position on a US QWERTY keyboard.
+use crate::device::{gamepad::GamepadButton, keyboard::KeyboardKey, mouse::MouseButton};
+
+pub const BTN_LEFT: u16 = 0x110;
+pub const BTN_RIGHT: u16 = 0x111;
+pub const BTN_MIDDLE: u16 = 0x112;
+
+// gamepad buttons, as reported by the xpad driver for Xbox controllers
+pub const BTN_A: u16 = 0x130;
+pub const BTN_B: u16 = 0x131;
+pub const BTN_X: u16 = 0x133;
+pub const BTN_Y: u16 = 0x134;
+pub const BTN_TL: u16 = 0x136;
+pub const BTN_TR: u16 = 0x137;
+pub const BTN_SELECT: u16 = 0x13a;
+pub const BTN_START: u16 = 0x13b;
+pub const BTN_MODE: u16 = 0x13c;
+pub const BTN_THUMBL: u16 = 0x13d;
+pub const BTN_THUMBR: u16 = 0x13e;
+
+// pen tablet tools and buttons
+pub const BTN_TOOL_PEN: u16 = 0x140;
+pub const BTN_TOOL_RUBBER: u16 = 0x141;
+pub const BTN_TOUCH: u16 = 0x14a;
+pub const BTN_STYLUS: u16 = 0x14b;
+pub const BTN_STYLUS2: u16 = 0x14c;
+
+/// evdev codes of the character keys, paired with the (unshifted) character they produce.
+const CHARACTER_KEYS: &[(u16, char)] = &[
+    (2, '1'),
+    (3, '2'),
+    (4, '3'),
+    (5, '4'),
+    (6, '5'),
+    (7, '6'),
+    (8, '7'),
+    (9, '8'),
+    (10, '9'),
+    (11, '0'),
+    (12, '-'),
+    (13, '='),
+    (16, 'q'),
+    (17, 'w'),
+    (18, 'e'),
+    (19, 'r'),
+    (20, 't'),
+    (21, 'y'),
+    (22, 'u'),
+    (23, 'i'),
+    (24, 'o'),
+    (25, 'p'),
+    (26, '['),
+    (27, ']'),
+    (30, 'a'),
+    (31, 's'),
+    (32, 'd'),
+    (33, 'f'),
+    (34, 'g'),
+    (35, 'h'),
+    (36, 'j'),
+    (37, 'k'),
+    (38, 'l'),
+    (39, ';'),
+    (40, '\''),
+    (41, '`'),
+    (43, '\\'),
+    (44, 'z'),
+    (45, 'x'),
+    (46, 'c'),
+    (47, 'v'),
+    (48, 'b'),
+    (49, 'n'),
+    (50, 'm'),
+    (51, ','),
+    (52, '.'),
+    (53, '/'),
+    (57, ' '),
+];
+
+/// Get the key for an evdev key code, `None` if the key is not supported.
+pub fn key_from_code(code: u16) -> Option<KeyboardKey> {
+    let key = match code {
+        1 => KeyboardKey::Escape,
+        14 => KeyboardKey::Backspace,
+        15 => KeyboardKey::Tab,
+        28 | 96 => KeyboardKey::Enter,
+        29 | 97 => KeyboardKey::Control,
+        42 | 54 => KeyboardKey::Shift,
+        56 | 100 => KeyboardKey::Alt,
+        102 => KeyboardKey::Home,
+        103 => KeyboardKey::ArrowUp,
+        104 => KeyboardKey::PageUp,
+        105 => KeyboardKey::ArrowLeft,
+        106 => KeyboardKey::ArrowRight,
+        107 => KeyboardKey::End,
+        108 => KeyboardKey::ArrowDown,
+        109 => KeyboardKey::PageDown,
+        _ => {
+            let (_, c) = CHARACTER_KEYS.iter().find(|(c, _)| *c == code)?;
+            KeyboardKey::Unicode(*c)
+        }
+    };
+    Some(key)
+}
+
+/// Get the evdev key code for a key, `None` if the key has no code.
+pub fn key_to_code(key: KeyboardKey) -> Option<u16> {
+    let code = match key {
+        KeyboardKey::Escape => 1,
+        KeyboardKey::Backspace => 14,
+        KeyboardKey::Tab => 15,
+        KeyboardKey::Enter => 28,
+        KeyboardKey::Control => 29,
+        KeyboardKey::Shift => 42,
+        KeyboardKey::Alt => 56,
+        KeyboardKey::Home => 102,
+        KeyboardKey::ArrowUp => 103,
+        KeyboardKey::PageUp => 104,
+        KeyboardKey::ArrowLeft => 105,
+        KeyboardKey::ArrowRight => 106,
+        KeyboardKey::End => 107,
+        KeyboardKey::ArrowDown => 108,
+        KeyboardKey::PageDown => 109,
+        KeyboardKey::Physical(key) => key.evdev(),
+        KeyboardKey::Unicode(c) => {
+            let c = c.to_ascii_lowercase();
+            let (code, _) = CHARACTER_KEYS.iter().find(|(_, k)| *k == c)?;
+            *code
+        }
+    };
+    Some(code)
+}
+
+/// Get the mouse button for an evdev button code, `None` if the button is not supported.
+pub fn button_from_code(code: u16) -> Option<MouseButton> {
+    match code {
+        BTN_LEFT => Some(MouseButton::Left),
+        BTN_RIGHT => Some(MouseButton::Right),
+        BTN_MIDDLE => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+/// Get the evdev button code for a mouse button.
+pub fn button_to_code(button: MouseButton) -> u16 {
+    match button {
+        MouseButton::Left => BTN_LEFT,
+        MouseButton::Right => BTN_RIGHT,
+        MouseButton::Middle => BTN_MIDDLE,
+    }
+}
+
+/// Get the evdev button code for a gamepad button, `None` for the d-pad which is reported as the
+/// `ABS_HAT0X` and `ABS_HAT0Y` axes.
+pub fn gamepad_button_to_code(button: GamepadButton) -> Option<u16> {
+    let code = match button {
+        GamepadButton::A => BTN_A,
+        GamepadButton::B => BTN_B,
+        GamepadButton::X => BTN_X,
+        GamepadButton::Y => BTN_Y,
+        GamepadButton::LeftBumper => BTN_TL,
+        GamepadButton::RightBumper => BTN_TR,
+        GamepadButton::Back => BTN_SELECT,
+        GamepadButton::Start => BTN_START,
+        GamepadButton::Guide => BTN_MODE,
+        GamepadButton::LeftThumb => BTN_THUMBL,
+        GamepadButton::RightThumb => BTN_THUMBR,
+        GamepadButton::DpadUp
+        | GamepadButton::DpadDown
+        | GamepadButton::DpadLeft
+        | GamepadButton::DpadRight => return None,
+    };
+    Some(code)
+}
+
+/// Get the gamepad button for an evdev button code, `None` if the code is not a gamepad button.
+pub fn gamepad_button_from_code(code: u16) -> Option<GamepadButton> {
+    GamepadButton::ALL
+        .into_iter()
+        .find(|button| gamepad_button_to_code(*button) == Some(code))
+}
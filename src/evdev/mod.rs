@@ -0,0 +1,118 @@
+//! Reading and writing of raw Linux `input_event` records.
+//!
+//! The layout matches `struct input_event` on 64-bit Linux, it is used both for
+//! `/dev/input/event*` devices and for recorded event stream files.
+pub mod codes;
+#[cfg(target_os = "linux")]
+pub mod monitor;
+
+use std::io::{ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Size in bytes of a single `input_event` record.
+pub const EVENT_SIZE: usize = 24;
+
+pub const EV_SYN: u16 = 0x00;
+pub const EV_KEY: u16 = 0x01;
+pub const EV_REL: u16 = 0x02;
+pub const EV_ABS: u16 = 0x03;
+
+pub const SYN_REPORT: u16 = 0x00;
+
+pub const REL_X: u16 = 0x00;
+pub const REL_Y: u16 = 0x01;
+pub const REL_HWHEEL: u16 = 0x06;
+pub const REL_WHEEL: u16 = 0x08;
+
+pub const ABS_X: u16 = 0x00;
+pub const ABS_Y: u16 = 0x01;
+pub const ABS_Z: u16 = 0x02;
+pub const ABS_RX: u16 = 0x03;
+pub const ABS_RY: u16 = 0x04;
+pub const ABS_RZ: u16 = 0x05;
+pub const ABS_HAT0X: u16 = 0x10;
+pub const ABS_HAT0Y: u16 = 0x11;
+pub const ABS_PRESSURE: u16 = 0x18;
+pub const ABS_TILT_X: u16 = 0x1a;
+pub const ABS_TILT_Y: u16 = 0x1b;
+
+/// Find the devices listed in `/dev/input/by-id` and `/dev/input/by-path` whose name ends with
+/// one of `suffixes`, e.g. `"-event-kbd"` or `"-event-mouse"`.
+pub fn discover(suffixes: &[&str]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for dir in ["/dev/input/by-id", "/dev/input/by-path"] {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !suffixes.iter().any(|suffix| name.ends_with(suffix)) {
+                continue;
+            }
+            // the same device is usually listed in both directories
+            if let Ok(path) = std::fs::canonicalize(entry.path()) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+    }
+    paths
+}
+
+/// A single raw input event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawEvent {
+    /// Timestamp of the event (time since the unix epoch for real devices).
+    pub time: Duration,
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl RawEvent {
+    pub fn new(kind: u16, code: u16, value: i32) -> Self {
+        Self {
+            time: Duration::ZERO,
+            kind,
+            code,
+            value,
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8; EVENT_SIZE]) -> Self {
+        let sec = i64::from_ne_bytes(bytes[0..8].try_into().unwrap());
+        let usec = i64::from_ne_bytes(bytes[8..16].try_into().unwrap());
+        Self {
+            time: Duration::new(sec.max(0) as u64, (usec.clamp(0, 999_999) * 1000) as u32),
+            kind: u16::from_ne_bytes(bytes[16..18].try_into().unwrap()),
+            code: u16::from_ne_bytes(bytes[18..20].try_into().unwrap()),
+            value: i32::from_ne_bytes(bytes[20..24].try_into().unwrap()),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; EVENT_SIZE] {
+        let mut bytes = [0u8; EVENT_SIZE];
+        bytes[0..8].copy_from_slice(&(self.time.as_secs() as i64).to_ne_bytes());
+        bytes[8..16].copy_from_slice(&(self.time.subsec_micros() as i64).to_ne_bytes());
+        bytes[16..18].copy_from_slice(&self.kind.to_ne_bytes());
+        bytes[18..20].copy_from_slice(&self.code.to_ne_bytes());
+        bytes[20..24].copy_from_slice(&self.value.to_ne_bytes());
+        bytes
+    }
+
+    /// Read the next event, returns `None` at the end of the stream.
+    pub fn read_from(reader: &mut impl Read) -> std::io::Result<Option<Self>> {
+        let mut bytes = [0u8; EVENT_SIZE];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => Ok(Some(Self::from_bytes(&bytes))),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+}
@@ -0,0 +1,80 @@
+//! Monitoring of physical keyboards, used to detect when the user presses a key.
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::core::result::InputError;
+
+use super::{discover, RawEvent, EV_KEY};
+
+/// Tracks key presses on all physical keyboards.
+///
+/// Input simulated by a backend does not go through the physical devices, so anything
+/// seen here was done by the user. Reading the devices usually requires root or
+/// membership of the `input` group.
+#[derive(Debug, Default)]
+pub struct KeyMonitor {
+    presses: AtomicU64,
+    pressed: Mutex<Vec<u16>>,
+}
+
+impl KeyMonitor {
+    /// The shared monitor, the keyboards are opened the first time this is called.
+    pub fn global() -> Result<Arc<KeyMonitor>, InputError> {
+        static MONITOR: OnceLock<Result<Arc<KeyMonitor>, String>> = OnceLock::new();
+        MONITOR
+            .get_or_init(|| Self::start(&discover(&["-event-kbd"])).map_err(|e| e.to_string()))
+            .clone()
+            .map_err(InputError::error)
+    }
+
+    /// Start monitoring the given evdev devices on background threads.
+    pub fn start(paths: &[PathBuf]) -> Result<Arc<KeyMonitor>, InputError> {
+        if paths.is_empty() {
+            return Err(InputError::error(
+                "No physical keyboards were found to monitor.".to_string(),
+            ));
+        }
+        let monitor = Arc::new(KeyMonitor::default());
+        for path in paths {
+            let mut file = File::open(path).map_err(|e| {
+                InputError::error(format!("Failed to open {}: {}", path.display(), e))
+            })?;
+            let monitor = Arc::clone(&monitor);
+            std::thread::spawn(move || {
+                while let Ok(Some(event)) = RawEvent::read_from(&mut file) {
+                    monitor.update(&event);
+                }
+            });
+        }
+        Ok(monitor)
+    }
+
+    fn update(&self, event: &RawEvent) {
+        if event.kind != EV_KEY {
+            return;
+        }
+        let mut pressed = self.pressed.lock().unwrap();
+        match event.value {
+            1 => {
+                self.presses.fetch_add(1, Ordering::SeqCst);
+                if !pressed.contains(&event.code) {
+                    pressed.push(event.code);
+                }
+            }
+            0 => pressed.retain(|code| *code != event.code),
+            _ => {}
+        }
+    }
+
+    /// The number of key presses seen since monitoring started.
+    pub fn presses(&self) -> u64 {
+        self.presses.load(Ordering::SeqCst)
+    }
+
+    /// The evdev codes of the keys that are currently held down.
+    pub fn pressed(&self) -> Vec<u16> {
+        self.pressed.lock().unwrap().clone()
+    }
+}
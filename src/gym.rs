@@ -0,0 +1,431 @@
+//! Gymnasium action spaces for agents that act through a session.
+//!
+//! [`ActionSpace`] describes a session's devices as a `gymnasium.spaces.Dict` and applies actions
+//! sampled from it, one batch of device calls per environment step. Gymnasium is imported when
+//! the space is first built, applying actions does not need it.
+use std::time::Duration;
+
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict};
+
+use crate::core::result::{InputError, InputResult};
+use crate::core::{GamepadHandler, KeyboardHandler, MouseHandler};
+use crate::device::gamepad::{GamepadButton, GamepadDevice, Stick, Trigger};
+use crate::device::keyboard::{KeyboardDevice, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseDevice};
+use crate::session::Session;
+
+/// The inputs that are held because of earlier actions, indexed like the spaces.
+#[derive(Default)]
+struct Held {
+    keys: Vec<bool>,
+    mouse_buttons: Vec<bool>,
+    gamepad_buttons: Vec<bool>,
+}
+
+/// One step's action for an [`ActionSpace`], entries that are `None` change nothing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Step {
+    /// The key to tap, 0 taps nothing and `i` taps `keys[i - 1]`.
+    pub key: Option<usize>,
+    /// Whether to hold each of the keys.
+    pub held_keys: Option<Vec<bool>>,
+    /// Whether to hold each of the mouse buttons.
+    pub mouse_buttons: Option<Vec<bool>>,
+    /// How far to move the cursor in pixels, clamped to the space's `max_delta`.
+    pub cursor: Option<(f64, f64)>,
+    /// Whether to hold each of the gamepad buttons.
+    pub gamepad_buttons: Option<Vec<bool>>,
+    /// The left and right stick positions.
+    pub sticks: Option<[(f64, f64); 2]>,
+    /// The left and right trigger values.
+    pub triggers: Option<[f64; 2]>,
+}
+
+/// The devices of a session as a Gymnasium action space.
+///
+/// The space is a `Dict` of:
+///
+/// - `"key"`: `Discrete(len(keys) + 1)`, a key to tap this step, 0 taps nothing. Held keys are
+///   not tapped.
+/// - `"held_keys"`: `MultiBinary(len(keys))`, the keys to hold down.
+/// - `"mouse_buttons"`: `MultiBinary(len(mouse_buttons))`, the mouse buttons to hold down.
+/// - `"cursor"`: `Box(-max_delta, max_delta, (2,))`, how far to move the cursor in pixels.
+///
+/// and with `gamepad=True`:
+///
+/// - `"gamepad_buttons"`: `MultiBinary(len(gamepad_buttons))`, the gamepad buttons to hold down.
+/// - `"sticks"`: `Box(-1, 1, (2, 2))`, the left and right stick positions.
+/// - `"triggers"`: `Box(0, 1, (2,))`, the left and right trigger values.
+///
+/// Held keys and buttons stay down across steps until an action releases them.
+#[pyclass]
+pub struct ActionSpace {
+    keyboard: KeyboardDevice,
+    mouse: MouseDevice,
+    gamepad: Option<GamepadDevice>,
+    keys: Vec<KeyboardKey>,
+    mouse_buttons: Vec<MouseButton>,
+    gamepad_buttons: Vec<GamepadButton>,
+    max_delta: f64,
+    held: Held,
+}
+
+impl ActionSpace {
+    /// Describe `session`'s devices as an action space, with `keys` (by default
+    /// [`KeyboardKey::common`]) and the gamepad if `gamepad` is true.
+    pub fn new(
+        session: &Session,
+        keys: Option<Vec<KeyboardKey>>,
+        max_delta: f64,
+        gamepad: bool,
+    ) -> Result<Self, InputError> {
+        let capabilities = session.capabilities()?;
+        let gamepad = match gamepad {
+            true => Some(
+                session
+                    .gamepad()
+                    .cloned()
+                    .ok_or_else(|| InputError::error("The session has no gamepad.".to_string()))?,
+            ),
+            false => None,
+        };
+        let keys = keys.unwrap_or(capabilities.keys);
+        let gamepad_buttons = match gamepad {
+            Some(_) => capabilities.gamepad_buttons,
+            None => Vec::new(),
+        };
+        let held = Held {
+            keys: vec![false; keys.len()],
+            mouse_buttons: vec![false; capabilities.mouse_buttons.len()],
+            gamepad_buttons: vec![false; gamepad_buttons.len()],
+        };
+        Ok(Self {
+            keyboard: session.keyboard().clone(),
+            mouse: session.mouse().clone(),
+            gamepad,
+            keys,
+            mouse_buttons: capabilities.mouse_buttons,
+            gamepad_buttons,
+            max_delta,
+            held,
+        })
+    }
+
+    /// Perform one step's action, see the python `apply`.
+    pub fn step(&mut self, step: &Step) -> InputResult {
+        let keyboard = &self.keyboard;
+        let mouse = &self.mouse;
+        if let Some(wanted) = &step.held_keys {
+            Self::update(
+                &self.keys,
+                &mut self.held.keys,
+                wanted,
+                "held_keys",
+                |key| keyboard.press(key),
+                |key| keyboard.release(key),
+            )?;
+        }
+        if let Some(wanted) = &step.mouse_buttons {
+            Self::update(
+                &self.mouse_buttons,
+                &mut self.held.mouse_buttons,
+                wanted,
+                "mouse_buttons",
+                |button| mouse.press(button),
+                |button| mouse.release(button),
+            )?;
+        }
+        if let Some((dx, dy)) = step.cursor {
+            let [dx, dy] =
+                [dx, dy].map(|d| d.clamp(-self.max_delta, self.max_delta).round() as i32);
+            if (dx, dy) != (0, 0) {
+                mouse.move_rel(dx, dy, Duration::ZERO)?;
+            }
+        }
+        if let Some(index) = step.key {
+            if index > self.keys.len() {
+                return Err(InputError::error(format!(
+                    "Key {} is outside of the space of {} keys",
+                    index,
+                    self.keys.len()
+                )));
+            }
+            // tapping a held key would release it while it is still meant to be held
+            if index > 0 && !self.held.keys[index - 1] {
+                keyboard.tap(self.keys[index - 1])?;
+            }
+        }
+        let Some(gamepad) = &self.gamepad else {
+            return Ok(());
+        };
+        if let Some(wanted) = &step.gamepad_buttons {
+            Self::update(
+                &self.gamepad_buttons,
+                &mut self.held.gamepad_buttons,
+                wanted,
+                "gamepad_buttons",
+                |button| gamepad.press(button),
+                |button| gamepad.release(button),
+            )?;
+        }
+        if let Some(sticks) = step.sticks {
+            for (stick, (x, y)) in [Stick::Left, Stick::Right].into_iter().zip(sticks) {
+                gamepad.move_stick(stick, x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0))?;
+            }
+        }
+        if let Some(triggers) = step.triggers {
+            for (trigger, value) in [Trigger::Left, Trigger::Right].into_iter().zip(triggers) {
+                gamepad.set_trigger(trigger, value.clamp(0.0, 1.0))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Release everything held by earlier steps and centre the gamepad.
+    pub fn release_all(&mut self) -> InputResult {
+        let wanted = vec![false; self.keys.len()];
+        let keyboard = &self.keyboard;
+        let keys = Self::update(
+            &self.keys,
+            &mut self.held.keys,
+            &wanted,
+            "held_keys",
+            |key| keyboard.press(key),
+            |key| keyboard.release(key),
+        );
+        let wanted = vec![false; self.mouse_buttons.len()];
+        let mouse = &self.mouse;
+        let buttons = Self::update(
+            &self.mouse_buttons,
+            &mut self.held.mouse_buttons,
+            &wanted,
+            "mouse_buttons",
+            |button| mouse.press(button),
+            |button| mouse.release(button),
+        );
+        let gamepad = match &self.gamepad {
+            Some(gamepad) => {
+                let wanted = vec![false; self.gamepad_buttons.len()];
+                Self::update(
+                    &self.gamepad_buttons,
+                    &mut self.held.gamepad_buttons,
+                    &wanted,
+                    "gamepad_buttons",
+                    |button| gamepad.press(button),
+                    |button| gamepad.release(button),
+                )
+                .and(gamepad.release_all())
+            }
+            None => Ok(()),
+        };
+        keys.and(buttons).and(gamepad)
+    }
+
+    /// Release the inputs whose entry went from 1 to 0 and press those that went from 0 to 1.
+    fn update<T: Copy>(
+        inputs: &[T],
+        held: &mut [bool],
+        wanted: &[bool],
+        name: &str,
+        press: impl Fn(T) -> InputResult,
+        release: impl Fn(T) -> InputResult,
+    ) -> InputResult {
+        if wanted.len() != inputs.len() {
+            return Err(InputError::error(format!(
+                "{:?} has {} values, the space has {}",
+                name,
+                wanted.len(),
+                inputs.len()
+            )));
+        }
+        // releases go first, so that swapping a held modifier does not combine both
+        for (i, input) in inputs.iter().enumerate() {
+            if held[i] && !wanted[i] {
+                release(*input)?;
+                held[i] = false;
+            }
+        }
+        for (i, input) in inputs.iter().enumerate() {
+            if !held[i] && wanted[i] {
+                press(*input)?;
+                held[i] = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entry of the action, `None` if the action leaves it out.
+fn entry<'py>(action: &Bound<'py, PyAny>, name: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+    match action.get_item(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_instance_of::<PyKeyError>(action.py()) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The values of a sampled array (or any iterable), flattened.
+fn values(array: &Bound<'_, PyAny>, len: usize, name: &str) -> PyResult<Vec<f64>> {
+    let mut values = Vec::new();
+    for item in array.iter()? {
+        let item = item?;
+        match item.extract::<f64>() {
+            Ok(value) => values.push(value),
+            // a row of a two dimensional array
+            Err(_) => {
+                for value in item.iter()? {
+                    values.push(value?.extract()?);
+                }
+            }
+        }
+    }
+    if values.len() != len {
+        return Err(PyValueError::new_err(format!(
+            "{:?} has {} values, the space has {}",
+            name,
+            values.len(),
+            len
+        )));
+    }
+    Ok(values)
+}
+
+#[pymethods]
+impl ActionSpace {
+    /// Describe a session's devices as an action space.
+    ///
+    /// Args:
+    ///     session: The session the actions are applied to.
+    ///     keys: The keys the agent can tap and hold, by default those of
+    ///         `session.capabilities()` (the named keys, letters and digits).
+    ///     max_delta: The largest cursor movement per step, in pixels.
+    ///     gamepad: Include the session's gamepad.
+    ///
+    /// Raises:
+    ///     ValueError: If `gamepad` is true and the session has no gamepad.
+    #[new]
+    #[pyo3(signature = (session, keys=None, max_delta=100.0, gamepad=false))]
+    fn py_new(
+        session: &Session,
+        keys: Option<Vec<KeyboardKey>>,
+        max_delta: f64,
+        gamepad: bool,
+    ) -> PyResult<Self> {
+        Self::new(session, keys, max_delta, gamepad).map_err(|e| e.into_py())
+    }
+
+    /// The `gymnasium.spaces.Dict` to use as an environment's `action_space`.
+    ///
+    /// Raises:
+    ///     ImportError: If gymnasium is not installed.
+    #[getter]
+    fn space(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let spaces = py.import_bound("gymnasium.spaces")?;
+        let discrete = spaces.getattr("Discrete")?;
+        let multi_binary = spaces.getattr("MultiBinary")?;
+        let float_box = |low: f64, high: f64, shape: Vec<usize>| {
+            let kwargs = [
+                ("shape", shape.into_py(py)),
+                ("dtype", "float32".into_py(py)),
+            ]
+            .into_py_dict_bound(py);
+            spaces.getattr("Box")?.call((low, high), Some(&kwargs))
+        };
+        let entries = PyDict::new_bound(py);
+        entries.set_item("key", discrete.call1((self.keys.len() + 1,))?)?;
+        entries.set_item("held_keys", multi_binary.call1((self.keys.len(),))?)?;
+        entries.set_item(
+            "mouse_buttons",
+            multi_binary.call1((self.mouse_buttons.len(),))?,
+        )?;
+        entries.set_item(
+            "cursor",
+            float_box(-self.max_delta, self.max_delta, vec![2])?,
+        )?;
+        if self.gamepad.is_some() {
+            entries.set_item(
+                "gamepad_buttons",
+                multi_binary.call1((self.gamepad_buttons.len(),))?,
+            )?;
+            entries.set_item("sticks", float_box(-1.0, 1.0, vec![2, 2])?)?;
+            entries.set_item("triggers", float_box(0.0, 1.0, vec![2])?)?;
+        }
+        Ok(spaces.getattr("Dict")?.call1((entries,))?.unbind())
+    }
+
+    /// The keys of the `"key"` and `"held_keys"` entries, `"key"` value `i` taps `keys[i - 1]`.
+    #[getter]
+    fn keys(&self) -> Vec<String> {
+        self.keys.iter().map(KeyboardKey::to_string).collect()
+    }
+
+    /// The buttons of the `"mouse_buttons"` entry.
+    #[getter]
+    fn mouse_buttons(&self) -> Vec<String> {
+        self.mouse_buttons
+            .iter()
+            .map(MouseButton::to_string)
+            .collect()
+    }
+
+    /// The buttons of the `"gamepad_buttons"` entry, empty without the gamepad.
+    #[getter]
+    fn gamepad_buttons(&self) -> Vec<String> {
+        self.gamepad_buttons
+            .iter()
+            .map(GamepadButton::to_string)
+            .collect()
+    }
+
+    /// Perform one step's action.
+    ///
+    /// Keys and buttons are released or pressed to match the held entries, then the cursor is
+    /// moved, the key is tapped (unless it is held) and the gamepad's sticks and triggers are set.
+    /// Entries left out of the action change nothing.
+    ///
+    /// Args:
+    ///     action: A sample of `space`, or a dict with some of its entries.
+    ///
+    /// Raises:
+    ///     ValueError: If an entry does not match the space.
+    ///
+    /// Example:
+    ///     actions = ActionSpace(session)
+    ///     actions.apply(actions.space.sample())
+    fn apply(&mut self, action: &Bound<'_, PyAny>) -> PyResult<()> {
+        let binary = |name: &str, len: usize| -> PyResult<Option<Vec<bool>>> {
+            let Some(array) = entry(action, name)? else {
+                return Ok(None);
+            };
+            let values = values(&array, len, name)?;
+            Ok(Some(values.into_iter().map(|value| value != 0.0).collect()))
+        };
+        let numbers = |name: &str, len: usize| -> PyResult<Option<Vec<f64>>> {
+            entry(action, name)?
+                .map(|array| values(&array, len, name))
+                .transpose()
+        };
+        let mut step = Step {
+            key: entry(action, "key")?.map(|key| key.extract()).transpose()?,
+            held_keys: binary("held_keys", self.keys.len())?,
+            mouse_buttons: binary("mouse_buttons", self.mouse_buttons.len())?,
+            cursor: numbers("cursor", 2)?.map(|delta| (delta[0], delta[1])),
+            ..Step::default()
+        };
+        if self.gamepad.is_some() {
+            step.gamepad_buttons = binary("gamepad_buttons", self.gamepad_buttons.len())?;
+            step.sticks = numbers("sticks", 4)?.map(|s| [(s[0], s[1]), (s[2], s[3])]);
+            step.triggers = numbers("triggers", 2)?.map(|t| [t[0], t[1]]);
+        }
+        self.step(&step).map_err(|e| e.into_py())
+    }
+
+    /// Release everything held by earlier actions and centre the gamepad, e.g. when an episode
+    /// ends.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&mut self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+}
@@ -0,0 +1,288 @@
+//! Keyboard layouts, translating characters into the physical keys that type them.
+//!
+//! Backends that emit key codes rather than characters need to know that `'@'` is Shift+2 on a
+//! US keyboard but AltGr+Q on a German one. Keys are identified by their evdev code, which is
+//! the same for a physical key position whatever the layout.
+pub mod physical;
+pub mod tables;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+#[cfg(feature = "python")]
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::Deserialize;
+
+use crate::core::result::InputError;
+use physical::PhysicalKey;
+
+/// The names of the bundled layouts, see [`Layout::bundled`].
+pub const LAYOUTS: &[&str] = &["us", "uk", "de", "fr", "dvorak"];
+
+/// evdev code of the left Shift key.
+pub const KEY_LEFTSHIFT: u16 = 42;
+/// evdev code of the right Alt key, which is AltGr on layouts that have one.
+pub const KEY_RIGHTALT: u16 = 100;
+
+/// The character keys by row, each row is shifted right of the one above it.
+const ROWS: [&[u16]; 4] = [
+    &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+    &[16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27],
+    &[30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40],
+    &[44, 45, 46, 47, 48, 49, 50, 51, 52, 53],
+];
+
+/// Keys that type the same character on every layout.
+const COMMON: &[(u16, char)] = &[(57, ' '), (28, '\n'), (15, '\t')];
+
+/// A physical key and the modifiers that are held while it is pressed to type a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStroke {
+    /// evdev code of the key.
+    pub code: u16,
+    pub shift: bool,
+    pub altgr: bool,
+}
+
+impl KeyStroke {
+    pub fn key(&self) -> PhysicalKey {
+        PhysicalKey::from_evdev(self.code)
+    }
+
+    /// evdev codes of the modifiers, in the order they are pressed.
+    pub fn modifiers(&self) -> Vec<u16> {
+        let mut modifiers = Vec::new();
+        if self.shift {
+            modifiers.push(KEY_LEFTSHIFT);
+        }
+        if self.altgr {
+            modifiers.push(KEY_RIGHTALT);
+        }
+        modifiers
+    }
+}
+
+/// A layout file, a JSON object such as
+/// `{"name": "custom", "keys": [[16, "qQ@"], [17, "wW"]]}`.
+///
+/// Each key is an evdev code and the characters it types without modifiers, with Shift, with AltGr
+/// and with Shift+AltGr, a space means nothing is typed at that level.
+#[derive(Deserialize)]
+struct LayoutFile {
+    name: String,
+    keys: Vec<(u16, String)>,
+}
+
+/// A keyboard layout, translating characters into the physical keys that type them.
+///
+/// Keys are identified by their evdev code, the modifiers are Shift (42) and AltGr (100).
+#[cfg_attr(feature = "python", pyclass(frozen))]
+#[derive(Debug, Clone)]
+pub struct Layout {
+    name: String,
+    // the characters typed by each key, by level
+    keys: HashMap<u16, Vec<Option<char>>>,
+    strokes: HashMap<char, KeyStroke>,
+}
+
+impl Layout {
+    /// One of the bundled layouts, see [`LAYOUTS`].
+    pub fn bundled(name: &str) -> Option<Self> {
+        let keys = match name.to_lowercase().as_str() {
+            "us" | "en-us" => tables::US,
+            "uk" | "gb" | "en-gb" => tables::UK,
+            "de" | "de-de" => tables::DE,
+            "fr" | "fr-fr" => tables::FR,
+            "dvorak" => tables::DVORAK,
+            _ => return None,
+        };
+        let keys = keys.iter().map(|(code, levels)| (*code, *levels));
+        Some(Self::new(name.to_lowercase(), keys))
+    }
+
+    /// A layout from the characters typed by each key at each level, see [`tables`].
+    pub fn new<'a>(name: String, keys: impl IntoIterator<Item = (u16, &'a str)>) -> Self {
+        let mut layout = Self {
+            name,
+            keys: HashMap::new(),
+            strokes: HashMap::new(),
+        };
+        for &(code, c) in COMMON {
+            layout.keys.insert(code, vec![Some(c)]);
+            layout.strokes.insert(c, Self::stroke_at(code, 0));
+        }
+        for (code, levels) in keys {
+            let levels: Vec<Option<char>> =
+                levels.chars().map(|c| (c != ' ').then_some(c)).collect();
+            for (level, c) in levels.iter().enumerate() {
+                // the lowest level is the simplest way to type a character
+                if let Some(c) = c {
+                    layout
+                        .strokes
+                        .entry(*c)
+                        .or_insert(Self::stroke_at(code, level));
+                }
+            }
+            layout.keys.insert(code, levels);
+        }
+        layout
+    }
+
+    /// Load a custom layout from a JSON file such as `{"name": "custom", "keys": [[16, "qQ@"]]}`,
+    /// with the same levels as the bundled [`tables`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let file = File::open(path)?;
+        let file: LayoutFile = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| InputError::error(e.to_string()))?;
+        if let Some((code, levels)) = file.keys.iter().find(|(_, l)| l.chars().count() > 4) {
+            return Err(InputError::error(format!(
+                "Key {} has more than 4 levels: {:?}",
+                code, levels
+            )));
+        }
+        let keys = file
+            .keys
+            .iter()
+            .map(|(code, levels)| (*code, levels.as_str()));
+        Ok(Self::new(file.name.clone(), keys))
+    }
+
+    fn stroke_at(code: u16, level: usize) -> KeyStroke {
+        KeyStroke {
+            code,
+            shift: level % 2 == 1,
+            altgr: level >= 2,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The key that types `c`, `None` if the layout can not type it.
+    pub fn stroke(&self, c: char) -> Option<KeyStroke> {
+        self.strokes.get(&c).copied()
+    }
+
+    /// The character typed by a key with the given modifiers, `None` if it types nothing.
+    pub fn char(&self, stroke: KeyStroke) -> Option<char> {
+        let level = stroke.shift as usize + 2 * stroke.altgr as usize;
+        *self.keys.get(&stroke.code)?.get(level)?
+    }
+
+    /// The characters of `text` that the layout can not type, each listed once.
+    pub fn untypeable(&self, text: &str) -> Vec<char> {
+        let mut untypeable: Vec<char> = Vec::new();
+        for c in text.chars() {
+            if !self.strokes.contains_key(&c) && !untypeable.contains(&c) {
+                untypeable.push(c);
+            }
+        }
+        untypeable
+    }
+
+    /// The keys that type `text`, or an error listing the characters the layout can not type.
+    pub fn translate(&self, text: &str) -> Result<Vec<KeyStroke>, InputError> {
+        let untypeable = self.untypeable(text);
+        if !untypeable.is_empty() {
+            let chars: Vec<String> = untypeable.iter().map(|c| format!("{:?}", c)).collect();
+            return Err(InputError::error(format!(
+                "The '{}' layout can not type {}.",
+                self.name,
+                chars.join(", ")
+            )));
+        }
+        Ok(text.chars().filter_map(|c| self.stroke(c)).collect())
+    }
+
+    /// The characters typed by the keys around the one that types `c`, with the same modifiers.
+    /// Empty if `c` is not on one of the character rows.
+    pub fn neighbours(&self, c: char) -> Vec<char> {
+        let Some(stroke) = self.stroke(c) else {
+            return Vec::new();
+        };
+        let Some((row, col)) = ROWS
+            .iter()
+            .enumerate()
+            .find_map(|(row, keys)| Some((row, keys.iter().position(|k| *k == stroke.code)?)))
+        else {
+            return Vec::new();
+        };
+        let mut positions = vec![(row, col.wrapping_sub(1)), (row, col + 1)];
+        if row > 0 {
+            positions.extend([(row - 1, col), (row - 1, col + 1)]);
+        }
+        if row + 1 < ROWS.len() {
+            positions.extend([(row + 1, col.wrapping_sub(1)), (row + 1, col)]);
+        }
+        positions
+            .into_iter()
+            .filter_map(|(row, col)| ROWS[row].get(col))
+            .filter_map(|&code| self.char(KeyStroke { code, ..stroke }))
+            .collect()
+    }
+}
+
+impl Default for Layout {
+    /// The US layout.
+    fn default() -> Self {
+        Self::bundled("us").unwrap()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Layout {
+    /// One of the bundled layouts: "us", "uk", "de", "fr" or "dvorak".
+    #[new]
+    #[pyo3(signature = (name="us"))]
+    fn py_new(name: &str) -> PyResult<Self> {
+        Self::bundled(name).ok_or(PyValueError::new_err(format!(
+            "Unknown layout {:?}, expected one of {}",
+            name,
+            LAYOUTS.join(", ")
+        )))
+    }
+
+    /// Load a custom layout from a JSON file.
+    ///
+    /// The file looks like `{"name": "custom", "keys": [[16, "qQ@"], [17, "wW"]]}`, each key is an
+    /// evdev code and the characters it types without modifiers, with Shift, with AltGr and with
+    /// Shift+AltGr. A space means the key types nothing at that level.
+    #[staticmethod]
+    #[pyo3(name = "load")]
+    fn py_load(path: &str) -> PyResult<Self> {
+        Self::load(path).map_err(|e| e.into_py())
+    }
+
+    #[getter(name)]
+    fn py_name(&self) -> &str {
+        &self.name
+    }
+
+    /// The evdev codes of the keys that type `text`, each with the codes of the modifiers to hold.
+    ///
+    /// Raises:
+    ///     ValueError: If the layout can not type some of the characters, they are listed in the
+    ///         message.
+    #[pyo3(name = "translate")]
+    fn py_translate(&self, text: &str) -> PyResult<Vec<(u16, Vec<u16>)>> {
+        let strokes = self.translate(text).map_err(|e| e.into_py())?;
+        Ok(strokes
+            .iter()
+            .map(|stroke| (stroke.code, stroke.modifiers()))
+            .collect())
+    }
+
+    /// The characters of `text` that the layout can not type, each listed once.
+    #[pyo3(name = "untypeable")]
+    fn py_untypeable(&self, text: &str) -> Vec<char> {
+        self.untypeable(text)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Layout({:?})", self.name)
+    }
+}
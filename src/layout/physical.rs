@@ -0,0 +1,227 @@
+//! Physical keys, identified by their position on the keyboard rather than what they type.
+//!
+//! Keys are stored as Linux evdev codes and can be converted to and from USB HID usage IDs
+//! (keyboard page) and X11 keycodes. They are named like the `code` of browser keyboard events,
+//! e.g. `"KeyW"` is the key where W is on a QWERTY keyboard, whatever the layout.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::core::result::InputError;
+
+/// The named keys: name, evdev code and USB HID usage ID.
+const KEYS: &[(&str, u16, u16)] = &[
+    ("Escape", 1, 0x29),
+    ("Digit1", 2, 0x1e),
+    ("Digit2", 3, 0x1f),
+    ("Digit3", 4, 0x20),
+    ("Digit4", 5, 0x21),
+    ("Digit5", 6, 0x22),
+    ("Digit6", 7, 0x23),
+    ("Digit7", 8, 0x24),
+    ("Digit8", 9, 0x25),
+    ("Digit9", 10, 0x26),
+    ("Digit0", 11, 0x27),
+    ("Minus", 12, 0x2d),
+    ("Equal", 13, 0x2e),
+    ("Backspace", 14, 0x2a),
+    ("Tab", 15, 0x2b),
+    ("KeyQ", 16, 0x14),
+    ("KeyW", 17, 0x1a),
+    ("KeyE", 18, 0x08),
+    ("KeyR", 19, 0x15),
+    ("KeyT", 20, 0x17),
+    ("KeyY", 21, 0x1c),
+    ("KeyU", 22, 0x18),
+    ("KeyI", 23, 0x0c),
+    ("KeyO", 24, 0x12),
+    ("KeyP", 25, 0x13),
+    ("BracketLeft", 26, 0x2f),
+    ("BracketRight", 27, 0x30),
+    ("Enter", 28, 0x28),
+    ("ControlLeft", 29, 0xe0),
+    ("KeyA", 30, 0x04),
+    ("KeyS", 31, 0x16),
+    ("KeyD", 32, 0x07),
+    ("KeyF", 33, 0x09),
+    ("KeyG", 34, 0x0a),
+    ("KeyH", 35, 0x0b),
+    ("KeyJ", 36, 0x0d),
+    ("KeyK", 37, 0x0e),
+    ("KeyL", 38, 0x0f),
+    ("Semicolon", 39, 0x33),
+    ("Quote", 40, 0x34),
+    ("Backquote", 41, 0x35),
+    ("ShiftLeft", 42, 0xe1),
+    ("Backslash", 43, 0x31),
+    ("KeyZ", 44, 0x1d),
+    ("KeyX", 45, 0x1b),
+    ("KeyC", 46, 0x06),
+    ("KeyV", 47, 0x19),
+    ("KeyB", 48, 0x05),
+    ("KeyN", 49, 0x11),
+    ("KeyM", 50, 0x10),
+    ("Comma", 51, 0x36),
+    ("Period", 52, 0x37),
+    ("Slash", 53, 0x38),
+    ("ShiftRight", 54, 0xe5),
+    ("NumpadMultiply", 55, 0x55),
+    ("AltLeft", 56, 0xe2),
+    ("Space", 57, 0x2c),
+    ("CapsLock", 58, 0x39),
+    ("F1", 59, 0x3a),
+    ("F2", 60, 0x3b),
+    ("F3", 61, 0x3c),
+    ("F4", 62, 0x3d),
+    ("F5", 63, 0x3e),
+    ("F6", 64, 0x3f),
+    ("F7", 65, 0x40),
+    ("F8", 66, 0x41),
+    ("F9", 67, 0x42),
+    ("F10", 68, 0x43),
+    ("NumLock", 69, 0x53),
+    ("ScrollLock", 70, 0x47),
+    ("Numpad7", 71, 0x5f),
+    ("Numpad8", 72, 0x60),
+    ("Numpad9", 73, 0x61),
+    ("NumpadSubtract", 74, 0x56),
+    ("Numpad4", 75, 0x5c),
+    ("Numpad5", 76, 0x5d),
+    ("Numpad6", 77, 0x5e),
+    ("NumpadAdd", 78, 0x57),
+    ("Numpad1", 79, 0x59),
+    ("Numpad2", 80, 0x5a),
+    ("Numpad3", 81, 0x5b),
+    ("Numpad0", 82, 0x62),
+    ("NumpadDecimal", 83, 0x63),
+    ("IntlBackslash", 86, 0x64),
+    ("F11", 87, 0x44),
+    ("F12", 88, 0x45),
+    ("NumpadEnter", 96, 0x58),
+    ("ControlRight", 97, 0xe4),
+    ("NumpadDivide", 98, 0x54),
+    ("PrintScreen", 99, 0x46),
+    ("AltRight", 100, 0xe6),
+    ("Home", 102, 0x4a),
+    ("ArrowUp", 103, 0x52),
+    ("PageUp", 104, 0x4b),
+    ("ArrowLeft", 105, 0x50),
+    ("ArrowRight", 106, 0x4f),
+    ("End", 107, 0x4d),
+    ("ArrowDown", 108, 0x51),
+    ("PageDown", 109, 0x4e),
+    ("Insert", 110, 0x49),
+    ("Delete", 111, 0x4c),
+    ("Pause", 119, 0x48),
+    ("MetaLeft", 125, 0xe3),
+    ("MetaRight", 126, 0xe7),
+    ("ContextMenu", 127, 0x65),
+];
+
+/// X11 keycodes are evdev codes offset by 8 (with the evdev driver that all current servers use).
+const X11_OFFSET: u16 = 8;
+
+/// A key at a position on the keyboard, pressed without regard to the layout.
+///
+/// The string and JSON form of a key is `"code:"` followed by its name, e.g. `"code:KeyW"`, or
+/// `"evdev:"` followed by its code for keys that are not in the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PhysicalKey(u16);
+
+impl PhysicalKey {
+    /// Every key that has a name, in the order of their evdev codes.
+    pub fn named() -> impl Iterator<Item = Self> {
+        KEYS.iter().map(|(_, code, _)| Self(*code))
+    }
+
+    /// The key with the given evdev code, any code is accepted.
+    pub fn from_evdev(code: u16) -> Self {
+        Self(code)
+    }
+
+    /// The key with the given USB HID usage ID, `None` if it is not in the table.
+    pub fn from_hid(usage: u16) -> Option<Self> {
+        let (_, code, _) = KEYS.iter().find(|(_, _, u)| *u == usage)?;
+        Some(Self(*code))
+    }
+
+    /// The key with the given X11 keycode, `None` for codes below 8 which are not keys.
+    pub fn from_x11(keycode: u16) -> Option<Self> {
+        keycode.checked_sub(X11_OFFSET).map(Self)
+    }
+
+    /// Parse a key name such as `"KeyW"` or `"ShiftLeft"` (case insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (_, code, _) = KEYS.iter().find(|(n, _, _)| n.eq_ignore_ascii_case(name))?;
+        Some(Self(*code))
+    }
+
+    pub fn evdev(&self) -> u16 {
+        self.0
+    }
+
+    /// The USB HID usage ID of the key, `None` if it is not in the table.
+    pub fn hid(&self) -> Option<u16> {
+        let (_, _, usage) = KEYS.iter().find(|(_, code, _)| *code == self.0)?;
+        Some(*usage)
+    }
+
+    pub fn x11(&self) -> u16 {
+        self.0.saturating_add(X11_OFFSET)
+    }
+
+    /// The name of the key, `None` if it is not in the table.
+    pub fn name(&self) -> Option<&'static str> {
+        let (name, _, _) = KEYS.iter().find(|(_, code, _)| *code == self.0)?;
+        Some(name)
+    }
+}
+
+impl FromStr for PhysicalKey {
+    type Err = InputError;
+
+    /// Parse a physical key, either a name (optionally prefixed with `"code:"`) or a code prefixed
+    /// by its kind: `"evdev:17"`, `"hid:26"` or `"x11:25"`. Codes may be decimal or hexadecimal (`0x1a`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InputError::error(format!("Invalid physical key: {:?}", s));
+        let (kind, code) = s.split_once(':').unwrap_or(("code", s));
+        if kind.eq_ignore_ascii_case("code") {
+            return Self::from_name(code).ok_or_else(invalid);
+        }
+        let code = match code.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16),
+            None => code.parse(),
+        }
+        .map_err(|_| invalid())?;
+        match kind.to_lowercase().as_str() {
+            "evdev" => Some(Self::from_evdev(code)),
+            "hid" => Self::from_hid(code),
+            "x11" => Self::from_x11(code),
+            _ => None,
+        }
+        .ok_or_else(invalid)
+    }
+}
+
+impl Display for PhysicalKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "code:{}", name),
+            None => write!(f, "evdev:{}", self.0),
+        }
+    }
+}
+
+impl Serialize for PhysicalKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PhysicalKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,256 @@
+//! The bundled layouts.
+//!
+//! Each key is an evdev key code and the characters it types at each level: without modifiers,
+//! with Shift, with AltGr and with Shift+AltGr. A space means the key types nothing at that
+//! level. Dead keys are left out, the characters they compose can not be typed.
+
+pub const US: &[(u16, &str)] = &[
+    (41, "`~"),
+    (2, "1!"),
+    (3, "2@"),
+    (4, "3#"),
+    (5, "4$"),
+    (6, "5%"),
+    (7, "6^"),
+    (8, "7&"),
+    (9, "8*"),
+    (10, "9("),
+    (11, "0)"),
+    (12, "-_"),
+    (13, "=+"),
+    (16, "qQ"),
+    (17, "wW"),
+    (18, "eE"),
+    (19, "rR"),
+    (20, "tT"),
+    (21, "yY"),
+    (22, "uU"),
+    (23, "iI"),
+    (24, "oO"),
+    (25, "pP"),
+    (26, "[{"),
+    (27, "]}"),
+    (30, "aA"),
+    (31, "sS"),
+    (32, "dD"),
+    (33, "fF"),
+    (34, "gG"),
+    (35, "hH"),
+    (36, "jJ"),
+    (37, "kK"),
+    (38, "lL"),
+    (39, ";:"),
+    (40, "'\""),
+    (43, "\\|"),
+    (44, "zZ"),
+    (45, "xX"),
+    (46, "cC"),
+    (47, "vV"),
+    (48, "bB"),
+    (49, "nN"),
+    (50, "mM"),
+    (51, ",<"),
+    (52, ".>"),
+    (53, "/?"),
+];
+
+pub const UK: &[(u16, &str)] = &[
+    (41, "`¬¦"),
+    (2, "1!"),
+    (3, "2\""),
+    (4, "3£"),
+    (5, "4$€"),
+    (6, "5%"),
+    (7, "6^"),
+    (8, "7&"),
+    (9, "8*"),
+    (10, "9("),
+    (11, "0)"),
+    (12, "-_"),
+    (13, "=+"),
+    (16, "qQ"),
+    (17, "wW"),
+    (18, "eE"),
+    (19, "rR"),
+    (20, "tT"),
+    (21, "yY"),
+    (22, "uU"),
+    (23, "iI"),
+    (24, "oO"),
+    (25, "pP"),
+    (26, "[{"),
+    (27, "]}"),
+    (30, "aA"),
+    (31, "sS"),
+    (32, "dD"),
+    (33, "fF"),
+    (34, "gG"),
+    (35, "hH"),
+    (36, "jJ"),
+    (37, "kK"),
+    (38, "lL"),
+    (39, ";:"),
+    (40, "'@"),
+    (43, "#~"),
+    (86, "\\|"),
+    (44, "zZ"),
+    (45, "xX"),
+    (46, "cC"),
+    (47, "vV"),
+    (48, "bB"),
+    (49, "nN"),
+    (50, "mM"),
+    (51, ",<"),
+    (52, ".>"),
+    (53, "/?"),
+];
+
+pub const DE: &[(u16, &str)] = &[
+    (41, " °"),
+    (2, "1!"),
+    (3, "2\"²"),
+    (4, "3§³"),
+    (5, "4$"),
+    (6, "5%"),
+    (7, "6&"),
+    (8, "7/{"),
+    (9, "8(["),
+    (10, "9)]"),
+    (11, "0=}"),
+    (12, "ß?\\"),
+    (16, "qQ@"),
+    (17, "wW"),
+    (18, "eE€"),
+    (19, "rR"),
+    (20, "tT"),
+    (21, "zZ"),
+    (22, "uU"),
+    (23, "iI"),
+    (24, "oO"),
+    (25, "pP"),
+    (26, "üÜ"),
+    (27, "+*~"),
+    (30, "aA"),
+    (31, "sS"),
+    (32, "dD"),
+    (33, "fF"),
+    (34, "gG"),
+    (35, "hH"),
+    (36, "jJ"),
+    (37, "kK"),
+    (38, "lL"),
+    (39, "öÖ"),
+    (40, "äÄ"),
+    (43, "#'"),
+    (86, "<>|"),
+    (44, "yY"),
+    (45, "xX"),
+    (46, "cC"),
+    (47, "vV"),
+    (48, "bB"),
+    (49, "nN"),
+    (50, "mMµ"),
+    (51, ",;"),
+    (52, ".:"),
+    (53, "-_"),
+];
+
+pub const FR: &[(u16, &str)] = &[
+    (41, "²"),
+    (2, "&1"),
+    (3, "é2"),
+    (4, "\"3#"),
+    (5, "'4{"),
+    (6, "(5["),
+    (7, "-6|"),
+    (8, "è7"),
+    (9, "_8\\"),
+    (10, "ç9"),
+    (11, "à0@"),
+    (12, ")°]"),
+    (13, "=+}"),
+    (16, "aA"),
+    (17, "zZ"),
+    (18, "eE€"),
+    (19, "rR"),
+    (20, "tT"),
+    (21, "yY"),
+    (22, "uU"),
+    (23, "iI"),
+    (24, "oO"),
+    (25, "pP"),
+    (27, "$£¤"),
+    (30, "qQ"),
+    (31, "sS"),
+    (32, "dD"),
+    (33, "fF"),
+    (34, "gG"),
+    (35, "hH"),
+    (36, "jJ"),
+    (37, "kK"),
+    (38, "lL"),
+    (39, "mM"),
+    (40, "ù%"),
+    (43, "*µ"),
+    (86, "<>"),
+    (44, "wW"),
+    (45, "xX"),
+    (46, "cC"),
+    (47, "vV"),
+    (48, "bB"),
+    (49, "nN"),
+    (50, ",?"),
+    (51, ";."),
+    (52, ":/"),
+    (53, "!§"),
+];
+
+pub const DVORAK: &[(u16, &str)] = &[
+    (41, "`~"),
+    (2, "1!"),
+    (3, "2@"),
+    (4, "3#"),
+    (5, "4$"),
+    (6, "5%"),
+    (7, "6^"),
+    (8, "7&"),
+    (9, "8*"),
+    (10, "9("),
+    (11, "0)"),
+    (12, "[{"),
+    (13, "]}"),
+    (16, "'\""),
+    (17, ",<"),
+    (18, ".>"),
+    (19, "pP"),
+    (20, "yY"),
+    (21, "fF"),
+    (22, "gG"),
+    (23, "cC"),
+    (24, "rR"),
+    (25, "lL"),
+    (26, "/?"),
+    (27, "=+"),
+    (30, "aA"),
+    (31, "oO"),
+    (32, "eE"),
+    (33, "uU"),
+    (34, "iI"),
+    (35, "dD"),
+    (36, "hH"),
+    (37, "tT"),
+    (38, "nN"),
+    (39, "sS"),
+    (40, "-_"),
+    (43, "\\|"),
+    (44, ";:"),
+    (45, "qQ"),
+    (46, "jJ"),
+    (47, "kK"),
+    (48, "xX"),
+    (49, "bB"),
+    (50, "mM"),
+    (51, "wW"),
+    (52, "vV"),
+    (53, "zZ"),
+];
@@ -1,72 +1,66 @@
 pub mod backends;
 pub mod core;
 pub mod device;
+pub mod evdev;
+#[cfg(feature = "python")]
+pub mod gym;
+pub mod layout;
+pub mod record;
+pub mod session;
+pub mod state;
 
-use core::{KeyboardHandler, MouseHandler, TouchHandler};
-use std::sync::Arc;
-
-use backends::backend::Backend;
-use backends::enigo_backend::EnigoBackend;
-use device::keyboard::KeyboardDevice;
-use device::mouse::MouseDevice;
-use device::touch::TouchDevice;
-
-use pyo3::exceptions::PyValueError;
-use pyo3::prelude::*;
-use pyo3::types::PyModule;
-
-// Define a Rust struct
-#[pyclass]
-struct Session {
-    mouse_device: MouseDevice,
-    keyboard_device: KeyboardDevice,
-    touch_device: TouchDevice,
-}
-
-#[pymethods]
-impl Session {
-    #[new]
-    fn new(py: Python<'_>, backend: Option<&str>) -> Self {
-        let backend = Arc::new(match backend {
-            Some("engio") => EnigoBackend::default(),
-            _ => EnigoBackend::default(),
-        });
-        let mouse_handler = Arc::clone(&backend) as Arc<dyn MouseHandler + Send + Sync>;
-        let mouse_device = MouseDevice::new(mouse_handler);
-
-        let keyboard_handler = Arc::clone(&backend) as Arc<dyn KeyboardHandler + Send + Sync>;
-        let keyboard_device = KeyboardDevice::new(keyboard_handler);
-
-        // this is unlikely to use the same backend?
-        let touch_handler = Arc::clone(&backend) as Arc<dyn TouchHandler + Send + Sync>;
-        let touch_device = TouchDevice::new(touch_handler);
-
-        // other devices?
-        return Session {
-            mouse_device,
-            keyboard_device,
-            touch_device,
-        };
-    }
-
-    fn get_device<'py>(&'py self, py: Python<'py>, device: &str) -> PyResult<Py<PyAny>> {
-        let device = match device {
-            // this clone is cheap, its only a clone of Arc
-            "mouse" => Ok(self.mouse_device.clone().into_py(py)),
-            "keyboard" => Ok(self.keyboard_device.clone().into_py(py)),
-            "touch" => Ok(self.touch_device.clone().into_py(py)),
-            _ => Err(PyValueError::new_err("Device not found")),
-        }?;
-        return Ok(device);
-    }
-}
+#[cfg(feature = "python")]
+use {
+    core::cancel::ActionHandle,
+    core::interrupt::InterruptPolicy,
+    core::receipt::Receipt,
+    core::result::{ActionCancelled, ActionInterrupt},
+    core::scheduler::{set_timing_tolerance, timing_tolerance},
+    core::trace::{disable_logging, enable_logging},
+    device::gamepad::{GamepadButton, GamepadDevice, Stick, Trigger},
+    device::keyboard::{HeldKey, KeyboardDevice, KeyboardKey},
+    device::mouse::{HeldButton, MouseButton, MouseDevice},
+    device::pen::{PenButton, PenDevice, PenTool},
+    device::touch::TouchDevice,
+    gym::ActionSpace,
+    layout::Layout,
+    pyo3::prelude::*,
+    pyo3::types::PyModule,
+    session::{Capabilities, Session},
+    state::InputState,
+};
 
+#[cfg(feature = "python")]
 #[pymodule]
 fn anyinput(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Session>()?;
     m.add_class::<MouseDevice>()?;
     m.add_class::<KeyboardDevice>()?;
     m.add_class::<TouchDevice>()?;
+    m.add_class::<GamepadDevice>()?;
+    m.add_class::<PenDevice>()?;
+    m.add_class::<HeldKey>()?;
+    m.add_class::<HeldButton>()?;
+    m.add_class::<InterruptPolicy>()?;
+    m.add_class::<ActionInterrupt>()?;
+    m.add_class::<ActionCancelled>()?;
+    m.add_class::<ActionHandle>()?;
+    m.add_class::<Receipt>()?;
+    m.add_class::<Layout>()?;
+    m.add_class::<Capabilities>()?;
+    m.add_class::<InputState>()?;
+    m.add_class::<ActionSpace>()?;
+    m.add("MouseButton", MouseButton::py_enum(m.py())?)?;
+    m.add("Key", KeyboardKey::py_enum(m.py())?)?;
+    m.add("GamepadButton", GamepadButton::py_enum(m.py())?)?;
+    m.add("Stick", Stick::py_enum(m.py())?)?;
+    m.add("Trigger", Trigger::py_enum(m.py())?)?;
+    m.add("PenButton", PenButton::py_enum(m.py())?)?;
+    m.add("PenTool", PenTool::py_enum(m.py())?)?;
+    m.add_function(wrap_pyfunction!(enable_logging, m)?)?;
+    m.add_function(wrap_pyfunction!(disable_logging, m)?)?;
+    m.add_function(wrap_pyfunction!(set_timing_tolerance, m)?)?;
+    m.add_function(wrap_pyfunction!(timing_tolerance, m)?)?;
     // m.add_function(wrap_pyfunction!(svg_to_numpy, m)?)?;
     Ok(())
 }
@@ -1,3 +1,265 @@
-fn main() {
-    println!("Hello, world!");
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyinput::backends;
+use anyinput::core::cancel::CancelToken;
+use anyinput::core::result::{InputError, InputResult};
+use anyinput::core::{KeyboardHandler, MouseHandler};
+use anyinput::device::keyboard::{KeyboardDevice, KeyboardKey};
+use anyinput::device::mouse::{MouseButton, MouseDevice};
+use anyinput::record::{Coordinates, Recording, ReplayOptions};
+use clap::{Parser, Subcommand};
+
+/// Exit code used when an action was interrupted by the user, e.g. with Ctrl-C.
+const EXIT_INTERRUPT: u8 = 130;
+/// Exit code used for all other errors.
+const EXIT_ERROR: u8 = 1;
+
+/// Simulate mouse and keyboard input from the command line.
+#[derive(Parser)]
+#[command(name = "anyinput", version)]
+struct Cli {
+    /// The backend used to simulate input, see `anyinput backends`.
+    #[arg(long, global = true, default_value = backends::BACKENDS[0])]
+    backend: String,
+
+    /// Print the actions instead of performing them, same as `--backend dry-run`.
+    #[arg(long, global = true, conflicts_with = "backend")]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Click a mouse button.
+    Click {
+        #[arg(default_value = "left", value_parser = parse_button)]
+        button: MouseButton,
+    },
+    /// Move the mouse to an absolute position, or by a relative amount with `--relative`.
+    #[command(allow_negative_numbers = true)]
+    Move {
+        x: i32,
+        y: i32,
+        #[arg(short, long)]
+        relative: bool,
+        /// Duration of the movement in seconds.
+        #[arg(short, long, default_value_t = 0.0)]
+        duration: f64,
+    },
+    /// Drag with a mouse button held by a relative amount.
+    #[command(allow_negative_numbers = true)]
+    Drag {
+        dx: i32,
+        dy: i32,
+        #[arg(short, long, default_value = "left", value_parser = parse_button)]
+        button: MouseButton,
+        /// Duration of the drag in seconds.
+        #[arg(short, long, default_value_t = 0.0)]
+        duration: f64,
+    },
+    /// Scroll the mouse wheel, positive values scroll down.
+    #[command(allow_negative_numbers = true)]
+    Scroll {
+        dy: i32,
+        /// Horizontal scroll amount, positive values scroll right.
+        #[arg(default_value_t = 0)]
+        dx: i32,
+    },
+    /// Tap a key, or hold it for a duration with `--hold`.
+    Key {
+        #[arg(value_parser = parse_key)]
+        key: KeyboardKey,
+        /// Hold the key down for this many seconds.
+        #[arg(long)]
+        hold: Option<f64>,
+    },
+    /// Press a combination of keys together, e.g. `anyinput hotkey ctrl shift t`.
+    Hotkey {
+        #[arg(required = true, value_parser = parse_key)]
+        keys: Vec<KeyboardKey>,
+    },
+    /// Type text.
+    Type {
+        text: String,
+        /// Duration over which to type the text in seconds.
+        #[arg(short, long, default_value_t = 0.0)]
+        duration: f64,
+    },
+    /// Play back a recording.
+    Play {
+        file: String,
+        /// Playback speed multiplier.
+        #[arg(short, long, default_value_t = 1.0)]
+        speed: f64,
+        /// Move the cursor to the recorded positions instead of replaying relative motion.
+        #[arg(short, long)]
+        absolute: bool,
+    },
+    /// Record keyboard and mouse input from evdev devices (Linux only), stop with Ctrl-C.
+    Record {
+        file: String,
+        /// Stop recording after this many seconds.
+        #[arg(short, long)]
+        duration: Option<f64>,
+        /// An evdev device to record from, all keyboards and mice are recorded if not given.
+        #[arg(long = "device")]
+        devices: Vec<String>,
+    },
+    /// List the available backends.
+    Backends,
+}
+
+fn parse_button(s: &str) -> Result<MouseButton, String> {
+    s.parse().map_err(|e: InputError| e.to_string())
+}
+
+fn parse_key(s: &str) -> Result<KeyboardKey, String> {
+    s.parse().map_err(|e: InputError| e.to_string())
+}
+
+fn seconds(duration: f64) -> Result<Duration, InputError> {
+    Duration::try_from_secs_f64(duration)
+        .map_err(|_| InputError::error(format!("Invalid duration: {}", duration)))
+}
+
+fn run(cli: Cli) -> InputResult {
+    if let Command::Backends = cli.command {
+        for name in backends::BACKENDS {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let handlers = match cli.dry_run {
+        true => backends::create("dry-run")?,
+        false => backends::create(&cli.backend)?,
+    };
+    // Ctrl-C cancels the action, which releases what it holds before the process exits
+    let cancel = CancelToken::new();
+    let stop = Arc::new(AtomicBool::new(false));
+    let (handler_cancel, handler_stop) = (cancel.clone(), Arc::clone(&stop));
+    ctrlc::set_handler(move || {
+        handler_stop.store(true, Ordering::Relaxed);
+        handler_cancel.cancel();
+    })
+    .map_err(|e| InputError::error(e.to_string()))?;
+    let mouse = MouseDevice::new(handlers.mouse).with_cancel_token(cancel.clone());
+    let keyboard = KeyboardDevice::new(handlers.keyboard).with_cancel_token(cancel);
+
+    match cli.command {
+        Command::Click { button } => mouse.click(button),
+        Command::Move {
+            x,
+            y,
+            relative: false,
+            duration,
+        } => mouse.move_abs(x, y, seconds(duration)?),
+        Command::Move {
+            x,
+            y,
+            relative: true,
+            duration,
+        } => mouse.move_rel(x, y, seconds(duration)?),
+        Command::Drag {
+            dx,
+            dy,
+            button,
+            duration,
+        } => mouse.drag(button, dx, dy, seconds(duration)?),
+        Command::Scroll { dy, dx } => mouse.scroll(dx, dy),
+        Command::Key { key, hold: None } => keyboard.tap(key),
+        Command::Key {
+            key,
+            hold: Some(duration),
+        } => keyboard.hold(key, seconds(duration)?),
+        Command::Hotkey { keys } => hotkey(&keyboard, &keys),
+        Command::Type { text, duration } => keyboard.text(&text, seconds(duration)?),
+        Command::Play {
+            file,
+            speed,
+            absolute,
+        } => {
+            let options = ReplayOptions {
+                speed,
+                coordinates: match absolute {
+                    true => Coordinates::Absolute,
+                    false => Coordinates::Relative,
+                },
+            };
+            Recording::load(file)?.replay(&mouse, &keyboard, &options)
+        }
+        Command::Record {
+            file,
+            duration,
+            devices,
+        } => record(&mouse, &file, duration, &devices, &stop),
+        Command::Backends => unreachable!(),
+    }
+}
+
+/// Press all keys in order, then release them in reverse order.
+fn hotkey(keyboard: &KeyboardDevice, keys: &[KeyboardKey]) -> InputResult {
+    for (i, key) in keys.iter().enumerate() {
+        if let Err(e) = keyboard.press(*key) {
+            // don't leave any keys held down
+            for key in keys[..i].iter().rev() {
+                let _ = keyboard.release(*key);
+            }
+            return Err(e);
+        }
+    }
+    for key in keys.iter().rev() {
+        keyboard.release(*key)?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn record(
+    mouse: &MouseDevice,
+    file: &str,
+    duration: Option<f64>,
+    devices: &[String],
+    stop: &AtomicBool,
+) -> InputResult {
+    use anyinput::record::evdev::EvdevRecorder;
+
+    let duration = duration.map(seconds).transpose()?;
+    let mut recorder = match devices.is_empty() {
+        true => EvdevRecorder::open_all()?,
+        false => EvdevRecorder::open(devices)?,
+    };
+    for path in recorder.paths() {
+        eprintln!("recording from {}", path.display());
+    }
+    let recording = recorder.record(duration, stop, mouse.location().ok())?;
+    recording.save(file)?;
+    eprintln!("recorded {} actions to {}", recording.actions.len(), file);
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn record(_: &MouseDevice, _: &str, _: Option<f64>, _: &[String], _: &AtomicBool) -> InputResult {
+    Err(InputError::error(
+        "Recording is only supported on Linux.".to_string(),
+    ))
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e @ (InputError::Interrupt | InputError::Cancelled)) => {
+            eprintln!("interrupted: {}", e);
+            ExitCode::from(EXIT_INTERRUPT)
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
 }
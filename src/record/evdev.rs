@@ -0,0 +1,195 @@
+//! Capture of user input from Linux evdev devices (`/dev/input/event*`) or recorded event streams.
+//!
+//! This is the only capture source, see the [module docs](super) for what it misses.
+use std::io::Read;
+use std::time::Duration;
+
+use crate::core::action::Action;
+use crate::core::result::InputError;
+use crate::evdev::{self, codes, RawEvent};
+
+use super::Recording;
+
+/// Converts raw evdev events into [`Action`]s.
+///
+/// Relative motion is accumulated until the next `SYN_REPORT` so that x and y
+/// motion reported together produce a single move.
+#[derive(Debug, Default)]
+pub struct EvdevTranslator {
+    motion: (i32, i32),
+    scroll: (i32, i32),
+}
+
+impl EvdevTranslator {
+    pub fn translate(&mut self, event: &RawEvent) -> Vec<Action> {
+        let mut actions = Vec::new();
+        match (event.kind, event.code) {
+            (evdev::EV_REL, evdev::REL_X) => self.motion.0 += event.value,
+            (evdev::EV_REL, evdev::REL_Y) => self.motion.1 += event.value,
+            // evdev reports positive wheel values for scrolling up, actions use positive for down
+            (evdev::EV_REL, evdev::REL_WHEEL) => self.scroll.1 -= event.value,
+            (evdev::EV_REL, evdev::REL_HWHEEL) => self.scroll.0 += event.value,
+            (evdev::EV_KEY, code) if event.value != 2 => {
+                // value 2 is auto-repeat, the recorded press/release pair is enough to reproduce it
+                let pressed = event.value == 1;
+                if let Some(button) = codes::button_from_code(code) {
+                    actions.push(match pressed {
+                        true => Action::MousePress { button },
+                        false => Action::MouseRelease { button },
+                    });
+                } else if let Some(key) = codes::key_from_code(code) {
+                    actions.push(match pressed {
+                        true => Action::KeyPress { key },
+                        false => Action::KeyRelease { key },
+                    });
+                }
+            }
+            (evdev::EV_SYN, evdev::SYN_REPORT) => {
+                let (dx, dy) = std::mem::take(&mut self.motion);
+                if (dx, dy) != (0, 0) {
+                    actions.push(Action::MouseMoveRel { dx, dy });
+                }
+                let (dx, dy) = std::mem::take(&mut self.scroll);
+                if (dx, dy) != (0, 0) {
+                    actions.push(Action::MouseScroll { dx, dy });
+                }
+            }
+            _ => {}
+        }
+        actions
+    }
+}
+
+impl Recording {
+    /// Build a recording from a stream of raw evdev events, for example a file captured with
+    /// `cat /dev/input/eventN > stream`. Times are relative to the first event in the stream.
+    pub fn from_evdev_stream(reader: &mut impl Read) -> Result<Self, InputError> {
+        let mut recording = Recording::new(None);
+        let mut translator = EvdevTranslator::default();
+        let mut start = None;
+        while let Some(event) = RawEvent::read_from(reader)? {
+            let start = *start.get_or_insert(event.time);
+            let time = event.time.saturating_sub(start).as_secs_f64();
+            for action in translator.translate(&event) {
+                recording.push(time, action);
+            }
+        }
+        Ok(recording)
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use recorder::EvdevRecorder;
+
+#[cfg(target_os = "linux")]
+mod recorder {
+    use std::fs::{File, OpenOptions};
+    use std::io::ErrorKind;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    /// Records user input from one or more evdev devices.
+    ///
+    /// Reading `/dev/input/event*` usually requires root or membership of the `input` group.
+    #[derive(Debug)]
+    pub struct EvdevRecorder {
+        devices: Vec<(PathBuf, File)>,
+    }
+
+    impl EvdevRecorder {
+        /// Open the given evdev devices for recording.
+        pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, InputError> {
+            let devices = paths
+                .iter()
+                .map(|path| {
+                    let file = OpenOptions::new()
+                        .read(true)
+                        .custom_flags(libc::O_NONBLOCK)
+                        .open(path)
+                        .map_err(|e| {
+                            InputError::error(format!(
+                                "Failed to open {}: {}",
+                                path.as_ref().display(),
+                                e
+                            ))
+                        })?;
+                    Ok((path.as_ref().to_path_buf(), file))
+                })
+                .collect::<Result<Vec<_>, InputError>>()?;
+            Ok(Self { devices })
+        }
+
+        /// Find the keyboard and mouse devices listed in `/dev/input/by-id` and `/dev/input/by-path`.
+        pub fn discover() -> Vec<PathBuf> {
+            evdev::discover(&["-event-kbd", "-event-mouse"])
+        }
+
+        /// Open all discovered keyboard and mouse devices.
+        pub fn open_all() -> Result<Self, InputError> {
+            let paths = Self::discover();
+            if paths.is_empty() {
+                return Err(InputError::error(
+                    "No keyboard or mouse evdev devices were found.".to_string(),
+                ));
+            }
+            Self::open(&paths)
+        }
+
+        pub fn paths(&self) -> impl Iterator<Item = &Path> {
+            self.devices.iter().map(|(path, _)| path.as_path())
+        }
+
+        /// Record until `duration` has passed (if given) or `stop` is set.
+        ///
+        /// `origin` should be the cursor position at the start of the recording, it allows the
+        /// recording to be replayed with absolute coordinates.
+        pub fn record(
+            &mut self,
+            duration: Option<Duration>,
+            stop: &AtomicBool,
+            origin: Option<(i32, i32)>,
+        ) -> Result<Recording, InputError> {
+            let mut recording = Recording::new(origin);
+            let mut translators: Vec<EvdevTranslator> = self
+                .devices
+                .iter()
+                .map(|_| EvdevTranslator::default())
+                .collect();
+            let start_time = Instant::now();
+            let start_epoch = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+
+            while !stop.load(Ordering::Relaxed) && duration.is_none_or(|d| start_time.elapsed() < d)
+            {
+                let mut idle = true;
+                for ((_, file), translator) in self.devices.iter_mut().zip(translators.iter_mut()) {
+                    loop {
+                        match RawEvent::read_from(file) {
+                            Ok(Some(event)) => {
+                                idle = false;
+                                let time = event.time.saturating_sub(start_epoch).as_secs_f64();
+                                for action in translator.translate(&event) {
+                                    recording.push(time, action);
+                                }
+                            }
+                            Ok(None) => break,
+                            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                }
+                if idle {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            }
+            // events from different devices are interleaved by time
+            recording.actions.sort_by(|a, b| a.time.total_cmp(&b.time));
+            Ok(recording)
+        }
+    }
+}
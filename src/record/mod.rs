@@ -0,0 +1,11 @@
+//! Recording of real user input and playback of recorded input.
+//!
+//! Input is only captured from Linux evdev devices. Capture through the X11 XRecord extension is
+//! out of scope: it would add a libXtst dependency and could only be tested against a running X
+//! server, while evdev capture is tested against recorded event stream files. Input that never
+//! passes through evdev, such as input injected into the X server by another program or a remote
+//! desktop, is not recorded.
+pub mod evdev;
+pub mod recording;
+
+pub use recording::{Coordinates, Recording, ReplayOptions};
@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::action::{Action, TimedAction};
+use crate::core::result::{InputError, InputResult};
+use crate::core::scheduler;
+use crate::device::keyboard::KeyboardHandler;
+use crate::device::mouse::MouseHandler;
+
+/// How mouse movement is reproduced during playback.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Coordinates {
+    /// Movement is replayed as relative motion from wherever the cursor currently is.
+    #[default]
+    Relative,
+    /// Movement is replayed at the recorded screen positions, this requires the recording to have an origin.
+    Absolute,
+}
+
+impl FromStr for Coordinates {
+    type Err = InputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "relative" | "rel" => Ok(Self::Relative),
+            "absolute" | "abs" => Ok(Self::Absolute),
+            _ => Err(InputError::error(format!(
+                "Invalid coordinates {:?}, expected 'relative' or 'absolute'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Options that control playback of a [`Recording`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayOptions {
+    /// Playback speed multiplier, 2.0 plays back twice as fast.
+    pub speed: f64,
+    pub coordinates: Coordinates,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            coordinates: Coordinates::default(),
+        }
+    }
+}
+
+/// A sequence of timestamped actions, typically a recorded human demonstration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// Cursor position at the start of the recording, if known.
+    #[serde(default)]
+    pub origin: Option<(i32, i32)>,
+    pub actions: Vec<TimedAction>,
+}
+
+impl Recording {
+    pub fn new(origin: Option<(i32, i32)>) -> Self {
+        Self {
+            origin,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, time: f64, action: Action) {
+        self.actions.push(TimedAction { time, action });
+    }
+
+    /// Duration of the recording in seconds.
+    pub fn duration(&self) -> f64 {
+        self.actions.last().map_or(0.0, |a| a.time)
+    }
+
+    /// Load a recording from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        let file = File::open(path)?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| InputError::error(e.to_string()))
+    }
+
+    /// Save the recording to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> InputResult {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .map_err(|e| InputError::error(e.to_string()))
+    }
+
+    /// Play back the recording, blocks until all actions have been performed.
+    pub fn replay(
+        &self,
+        mouse: &dyn MouseHandler,
+        keyboard: &dyn KeyboardHandler,
+        options: &ReplayOptions,
+    ) -> InputResult {
+        if !(options.speed.is_finite() && options.speed > 0.0) {
+            return Err(InputError::error(format!(
+                "Invalid playback speed: {}",
+                options.speed
+            )));
+        }
+        // the recorded cursor position, used to convert between relative and absolute movement
+        let mut position = self.origin;
+        if options.coordinates == Coordinates::Absolute {
+            let (x, y) = position.ok_or_else(|| {
+                InputError::error(
+                    "Recording has no origin, it cannot be replayed with absolute coordinates."
+                        .to_string(),
+                )
+            })?;
+            mouse.move_abs(x, y, Duration::ZERO)?;
+        }
+
+        let start_time = Instant::now();
+        for timed in &self.actions {
+            let target = Duration::from_secs_f64(timed.time.max(0.0) / options.speed);
+            scheduler::wait_until(start_time + target);
+            let action = match (timed.action, position) {
+                (Action::MouseMoveRel { dx, dy }, Some((x, y))) => {
+                    position = Some((x + dx, y + dy));
+                    match options.coordinates {
+                        Coordinates::Absolute => Action::MouseMoveAbs {
+                            x: x + dx,
+                            y: y + dy,
+                        },
+                        Coordinates::Relative => timed.action,
+                    }
+                }
+                (Action::MouseMoveAbs { x, y }, previous) => {
+                    position = Some((x, y));
+                    match (options.coordinates, previous) {
+                        (Coordinates::Relative, Some((px, py))) => Action::MouseMoveRel {
+                            dx: x - px,
+                            dy: y - py,
+                        },
+                        _ => timed.action,
+                    }
+                }
+                (action, _) => action,
+            };
+            action.apply(mouse, keyboard)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,604 @@
+#[cfg(feature = "python")]
+use {
+    crate::core::failsafe::Corner,
+    crate::record::{self, Coordinates, Recording, ReplayOptions},
+    pyo3::exceptions::PyValueError,
+    pyo3::prelude::*,
+    std::time::Duration,
+};
+
+use std::sync::Arc;
+
+use crate::backends::{self, Handlers};
+use crate::core::failsafe::{FailSafe, FailSafeConfig};
+use crate::core::humanize::{HumanizeProfile, Humanizer};
+use crate::core::interrupt::InterruptPolicy;
+use crate::core::result::{InputError, InputResult};
+use crate::core::{KeyboardHandler, MouseHandler, TouchHandler};
+use crate::device::gamepad::{GamepadButton, GamepadDevice};
+use crate::device::keyboard::{KeyboardDevice, KeyboardKey};
+use crate::device::mouse::{MouseButton, MouseDevice};
+use crate::device::pen::{PenButton, PenDevice};
+use crate::device::touch::TouchDevice;
+use crate::device::{Device, DeviceKind};
+use crate::state::InputState;
+
+/// A set of devices created from a backend.
+///
+/// Any keys, buttons, touch contacts, sticks, triggers or pens that are still held when the
+/// session is dropped are released.
+#[cfg_attr(feature = "python", pyclass)]
+pub struct Session {
+    mouse_device: MouseDevice,
+    keyboard_device: KeyboardDevice,
+    touch_device: TouchDevice,
+    gamepad_device: Option<GamepadDevice>,
+    pen_device: Option<PenDevice>,
+    // watched by the fail-safe, this is the backend rather than the device so that reading it is never refused
+    cursor: Arc<dyn MouseHandler + Send + Sync>,
+}
+
+/// What a session can do: its devices, its screen and the inputs each device accepts.
+#[cfg_attr(feature = "python", pyclass(frozen))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capabilities {
+    /// The kinds of device the session has, ordered by [`DeviceKind::ALL`].
+    pub devices: Vec<DeviceKind>,
+    /// Size of the screen in pixels, positions of the mouse, touch screen and pen are within it.
+    pub screen: (i32, i32),
+    pub mouse_buttons: Vec<MouseButton>,
+    /// [`KeyboardKey::common`], other characters and physical keys can be pressed too.
+    pub keys: Vec<KeyboardKey>,
+    /// Empty if the session has no gamepad.
+    pub gamepad_buttons: Vec<GamepadButton>,
+    /// Empty if the session has no pen.
+    pub pen_buttons: Vec<PenButton>,
+}
+
+impl Session {
+    /// Create a session using the backend with the given name, see [`backends::BACKENDS`].
+    pub fn new(backend: &str) -> Result<Self, InputError> {
+        Ok(Self::from_handlers(backends::create(backend)?))
+    }
+
+    pub fn from_handlers(handlers: Handlers) -> Self {
+        // other devices?
+        Session {
+            keyboard_device: KeyboardDevice::new(handlers.keyboard)
+                .with_cursor(Arc::clone(&handlers.mouse)),
+            gamepad_device: handlers.gamepad.map(|gamepad| {
+                GamepadDevice::new(gamepad).with_cursor(Arc::clone(&handlers.mouse))
+            }),
+            pen_device: handlers
+                .pen
+                .map(|pen| PenDevice::new(pen).with_cursor(Arc::clone(&handlers.mouse))),
+            cursor: Arc::clone(&handlers.mouse),
+            mouse_device: MouseDevice::new(handlers.mouse),
+            touch_device: TouchDevice::new(handlers.touch),
+        }
+    }
+
+    pub fn mouse(&self) -> &MouseDevice {
+        &self.mouse_device
+    }
+
+    pub fn keyboard(&self) -> &KeyboardDevice {
+        &self.keyboard_device
+    }
+
+    pub fn touch(&self) -> &TouchDevice {
+        &self.touch_device
+    }
+
+    /// The session's gamepad, `None` if the backend has none.
+    pub fn gamepad(&self) -> Option<&GamepadDevice> {
+        self.gamepad_device.as_ref()
+    }
+
+    /// The session's pen, `None` if the backend has none.
+    pub fn pen(&self) -> Option<&PenDevice> {
+        self.pen_device.as_ref()
+    }
+
+    /// Every device of the session, ordered by [`DeviceKind::ALL`].
+    pub fn devices(&self) -> Vec<Device> {
+        let mut devices = vec![
+            Device::Mouse(self.mouse_device.clone()),
+            Device::Keyboard(self.keyboard_device.clone()),
+            Device::Touch(self.touch_device.clone()),
+        ];
+        devices.extend(self.gamepad_device.clone().map(Device::Gamepad));
+        devices.extend(self.pen_device.clone().map(Device::Pen));
+        devices
+    }
+
+    /// What the session's devices can do, this asks the backend for the screen size.
+    pub fn capabilities(&self) -> Result<Capabilities, InputError> {
+        let devices = self.devices();
+        let has = |kind| devices.iter().any(|device| device.kind() == kind);
+        Ok(Capabilities {
+            devices: devices.iter().map(Device::kind).collect(),
+            screen: self.cursor.display_size()?,
+            mouse_buttons: MouseButton::ALL.to_vec(),
+            keys: KeyboardKey::common(),
+            gamepad_buttons: match has(DeviceKind::Gamepad) {
+                true => GamepadButton::ALL.to_vec(),
+                false => Vec::new(),
+            },
+            pen_buttons: match has(DeviceKind::Pen) {
+                true => PenButton::ALL.to_vec(),
+                false => Vec::new(),
+            },
+        })
+    }
+
+    /// The keys, mouse buttons and touch contacts that are down and where the cursor is.
+    ///
+    /// These are what the system reports if the backend can query all of them, otherwise what the
+    /// session pressed and has not released, see [`InputState::reconciled`].
+    pub fn state(&self) -> InputState {
+        let cursor = self.cursor.location().ok();
+        let reported = (
+            self.keyboard_device.pressed_keys(),
+            self.mouse_device.pressed_buttons(),
+            self.touch_device.active_contacts(),
+        );
+        match reported {
+            (Ok(keys), Ok(mouse_buttons), Ok(touches)) => InputState {
+                keys,
+                mouse_buttons,
+                touches,
+                cursor,
+                reconciled: true,
+            },
+            _ => InputState {
+                keys: self.keyboard_device.held_keys(),
+                mouse_buttons: self.mouse_device.held_buttons(),
+                touches: self.touch_device.held_contacts(),
+                cursor,
+                reconciled: false,
+            },
+        }
+    }
+
+    /// The devices of one kind, a session may have several devices of a kind or none.
+    pub fn devices_of(&self, kind: DeviceKind) -> Vec<Device> {
+        self.devices()
+            .into_iter()
+            .filter(|device| device.kind() == kind)
+            .collect()
+    }
+
+    /// The first device of a kind, `None` if the session has none.
+    pub fn device(&self, kind: DeviceKind) -> Option<Device> {
+        self.devices_of(kind).into_iter().next()
+    }
+
+    /// Release every key, button and touch contact that is held by the session's devices, reset
+    /// the gamepad's sticks and triggers, and lift the pen.
+    ///
+    /// Every device is released even if some fail, the first error is returned.
+    pub fn release_all(&self) -> InputResult {
+        let mouse = self.mouse_device.release_all();
+        let keyboard = self.keyboard_device.release_all();
+        let touch = self.touch_device.release_all();
+        let gamepad = match &self.gamepad_device {
+            Some(gamepad) => gamepad.release_all(),
+            None => Ok(()),
+        };
+        let pen = match &self.pen_device {
+            Some(pen) => pen.release_all(),
+            None => Ok(()),
+        };
+        mouse.and(keyboard).and(touch).and(gamepad).and(pen)
+    }
+
+    /// The policy used to interrupt timed actions.
+    pub fn interrupt_policy(&self) -> InterruptPolicy {
+        self.mouse_device.interrupt_policy()
+    }
+
+    /// Set the policy used to interrupt timed actions on all of the session's devices.
+    pub fn set_interrupt_policy(&self, policy: InterruptPolicy) {
+        self.mouse_device.set_interrupt_policy(policy.clone());
+        if let Some(gamepad) = &self.gamepad_device {
+            gamepad.set_interrupt_policy(policy.clone());
+        }
+        if let Some(pen) = &self.pen_device {
+            pen.set_interrupt_policy(policy.clone());
+        }
+        self.keyboard_device.set_interrupt_policy(policy);
+    }
+
+    /// Enable a fail-safe that stops every device of the session when the user triggers it, see
+    /// [`FailSafe`]. This replaces any fail-safe that was enabled before.
+    pub fn enable_failsafe(&self, config: FailSafeConfig) -> InputResult {
+        let failsafe = FailSafe::start(config, Arc::clone(&self.cursor))?;
+        self.set_failsafe(Some(failsafe));
+        Ok(())
+    }
+
+    pub fn disable_failsafe(&self) {
+        self.set_failsafe(None);
+    }
+
+    fn set_failsafe(&self, failsafe: Option<Arc<FailSafe>>) {
+        self.mouse_device.set_failsafe(failsafe.clone());
+        self.keyboard_device.set_failsafe(failsafe.clone());
+        if let Some(gamepad) = &self.gamepad_device {
+            gamepad.set_failsafe(failsafe.clone());
+        }
+        if let Some(pen) = &self.pen_device {
+            pen.set_failsafe(failsafe.clone());
+        }
+        self.touch_device.set_failsafe(failsafe);
+    }
+
+    /// The session's fail-safe, `None` if it is not enabled.
+    pub fn failsafe(&self) -> Option<Arc<FailSafe>> {
+        self.mouse_device.failsafe()
+    }
+
+    /// Allow actions again after the fail-safe was tripped.
+    pub fn rearm(&self) {
+        if let Some(failsafe) = self.failsafe() {
+            failsafe.rearm();
+        }
+    }
+
+    /// Add random variation to the mouse and keyboard actions of the session, see
+    /// [`HumanizeProfile`]. Both devices draw from the same generator, so a seeded profile
+    /// reproduces a run of the same calls. `None` turns it off.
+    pub fn set_humanize(&self, profile: Option<HumanizeProfile>) {
+        let humanizer = profile.map(|profile| Arc::new(Humanizer::new(profile)));
+        self.mouse_device.set_humanizer(humanizer.clone());
+        self.keyboard_device.set_humanizer(humanizer);
+    }
+
+    /// The session's humanize profile, `None` if it is not enabled.
+    pub fn humanize_profile(&self) -> Option<HumanizeProfile> {
+        self.mouse_device
+            .humanizer()
+            .map(|humanizer| humanizer.profile().clone())
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // devices may outlive the session (e.g. in python), release their inputs anyway
+        let _ = self.release_all();
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Session {
+    /// Create a session using the named backend.
+    ///
+    /// Args:
+    ///     backend: "enigo" to simulate input (the default), or "dry-run" to check actions against
+    ///         a virtual 1920x1080 screen and print a timestamped trace of them instead.
+    #[new]
+    #[pyo3(signature = (backend=None))]
+    fn py_new(backend: Option<&str>) -> PyResult<Self> {
+        Session::new(backend.unwrap_or(backends::BACKENDS[0])).map_err(|e| e.into_py())
+    }
+
+    #[getter(mouse)]
+    fn py_mouse(&self) -> MouseDevice {
+        // this clone is cheap, its only a clone of Arc
+        self.mouse_device.clone()
+    }
+
+    #[getter(keyboard)]
+    fn py_keyboard(&self) -> KeyboardDevice {
+        self.keyboard_device.clone()
+    }
+
+    #[getter(touch)]
+    fn py_touch(&self) -> TouchDevice {
+        self.touch_device.clone()
+    }
+
+    /// A virtual Xbox controller, created the first time it is used. None if the backend has no
+    /// gamepad, "enigo" has one on Linux only.
+    #[getter(gamepad)]
+    fn py_gamepad(&self) -> Option<GamepadDevice> {
+        self.gamepad_device.clone()
+    }
+
+    /// A pen on a virtual screen tablet, created the first time it is used. None if the backend
+    /// has no pen, "enigo" has one on Linux only.
+    #[getter(pen)]
+    fn py_pen(&self) -> Option<PenDevice> {
+        self.pen_device.clone()
+    }
+
+    /// Get the first device of the given type, prefer the `mouse`, `keyboard`, `touch`, `gamepad`
+    /// and `pen` properties which are typed.
+    ///
+    /// Args:
+    ///     device: "mouse", "keyboard", "touch", "gamepad" or "pen".
+    ///
+    /// Raises:
+    ///     ValueError: If there is no device of that type.
+    fn get_device(&self, device: &str) -> PyResult<Device> {
+        let kind: DeviceKind = device.parse().map_err(|e: InputError| e.into_py())?;
+        self.device(kind)
+            .ok_or_else(|| PyValueError::new_err(format!("The session has no {} device", kind)))
+    }
+
+    /// The devices of the session, all of them or those of one type.
+    ///
+    /// Args:
+    ///     kind: "mouse", "keyboard", "touch", "gamepad" or "pen", all devices are listed if not
+    ///         given.
+    #[pyo3(name = "devices", signature = (kind=None))]
+    fn py_devices(&self, kind: Option<&str>) -> PyResult<Vec<Device>> {
+        match kind {
+            Some(kind) => {
+                let kind: DeviceKind = kind.parse().map_err(|e: InputError| e.into_py())?;
+                Ok(self.devices_of(kind))
+            }
+            None => Ok(self.devices()),
+        }
+    }
+
+    /// What the session's devices can do: the device types, the screen size and the buttons and
+    /// keys each device accepts.
+    #[pyo3(name = "capabilities")]
+    fn py_capabilities(&self) -> PyResult<Capabilities> {
+        self.capabilities().map_err(|e| e.into_py())
+    }
+
+    /// The keys, mouse buttons and touch contacts that are down and where the cursor is.
+    ///
+    /// With the "dry-run" backend these are the backend's own state, with "enigo" they are what
+    /// the session pressed and has not released (`reconciled` is False).
+    #[pyo3(name = "state")]
+    fn py_state(&self) -> InputState {
+        self.state()
+    }
+
+    /// Release every key, button and touch contact that is currently held, centre the gamepad's
+    /// sticks, release its triggers and lift the pen.
+    ///
+    /// This also happens automatically when the session is garbage collected.
+    #[pyo3(name = "release_all")]
+    fn py_release_all(&self) -> PyResult<()> {
+        self.release_all().map_err(|e| e.into_py())
+    }
+
+    /// The policy used to interrupt timed actions, individual calls can override it with `interrupt=`.
+    #[getter(interrupt_policy)]
+    fn py_interrupt_policy(&self) -> InterruptPolicy {
+        self.interrupt_policy()
+    }
+
+    #[setter(interrupt_policy)]
+    fn py_set_interrupt_policy(&self, policy: InterruptPolicy) {
+        self.set_interrupt_policy(policy);
+    }
+
+    /// Enable a kill switch for the whole session.
+    ///
+    /// When the user moves the cursor into one of the corners, or holds every key of the hotkey on a
+    /// physical keyboard, everything held is released, running actions raise `ActionInterrupt` and
+    /// further actions raise `ActionInterrupt` until `rearm` is called.
+    ///
+    /// Args:
+    ///     corners: Any of "top_left", "top_right", "bottom_left" and "bottom_right".
+    ///     hotkey: Keys that trip the fail-safe when held together, e.g. ["ctrl", "alt", "esc"]
+    ///         (Linux only).
+    ///     margin: How close to a corner, in pixels, the cursor has to be.
+    #[pyo3(name = "enable_failsafe", signature = (corners=vec!["top_left".to_string()], hotkey=None, margin=0))]
+    fn py_enable_failsafe(
+        &self,
+        corners: Vec<String>,
+        hotkey: Option<Vec<KeyboardKey>>,
+        margin: u32,
+    ) -> PyResult<()> {
+        let corners = corners
+            .iter()
+            .map(|corner| corner.parse::<Corner>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.into_py())?;
+        let config = FailSafeConfig {
+            corners,
+            margin,
+            hotkey: hotkey.unwrap_or_default(),
+            ..Default::default()
+        };
+        self.enable_failsafe(config).map_err(|e| e.into_py())
+    }
+
+    #[pyo3(name = "disable_failsafe")]
+    fn py_disable_failsafe(&self) {
+        self.disable_failsafe();
+    }
+
+    /// Allow actions again after the fail-safe was tripped.
+    #[pyo3(name = "rearm")]
+    fn py_rearm(&self) {
+        self.rearm();
+    }
+
+    /// Whether the fail-safe has been tripped and not re-armed.
+    #[getter]
+    fn failsafe_tripped(&self) -> bool {
+        self.failsafe()
+            .is_some_and(|failsafe| failsafe.is_tripped())
+    }
+
+    /// Add human-like random variation to the mouse and keyboard of the session.
+    ///
+    /// Method signatures do not change, clicks, taps, timed moves and typing are varied internally.
+    ///
+    /// Args:
+    ///     seed: Seed of the random number generator, runs with the same seed and calls are
+    ///         reproducible.
+    ///     click_jitter: Clicks land up to this many pixels away from the cursor.
+    ///     press_time: Clicked buttons and tapped keys are held for a random time in this range, in
+    ///         seconds.
+    ///     overshoot_chance: The probability that a timed move overshoots its target and corrects.
+    ///     overshoot: How far an overshoot goes past the target, as a fraction of the distance.
+    ///     typing_interval: The average time between typed characters, in seconds.
+    ///     typing_variation: The standard deviation of the time between characters, as a fraction
+    ///         of the average.
+    ///
+    /// Raises:
+    ///     ValueError: If a time, fraction or probability is negative or not finite.
+    #[pyo3(
+        name = "enable_humanize",
+        signature = (seed=None, click_jitter=3, press_time=(0.04, 0.12), overshoot_chance=0.3, overshoot=0.05, typing_interval=0.08, typing_variation=0.4)
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn py_enable_humanize(
+        &self,
+        seed: Option<u64>,
+        click_jitter: u32,
+        press_time: (f64, f64),
+        overshoot_chance: f64,
+        overshoot: f64,
+        typing_interval: f64,
+        typing_variation: f64,
+    ) -> PyResult<()> {
+        let duration = |seconds: f64| {
+            Duration::try_from_secs_f64(seconds)
+                .map_err(|_| PyValueError::new_err("Times must be positive numbers of seconds"))
+        };
+        let profile = HumanizeProfile {
+            click_jitter,
+            press_time: (duration(press_time.0)?, duration(press_time.1)?),
+            overshoot_chance,
+            overshoot,
+            typing_interval: duration(typing_interval)?,
+            typing_variation,
+            seed,
+        };
+        profile.validate().map_err(|e| e.into_py())?;
+        self.set_humanize(Some(profile));
+        Ok(())
+    }
+
+    #[pyo3(name = "disable_humanize")]
+    fn py_disable_humanize(&self) {
+        self.set_humanize(None);
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Release everything that is held when leaving a `with` block, exceptions are not suppressed.
+    fn __exit__(
+        &self,
+        _exc_type: PyObject,
+        _exc: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.py_release_all()?;
+        Ok(false)
+    }
+
+    /// Record user input from evdev devices and save it to a file (Linux only).
+    ///
+    /// Only evdev is supported, X11 capture is not. Input that does not pass through evdev, such
+    /// as input injected into the X server by another program or a remote desktop, is missed.
+    ///
+    /// Args:
+    ///     path: The file to save the recording to.
+    ///     duration: How long to record for, in seconds.
+    ///     devices: Paths of the evdev devices to record, all keyboards and mice are recorded if not
+    ///         given.
+    ///
+    /// Raises:
+    ///     ValueError: If `duration` is negative or not a number, or a device can not be opened.
+    #[cfg(target_os = "linux")]
+    #[pyo3(signature = (path, duration, devices=None))]
+    fn record(&self, path: &str, duration: f64, devices: Option<Vec<String>>) -> PyResult<()> {
+        let duration = crate::core::result::py_duration(duration)?;
+        let mut recorder = match devices {
+            Some(devices) => record::evdev::EvdevRecorder::open(&devices),
+            None => record::evdev::EvdevRecorder::open_all(),
+        }
+        .map_err(|e| e.into_py())?;
+        let origin = self.mouse_device.location().ok();
+        let stop = std::sync::atomic::AtomicBool::new(false);
+        let recording = recorder
+            .record(Some(duration), &stop, origin)
+            .map_err(|e| e.into_py())?;
+        recording.save(path).map_err(|e| e.into_py())
+    }
+
+    /// Play back a recording.
+    ///
+    /// Args:
+    ///     path: The recording file.
+    ///     speed: Playback speed multiplier.
+    ///     coordinates: "relative" or "absolute", absolute playback moves the cursor to the recorded
+    ///         positions.
+    #[pyo3(signature = (path, speed=1.0, coordinates="relative"))]
+    fn replay(&self, path: &str, speed: f64, coordinates: &str) -> PyResult<()> {
+        let coordinates: Coordinates = coordinates.parse().map_err(|e: InputError| e.into_py())?;
+        let recording = Recording::load(path).map_err(|e| e.into_py())?;
+        let options = ReplayOptions { speed, coordinates };
+        recording
+            .replay(&self.mouse_device, &self.keyboard_device, &options)
+            .map_err(|e| e.into_py())
+    }
+}
+
+/// The names of `inputs`, as accepted wherever python takes a key or button.
+#[cfg(feature = "python")]
+fn names<T: ToString>(inputs: &[T]) -> Vec<String> {
+    inputs.iter().map(T::to_string).collect()
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Capabilities {
+    /// The device types of the session, e.g. ["mouse", "keyboard", "touch"].
+    #[getter(devices)]
+    fn py_devices(&self) -> Vec<String> {
+        names(&self.devices)
+    }
+
+    /// Width and height of the screen in pixels.
+    #[getter(screen)]
+    fn py_screen(&self) -> (i32, i32) {
+        self.screen
+    }
+
+    #[getter(mouse_buttons)]
+    fn py_mouse_buttons(&self) -> Vec<String> {
+        names(&self.mouse_buttons)
+    }
+
+    /// The named keys, letters and digits, other characters and physical keys can be pressed too.
+    #[getter(keys)]
+    fn py_keys(&self) -> Vec<String> {
+        names(&self.keys)
+    }
+
+    /// Empty if the session has no gamepad.
+    #[getter(gamepad_buttons)]
+    fn py_gamepad_buttons(&self) -> Vec<String> {
+        names(&self.gamepad_buttons)
+    }
+
+    /// Empty if the session has no pen.
+    #[getter(pen_buttons)]
+    fn py_pen_buttons(&self) -> Vec<String> {
+        names(&self.pen_buttons)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Capabilities(devices={:?}, screen={:?}, keys={})",
+            self.py_devices(),
+            self.screen,
+            self.keys.len()
+        )
+    }
+}
@@ -0,0 +1,109 @@
+//! Snapshots of what a session is holding down, e.g. for an agent's observations.
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::device::keyboard::KeyboardKey;
+use crate::device::mouse::MouseButton;
+
+/// The keys, mouse buttons and touch contacts that are down and where the cursor is, see
+/// [`Session::state`](crate::session::Session::state).
+#[cfg_attr(feature = "python", pyclass(frozen))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InputState {
+    pub keys: Vec<KeyboardKey>,
+    pub mouse_buttons: Vec<MouseButton>,
+    /// Ids of the touch contacts that are down.
+    pub touches: Vec<u32>,
+    /// `None` if the backend can not tell where the cursor is.
+    pub cursor: Option<(i32, i32)>,
+    /// Whether the keys, buttons and contacts were reported by the system. Otherwise they are
+    /// what the session pressed and has not released, which misses input from the user and other
+    /// programs.
+    pub reconciled: bool,
+}
+
+impl InputState {
+    /// The state as a fixed length vector: the cursor position (-1 if unknown), then 1.0 or 0.0
+    /// for each of `keys` and each of [`MouseButton::ALL`], then the number of touch contacts.
+    pub fn vector(&self, keys: &[KeyboardKey]) -> Vec<f32> {
+        let flag = |down: bool| if down { 1.0 } else { 0.0 };
+        let (x, y) = self.cursor.unwrap_or((-1, -1));
+        let mut vector = vec![x as f32, y as f32];
+        vector.extend(keys.iter().map(|key| flag(self.keys.contains(key))));
+        vector.extend(
+            MouseButton::ALL
+                .iter()
+                .map(|button| flag(self.mouse_buttons.contains(button))),
+        );
+        vector.push(self.touches.len() as f32);
+        vector
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl InputState {
+    /// The keys that are down, by name.
+    #[getter(keys)]
+    fn py_keys(&self) -> Vec<String> {
+        self.keys.iter().map(KeyboardKey::to_string).collect()
+    }
+
+    #[getter(mouse_buttons)]
+    fn py_mouse_buttons(&self) -> Vec<String> {
+        self.mouse_buttons
+            .iter()
+            .map(MouseButton::to_string)
+            .collect()
+    }
+
+    /// Ids of the touch contacts that are down.
+    #[getter(touches)]
+    fn py_touches(&self) -> Vec<u32> {
+        self.touches.clone()
+    }
+
+    /// The cursor position, None if the backend can not tell.
+    #[getter(cursor)]
+    fn py_cursor(&self) -> Option<(i32, i32)> {
+        self.cursor
+    }
+
+    /// Whether the keys, buttons and contacts were reported by the system rather than tracked by
+    /// the session, tracking misses input from the user and other programs.
+    #[getter(reconciled)]
+    fn py_reconciled(&self) -> bool {
+        self.reconciled
+    }
+
+    /// The state as a list of floats of a fixed length, for an observation space.
+    ///
+    /// The list is the cursor position (-1 if unknown), then 1.0 or 0.0 for each of `keys` and
+    /// for the "left", "right" and "middle" mouse buttons, then the number of touch contacts.
+    ///
+    /// Args:
+    ///     keys: The keys to include, by default `Capabilities.keys` (the named keys, letters
+    ///         and digits).
+    ///
+    /// Example:
+    ///     observation = numpy.asarray(session.state().vector(), dtype=numpy.float32)
+    #[pyo3(name = "vector", signature = (keys=None))]
+    fn py_vector(&self, keys: Option<Vec<KeyboardKey>>) -> Vec<f32> {
+        self.vector(&keys.unwrap_or_else(KeyboardKey::common))
+    }
+
+    fn __repr__(&self) -> String {
+        let cursor = match self.cursor {
+            Some((x, y)) => format!("({}, {})", x, y),
+            None => "None".to_string(),
+        };
+        format!(
+            "InputState(keys={:?}, mouse_buttons={:?}, touches={:?}, cursor={}, reconciled={})",
+            self.py_keys(),
+            self.py_mouse_buttons(),
+            self.touches,
+            cursor,
+            if self.reconciled { "True" } else { "False" },
+        )
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyinput::core::cancel::{ActionHandle, CancelToken};
+use anyinput::core::result::InputError;
+use anyinput::core::{KeyboardHandler, MouseHandler};
+use anyinput::device::keyboard::{KeyboardDevice, KeyboardKey};
+use anyinput::device::mouse::{MouseButton, MouseDevice};
+
+mod common;
+
+#[test]
+fn cancelled_actions_stop_and_release() {
+    let (backend, trace) = common::dry_run();
+    let mouse = MouseDevice::new(Arc::new(backend));
+    let token = CancelToken::new();
+    let device = mouse.with_cancel_token(token.clone());
+    let start = Instant::now();
+    let handle = ActionHandle::spawn(token, move || {
+        device.hold(MouseButton::Left, Duration::from_secs(5))
+    });
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(!handle.is_done());
+    handle.cancel();
+    let result = handle.wait(Some(Duration::from_secs(1)));
+    assert!(matches!(result, Some(Err(InputError::Cancelled))));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(handle.receipt().unwrap().truncated);
+    assert!(mouse.held_buttons().is_empty());
+    assert_eq!(trace.lines(), ["mouse press left", "mouse release left"]);
+
+    // the device that was given the token refuses further actions, others are unaffected
+    let cancelled = mouse.with_cancel_token(cancelled_token());
+    assert!(matches!(
+        cancelled.press(MouseButton::Left),
+        Err(InputError::Cancelled)
+    ));
+    mouse.click(MouseButton::Right).unwrap();
+}
+
+#[test]
+fn cancelled_devices_send_nothing() {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(backend);
+    let mouse = MouseDevice::new(backend.clone());
+    let keyboard = KeyboardDevice::new(backend);
+    let token = cancelled_token();
+    let (mouse, keyboard) = (
+        mouse.with_cancel_token(token.clone()),
+        keyboard.with_cancel_token(token.clone()),
+    );
+    let handle = ActionHandle::spawn(token, move || {
+        let results = [
+            mouse.move_abs(10, 10, Duration::ZERO),
+            mouse.move_rel(10, 10, Duration::from_millis(20)),
+            MouseHandler::hold(&mouse, MouseButton::Left, Duration::from_millis(20)),
+            mouse.drag(MouseButton::Left, 10, 10, Duration::ZERO),
+            KeyboardHandler::hold(&keyboard, KeyboardKey::Shift, Duration::from_millis(20)),
+            keyboard.text("abc", Duration::ZERO),
+        ];
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(InputError::Cancelled))));
+        mouse
+            .location()
+            .map(|location| assert_eq!(location, (960, 540)))
+    });
+    assert!(matches!(
+        handle.wait(Some(Duration::from_secs(1))),
+        Some(Ok(()))
+    ));
+    assert_eq!(trace.lines(), Vec::<String>::new());
+}
+
+/// A token that has already been cancelled.
+fn cancelled_token() -> CancelToken {
+    let token = CancelToken::new();
+    token.cancel();
+    token
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+/// The command line tool with the dry-run backend.
+fn anyinput(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_anyinput"));
+    command.arg("--dry-run").args(args);
+    command
+}
+
+/// The exit code and printed actions of a run, without their timestamps.
+fn run(args: &[&str]) -> (i32, Vec<String>) {
+    let output = anyinput(args).output().unwrap();
+    (output.status.code().unwrap(), actions(&output))
+}
+
+fn actions(output: &Output) -> Vec<String> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.split_once("] ").map_or(line, |(_, action)| action))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn subcommands_print_their_actions() {
+    let cases: &[(&[&str], &[&str])] = &[
+        (&["click"], &["mouse click left"]),
+        (&["click", "middle"], &["mouse click middle"]),
+        (&["move", "10", "20"], &["mouse move to (10, 20)"]),
+        (
+            &["move", "--relative", "-5", "5"],
+            &["mouse move by (-5, 5) to (955, 545)"],
+        ),
+        (
+            &["drag", "5", "5", "--button", "right"],
+            &[
+                "mouse press right",
+                "mouse move by (5, 5) to (965, 545)",
+                "mouse release right",
+            ],
+        ),
+        (&["scroll", "3"], &["mouse scroll (0, 3)"]),
+        (&["scroll", "-1", "2"], &["mouse scroll (2, -1)"]),
+        (&["key", "a"], &["key press 'a'", "key release 'a'"]),
+        (
+            &["key", "shift", "--hold", "0.05"],
+            &["key press Shift", "key release Shift"],
+        ),
+        (
+            &["hotkey", "ctrl", "t"],
+            &[
+                "key press Control",
+                "key press 't'",
+                "key release 't'",
+                "key release Control",
+            ],
+        ),
+        (&["type", "hi"], &["type \"hi\""]),
+    ];
+    for (args, expected) in cases {
+        let (code, actions) = run(args);
+        assert_eq!(code, 0, "{:?}", args);
+        assert_eq!(actions, *expected, "{:?}", args);
+    }
+
+    let (code, moves) = run(&["move", "10", "20", "--duration", "0.05"]);
+    assert_eq!(code, 0);
+    assert_eq!(moves.last().unwrap(), "mouse move to (10, 20)");
+}
+
+#[test]
+fn errors_have_exit_codes() {
+    let output = Command::new(env!("CARGO_BIN_EXE_anyinput"))
+        .arg("backends")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|line| line == "dry-run"));
+
+    // usage errors come from the argument parser
+    assert_eq!(run(&["click", "nope"]), (2, Vec::new()));
+    assert_eq!(
+        run(&["move", "1", "2", "--duration", "-1"]),
+        (1, Vec::new())
+    );
+    assert_eq!(
+        run(&["play", "/nonexistent/recording.json"]),
+        (1, Vec::new())
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn ctrl_c_releases_what_is_held() {
+    for args in [
+        &["key", "shift", "--hold", "5"][..],
+        &["drag", "0", "0", "--duration", "5"],
+    ] {
+        let start = Instant::now();
+        let child = anyinput(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        unsafe { libc::kill(child.id() as i32, libc::SIGINT) };
+        let output = child.wait_with_output().unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(output.status.code(), Some(130), "{:?}", args);
+        let actions = actions(&output);
+        let (pressed, released) = match args[0] {
+            "key" => ("key press Shift", "key release Shift"),
+            _ => ("mouse press left", "mouse release left"),
+        };
+        assert_eq!(actions.first().unwrap(), pressed);
+        assert_eq!(actions.last().unwrap(), released);
+    }
+}
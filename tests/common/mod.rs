@@ -0,0 +1,43 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use anyinput::backends::dry_run::{DryRunBackend, DryRunConfig};
+
+/// The trace written by a dry-run backend.
+#[derive(Clone, Default)]
+pub struct Trace(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Trace {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Trace {
+    /// The lines written so far, without their timestamps.
+    pub fn lines(&self) -> Vec<String> {
+        let trace = self.0.lock().unwrap();
+        String::from_utf8_lossy(&trace)
+            .lines()
+            .map(|line| line.split_once("] ").map_or(line, |(_, action)| action))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// A dry-run backend with a 1920x1080 screen that accepts any key, and its trace.
+pub fn dry_run() -> (DryRunBackend, Trace) {
+    let trace = Trace::default();
+    let backend = DryRunBackend::new(DryRunConfig {
+        output: Box::new(trace.clone()),
+        ..DryRunConfig::default()
+    });
+    (backend, trace)
+}
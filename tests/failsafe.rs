@@ -0,0 +1,205 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyinput::backends::Handlers;
+use anyinput::core::cancel::{ActionHandle, CancelToken};
+use anyinput::core::failsafe::{Corner, FailSafe, FailSafeConfig};
+use anyinput::core::result::{InputError, InputResult};
+use anyinput::core::{GamepadHandler, KeyboardHandler, MouseHandler, PenHandler};
+use anyinput::device::gamepad::{GamepadButton, GamepadDevice, Stick, Trigger};
+use anyinput::device::keyboard::KeyboardKey;
+use anyinput::device::mouse::{MouseButton, MouseDevice, PathPoint};
+use anyinput::device::pen::{PenButton, PenDevice, PenPoint};
+use anyinput::session::Session;
+
+mod common;
+
+/// Whether the fail-safe trips with the cursor at `(x, y)`, re-arming it afterwards.
+fn trips_at(failsafe: &FailSafe, cursor: &dyn MouseHandler, x: i32, y: i32) -> bool {
+    cursor.move_abs(x, y, Duration::ZERO).unwrap();
+    let tripped = failsafe.check().is_err();
+    failsafe.rearm();
+    tripped
+}
+
+#[test]
+fn corners_trip_within_the_margin() {
+    let (backend, _) = common::dry_run();
+    let cursor = Arc::new(backend);
+    let failsafe = FailSafe::start(FailSafeConfig::default(), cursor.clone()).unwrap();
+    assert!(trips_at(&failsafe, &*cursor, 0, 0));
+    assert!(!trips_at(&failsafe, &*cursor, 1, 0));
+    assert!(!trips_at(&failsafe, &*cursor, 1919, 1079));
+
+    let config = FailSafeConfig {
+        corners: vec![Corner::TopRight, Corner::BottomRight],
+        margin: 5,
+        ..FailSafeConfig::default()
+    };
+    let failsafe = FailSafe::start(config, cursor.clone()).unwrap();
+    assert!(trips_at(&failsafe, &*cursor, 1919, 0));
+    assert!(trips_at(&failsafe, &*cursor, 1914, 5));
+    assert!(!trips_at(&failsafe, &*cursor, 1913, 5));
+    assert!(!trips_at(&failsafe, &*cursor, 1914, 6));
+    assert!(trips_at(&failsafe, &*cursor, 1914, 1074));
+    assert!(!trips_at(&failsafe, &*cursor, 1914, 1073));
+    assert!(!trips_at(&failsafe, &*cursor, 0, 0));
+
+    let config = FailSafeConfig {
+        corners: Vec::new(),
+        ..FailSafeConfig::default()
+    };
+    let failsafe = FailSafe::start(config, cursor.clone()).unwrap();
+    assert!(!trips_at(&failsafe, &*cursor, 0, 0));
+}
+
+#[test]
+fn tripping_releases_and_interrupts_running_actions() {
+    let (backend, trace) = common::dry_run();
+    let mouse = MouseDevice::new(Arc::new(backend));
+    let failsafe =
+        FailSafe::start(FailSafeConfig::default(), Arc::new(common::dry_run().0)).unwrap();
+    mouse.set_failsafe(Some(failsafe.clone()));
+    let token = CancelToken::new();
+    let device = mouse.with_cancel_token(token.clone());
+    let handle = ActionHandle::spawn(token, move || {
+        device.hold(MouseButton::Left, Duration::from_secs(5))
+    });
+    std::thread::sleep(Duration::from_millis(50));
+    failsafe.trip();
+    let result = handle.wait(Some(Duration::from_secs(1)));
+    assert!(matches!(result, Some(Err(InputError::Interrupt))));
+    assert!(mouse.held_buttons().is_empty());
+    // the trip releases the button, the interrupted hold does not release it again
+    assert_eq!(trace.lines(), ["mouse press left", "mouse release left"]);
+    assert!(matches!(
+        mouse.click(MouseButton::Left),
+        Err(InputError::Interrupt)
+    ));
+
+    failsafe.rearm();
+    mouse.click(MouseButton::Left).unwrap();
+}
+
+#[test]
+fn tripped_sessions_send_nothing() {
+    let (backend, trace) = common::dry_run();
+    let session = Session::from_handlers(Handlers::from_backend(backend));
+    session.enable_failsafe(FailSafeConfig::default()).unwrap();
+    session.failsafe().unwrap().trip();
+    let mouse = session.mouse();
+    let keyboard = session.keyboard();
+    let path = [PathPoint {
+        x: 100,
+        y: 100,
+        time: Duration::ZERO,
+        down: true,
+    }];
+    let actions: [(&str, &dyn Fn() -> InputResult); 13] = [
+        ("press", &|| MouseHandler::press(mouse, MouseButton::Left)),
+        ("click", &|| mouse.click(MouseButton::Left)),
+        ("scroll", &|| mouse.scroll(0, 1)),
+        ("move_abs", &|| mouse.move_abs(500, 500, Duration::ZERO)),
+        ("move_rel", &|| mouse.move_rel(5, 5, Duration::ZERO)),
+        ("timed move", &|| {
+            mouse.move_abs(500, 500, Duration::from_millis(50))
+        }),
+        ("hold", &|| {
+            MouseHandler::hold(mouse, MouseButton::Left, Duration::from_millis(50))
+        }),
+        ("drag", &|| {
+            mouse.drag(MouseButton::Left, 5, 5, Duration::ZERO)
+        }),
+        ("play_path", &|| mouse.play_path(&path, MouseButton::Left)),
+        ("key press", &|| {
+            KeyboardHandler::press(keyboard, KeyboardKey::Shift)
+        }),
+        ("tap", &|| {
+            KeyboardHandler::tap(keyboard, KeyboardKey::Shift)
+        }),
+        ("key hold", &|| {
+            KeyboardHandler::hold(keyboard, KeyboardKey::Shift, Duration::from_millis(50))
+        }),
+        ("text", &|| keyboard.text("abc", Duration::ZERO)),
+    ];
+    for (name, action) in actions {
+        assert!(matches!(action(), Err(InputError::Interrupt)), "{}", name);
+    }
+    assert_eq!(trace.lines(), Vec::<String>::new());
+    assert_eq!(mouse.location().unwrap(), (960, 540));
+}
+
+#[test]
+fn stopped_gamepads_send_nothing() {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(backend);
+    let tripped = GamepadDevice::new(backend.clone());
+    let failsafe = FailSafe::start(FailSafeConfig::default(), backend.clone()).unwrap();
+    tripped.set_failsafe(Some(failsafe.clone()));
+    failsafe.trip();
+    let cancelled = GamepadDevice::new(backend).with_cancel_token(cancelled_token());
+    for device in [&tripped, &cancelled] {
+        let actions: [(&str, &dyn Fn() -> InputResult); 6] = [
+            ("press", &|| device.press(GamepadButton::A)),
+            ("hold", &|| {
+                device.hold(GamepadButton::A, Duration::from_millis(50))
+            }),
+            ("move_stick", &|| device.move_stick(Stick::Left, 1.0, 0.0)),
+            ("move_stick_over", &|| {
+                device.move_stick_over(Stick::Left, 1.0, 0.0, Duration::from_millis(50))
+            }),
+            ("hold_stick", &|| {
+                device.hold_stick(Stick::Left, 1.0, 0.0, Duration::from_millis(50))
+            }),
+            ("set_trigger", &|| device.set_trigger(Trigger::Left, 1.0)),
+        ];
+        for (name, action) in actions {
+            let result = action();
+            assert!(
+                matches!(result, Err(InputError::Interrupt | InputError::Cancelled)),
+                "{}",
+                name
+            );
+        }
+    }
+    assert_eq!(trace.lines(), Vec::<String>::new());
+}
+
+#[test]
+fn stopped_pens_send_nothing() {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(backend);
+    let tripped = PenDevice::new(backend.clone());
+    let failsafe = FailSafe::start(FailSafeConfig::default(), backend.clone()).unwrap();
+    tripped.set_failsafe(Some(failsafe.clone()));
+    failsafe.trip();
+    let cancelled = PenDevice::new(backend).with_cancel_token(cancelled_token());
+    let points = [PenPoint::new(100, 100), PenPoint::new(200, 200)];
+    for device in [&tripped, &cancelled] {
+        let actions: [(&str, &dyn Fn() -> InputResult); 5] = [
+            ("hover", &|| device.hover(points[0])),
+            ("down", &|| device.down(points[0])),
+            ("press", &|| device.press(PenButton::Primary)),
+            ("stroke", &|| device.stroke(&points, Duration::ZERO)),
+            ("timed stroke", &|| {
+                device.stroke(&points, Duration::from_millis(50))
+            }),
+        ];
+        for (name, action) in actions {
+            let result = action();
+            assert!(
+                matches!(result, Err(InputError::Interrupt | InputError::Cancelled)),
+                "{}",
+                name
+            );
+        }
+    }
+    assert_eq!(trace.lines(), Vec::<String>::new());
+}
+
+/// A token that has already been cancelled.
+fn cancelled_token() -> CancelToken {
+    let token = CancelToken::new();
+    token.cancel();
+    token
+}
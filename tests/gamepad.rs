@@ -0,0 +1,44 @@
+use anyinput::backends::uinput::UinputGamepad;
+use anyinput::core::GamepadHandler;
+use anyinput::device::gamepad::{GamepadButton, Stick, Trigger};
+use anyinput::evdev::{
+    codes, RawEvent, ABS_HAT0X, ABS_HAT0Y, ABS_RZ, ABS_X, ABS_Y, EV_ABS, EV_KEY, EV_SYN,
+};
+
+/// The events written by the gamepad, without the `SYN_REPORT`s and timestamps.
+fn events(gamepad: UinputGamepad<Vec<u8>>) -> Vec<(u16, u16, i32)> {
+    let stream = gamepad.into_inner();
+    let mut reader = stream.as_slice();
+    let mut events = Vec::new();
+    while let Some(event) = RawEvent::read_from(&mut reader).unwrap() {
+        if event.kind != EV_SYN {
+            events.push((event.kind, event.code, event.value));
+        }
+    }
+    events
+}
+
+#[test]
+fn gamepad_writes_xbox_events() {
+    let gamepad = UinputGamepad::new(Vec::new());
+    gamepad.tap(GamepadButton::A).unwrap();
+    gamepad.press(GamepadButton::DpadUp).unwrap();
+    gamepad.release(GamepadButton::DpadUp).unwrap();
+    gamepad.move_stick(Stick::Left, 1.0, -0.5).unwrap();
+    gamepad.set_trigger(Trigger::Right, 1.0).unwrap();
+    assert_eq!(gamepad.stick(Stick::Left).unwrap(), (1.0, -0.5));
+    assert_eq!(
+        events(gamepad),
+        [
+            (EV_KEY, codes::BTN_A, 1),
+            (EV_KEY, codes::BTN_A, 0),
+            (EV_ABS, ABS_HAT0X, 0),
+            (EV_ABS, ABS_HAT0Y, -1),
+            (EV_ABS, ABS_HAT0X, 0),
+            (EV_ABS, ABS_HAT0Y, 0),
+            (EV_ABS, ABS_X, 32767),
+            (EV_ABS, ABS_Y, -16384),
+            (EV_ABS, ABS_RZ, 255),
+        ]
+    );
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "python")]
+
+use std::sync::Arc;
+
+use anyinput::backends::Handlers;
+use anyinput::core::GamepadHandler;
+use anyinput::device::gamepad::GamepadButton;
+use anyinput::device::keyboard::KeyboardKey;
+use anyinput::device::mouse::MouseButton;
+use anyinput::device::pen::PenButton;
+use anyinput::device::DeviceKind;
+use anyinput::gym::{ActionSpace, Step};
+use anyinput::session::Session;
+
+mod common;
+
+/// A dry-run session, with a gamepad and a pen if `gamepad` is true, and its trace.
+fn session(gamepad: bool) -> (Session, common::Trace) {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(backend);
+    let mut handlers = Handlers::from_shared(Arc::clone(&backend));
+    if gamepad {
+        handlers = handlers
+            .with_gamepad(Arc::clone(&backend) as Arc<dyn GamepadHandler + Send + Sync>)
+            .with_pen(backend);
+    }
+    (Session::from_handlers(handlers), trace)
+}
+
+#[test]
+fn capabilities_describe_the_devices() {
+    let (full, _) = session(true);
+    let capabilities = full.capabilities().unwrap();
+    assert_eq!(capabilities.devices, DeviceKind::ALL);
+    assert_eq!(capabilities.screen, (1920, 1080));
+    assert_eq!(capabilities.mouse_buttons, MouseButton::ALL);
+    assert_eq!(capabilities.keys, KeyboardKey::common());
+    assert_eq!(capabilities.gamepad_buttons, GamepadButton::ALL);
+    assert_eq!(capabilities.pen_buttons, PenButton::ALL);
+
+    let (plain, _) = session(false);
+    let capabilities = plain.capabilities().unwrap();
+    assert_eq!(
+        capabilities.devices,
+        [DeviceKind::Mouse, DeviceKind::Keyboard, DeviceKind::Touch]
+    );
+    assert!(capabilities.gamepad_buttons.is_empty());
+    assert!(capabilities.pen_buttons.is_empty());
+}
+
+#[test]
+fn steps_press_and_release_what_changed() {
+    let (session, trace) = session(false);
+    let keys = vec![KeyboardKey::Shift, KeyboardKey::Unicode('a')];
+    let mut space = ActionSpace::new(&session, Some(keys), 10.0, false).unwrap();
+    space
+        .step(&Step {
+            key: Some(2),
+            held_keys: Some(vec![true, false]),
+            mouse_buttons: Some(vec![true, false, false]),
+            cursor: Some((25.0, -3.4)),
+            ..Step::default()
+        })
+        .unwrap();
+    space
+        .step(&Step {
+            key: Some(0),
+            held_keys: Some(vec![true, false]),
+            mouse_buttons: Some(vec![false, false, false]),
+            ..Step::default()
+        })
+        .unwrap();
+    assert_eq!(
+        trace.lines(),
+        [
+            "key press Shift",
+            "mouse press left",
+            "mouse move by (10, -3) to (970, 537)",
+            "key press 'a'",
+            "key release 'a'",
+            "mouse release left",
+        ]
+    );
+}
+
+#[test]
+fn held_keys_are_not_tapped() {
+    let (session, trace) = session(false);
+    let keys = vec![KeyboardKey::Unicode('a')];
+    let mut space = ActionSpace::new(&session, Some(keys), 10.0, false).unwrap();
+    space
+        .step(&Step {
+            key: Some(1),
+            held_keys: Some(vec![true]),
+            ..Step::default()
+        })
+        .unwrap();
+    assert_eq!(session.keyboard().held_keys(), [KeyboardKey::Unicode('a')]);
+    space.release_all().unwrap();
+    assert_eq!(trace.lines(), ["key press 'a'", "key release 'a'"]);
+    assert!(session.keyboard().held_keys().is_empty());
+}
+
+#[test]
+fn invalid_steps_are_errors() {
+    let (session, _) = session(false);
+    let keys = vec![KeyboardKey::Unicode('a')];
+    let mut space = ActionSpace::new(&session, Some(keys.clone()), 10.0, false).unwrap();
+    let key = Step {
+        key: Some(2),
+        ..Step::default()
+    };
+    assert!(space.step(&key).is_err());
+    let held = Step {
+        held_keys: Some(vec![true, true]),
+        ..Step::default()
+    };
+    assert!(space.step(&held).is_err());
+    assert!(ActionSpace::new(&session, Some(keys), 10.0, true).is_err());
+}
+
+#[test]
+fn gamepad_steps_are_released_with_everything_else() {
+    let (session, trace) = session(true);
+    let mut space = ActionSpace::new(&session, Some(Vec::new()), 10.0, true).unwrap();
+    let mut buttons = vec![false; GamepadButton::ALL.len()];
+    buttons[0] = true;
+    space
+        .step(&Step {
+            mouse_buttons: Some(vec![false, true, false]),
+            gamepad_buttons: Some(buttons),
+            sticks: Some([(2.0, 0.5), (0.0, 0.0)]),
+            triggers: Some([0.25, -1.0]),
+            ..Step::default()
+        })
+        .unwrap();
+    space.release_all().unwrap();
+    // stick and trigger values are clamped to their ranges
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse press right",
+            "gamepad press a",
+            "gamepad left stick to (1.00, 0.50)",
+            "gamepad right stick to (0.00, 0.00)",
+            "gamepad left trigger to 0.25",
+            "gamepad right trigger to 0.00",
+            "mouse release right",
+            "gamepad release a",
+            "gamepad left trigger to 0.00",
+            "gamepad left stick to (0.00, 0.00)",
+        ]
+    );
+}
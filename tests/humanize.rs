@@ -0,0 +1,77 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyinput::core::humanize::{HumanizeProfile, Humanizer};
+use anyinput::core::MouseHandler;
+use anyinput::device::mouse::{MouseButton, MouseDevice};
+
+mod common;
+
+fn humanizer(seed: u64) -> Humanizer {
+    Humanizer::new(HumanizeProfile {
+        seed: Some(seed),
+        ..HumanizeProfile::default()
+    })
+}
+
+#[test]
+fn the_same_seed_gives_the_same_rhythm() {
+    let gaps = |seed| humanizer(seed).typing_gaps(20, None);
+    assert_eq!(gaps(7), gaps(7));
+    assert_ne!(gaps(7), gaps(8));
+
+    // the clicks land at the same offsets
+    let clicks = |seed| {
+        let (backend, trace) = common::dry_run();
+        let mouse = MouseDevice::new(Arc::new(backend));
+        mouse.set_humanizer(Some(Arc::new(humanizer(seed))));
+        for _ in 0..3 {
+            mouse.click(MouseButton::Left).unwrap();
+        }
+        trace.lines()
+    };
+    assert_eq!(clicks(7), clicks(7));
+    assert_ne!(clicks(7), clicks(8));
+}
+
+#[test]
+fn typing_gaps_add_up_to_the_duration() {
+    let humanizer = humanizer(3);
+    for duration in [Duration::from_millis(250), Duration::from_secs(3)] {
+        for count in [1, 2, 17] {
+            let gaps = humanizer.typing_gaps(count, Some(duration));
+            assert_eq!(gaps.len(), count);
+            let total: Duration = gaps.iter().sum();
+            let error = total.abs_diff(duration);
+            assert!(error <= Duration::from_nanos(count as u64), "{:?}", error);
+        }
+    }
+    // without a duration the gaps vary around the typing interval
+    let gaps = humanizer.typing_gaps(200, None);
+    let interval = HumanizeProfile::default().typing_interval;
+    let mean = gaps.iter().sum::<Duration>() / 200;
+    assert!(mean.abs_diff(interval) < interval / 5, "{:?}", mean);
+    assert!(gaps.iter().any(|gap| *gap != gaps[0]));
+}
+
+#[test]
+fn profiles_must_be_finite() {
+    assert!(HumanizeProfile::default().validate().is_ok());
+    let invalid = [
+        HumanizeProfile {
+            typing_variation: f64::INFINITY,
+            ..HumanizeProfile::default()
+        },
+        HumanizeProfile {
+            overshoot: f64::NAN,
+            ..HumanizeProfile::default()
+        },
+        HumanizeProfile {
+            overshoot_chance: -0.5,
+            ..HumanizeProfile::default()
+        },
+    ];
+    for profile in invalid {
+        assert!(profile.validate().is_err(), "{:?}", profile);
+    }
+}
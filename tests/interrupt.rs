@@ -0,0 +1,192 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyinput::core::interrupt::{InterruptCondition, InterruptPolicy};
+use anyinput::core::receipt::{self, Receipt};
+use anyinput::core::result::{InputError, InputResult};
+use anyinput::core::{KeyboardHandler, MouseHandler};
+use anyinput::device::keyboard::{KeyboardDevice, KeyboardKey};
+use anyinput::device::mouse::{MouseButton, MouseDevice};
+
+mod common;
+
+/// A policy that interrupts once `delay` has passed.
+fn after(delay: Duration) -> InterruptPolicy {
+    let start = Instant::now();
+    let late = move || start.elapsed() >= delay;
+    InterruptPolicy::none().with(InterruptCondition::Custom(Arc::new(late)))
+}
+
+/// Run `action` and take its receipt.
+fn measured(action: impl FnOnce() -> InputResult) -> (InputResult, Receipt) {
+    let result = action();
+    (result, receipt::take_last().unwrap())
+}
+
+#[test]
+fn moving_the_cursor_interrupts_holds() {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(backend);
+    let mouse = MouseDevice::new(Arc::clone(&backend) as _);
+    mouse.set_interrupt_policy(
+        InterruptPolicy::none().with(InterruptCondition::CursorMoved { threshold: 5 }),
+    );
+    let start = Instant::now();
+    let (result, receipt) = std::thread::scope(|scope| {
+        let hold =
+            scope.spawn(|| measured(|| mouse.hold(MouseButton::Left, Duration::from_secs(2))));
+        // the user moves the cursor, within the threshold and then past it
+        std::thread::sleep(Duration::from_millis(50));
+        backend.move_rel(3, 0, Duration::ZERO).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        backend.move_rel(10, 0, Duration::ZERO).unwrap();
+        hold.join().unwrap()
+    });
+    assert!(matches!(result, Err(InputError::Interrupt)));
+    assert!(receipt.truncated);
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(mouse.held_buttons().is_empty());
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse press left",
+            "mouse move by (3, 0) to (963, 540)",
+            "mouse move by (10, 0) to (973, 540)",
+            "mouse release left",
+        ]
+    );
+}
+
+#[test]
+fn custom_predicates_interrupt_holds() {
+    let (backend, trace) = common::dry_run();
+    let keyboard = KeyboardDevice::new(Arc::new(backend));
+    keyboard.set_interrupt_policy(after(Duration::from_millis(50)));
+    let start = Instant::now();
+    let (result, receipt) = measured(|| keyboard.hold(KeyboardKey::Shift, Duration::from_secs(2)));
+    assert!(matches!(result, Err(InputError::Interrupt)));
+    assert!(receipt.truncated);
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(keyboard.held_keys().is_empty());
+    assert_eq!(trace.lines(), ["key press Shift", "key release Shift"]);
+}
+
+#[test]
+fn custom_predicates_interrupt_moves() {
+    let (backend, trace) = common::dry_run();
+    let mouse = MouseDevice::new(Arc::new(backend));
+    mouse.set_interrupt_policy(after(Duration::from_millis(50)));
+    let (result, receipt) = measured(|| mouse.move_abs(0, 540, Duration::from_secs(2)));
+    assert!(matches!(result, Err(InputError::Interrupt)));
+    assert!(receipt.truncated);
+    // the move stops part way
+    let (x, y) = receipt.cursor.unwrap();
+    assert!(0 < x && x < 960 && y == 540, "{:?}", (x, y));
+    assert_eq!(mouse.location().unwrap(), (x, y));
+    assert!(!trace.lines().is_empty());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn key_presses_are_counted_by_the_monitor() {
+    use anyinput::evdev::monitor::KeyMonitor;
+    use anyinput::evdev::{RawEvent, EV_KEY, EV_SYN};
+
+    let path = std::env::temp_dir().join(format!("anyinput-keys-{}", std::process::id()));
+    let mut events = Vec::new();
+    for (kind, value) in [
+        (EV_KEY, 1),
+        (EV_SYN, 0),
+        (EV_KEY, 0),
+        (EV_KEY, 1),
+        (EV_KEY, 2),
+    ] {
+        RawEvent::new(kind, 30, value)
+            .write_to(&mut events)
+            .unwrap();
+    }
+    std::fs::write(&path, events).unwrap();
+    let monitor = KeyMonitor::start(std::slice::from_ref(&path)).unwrap();
+    let start = Instant::now();
+    // repeats (value 2) are not presses
+    while monitor.presses() < 2 && start.elapsed() < Duration::from_secs(1) {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(monitor.presses(), 2);
+    assert_eq!(monitor.pressed(), [30]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn key_pressed_fails_without_a_keyboard_to_monitor() {
+    use anyinput::evdev::monitor::KeyMonitor;
+
+    // only meaningful where the physical keyboards can not be read, e.g. in CI
+    if KeyMonitor::global().is_ok() {
+        return;
+    }
+    let (backend, trace) = common::dry_run();
+    let keyboard = KeyboardDevice::new(Arc::new(backend));
+    keyboard.set_interrupt_policy(InterruptPolicy::none().with(InterruptCondition::KeyPressed));
+    let result = keyboard.hold(KeyboardKey::Shift, Duration::from_millis(50));
+    assert!(matches!(result, Err(InputError::Error(_))));
+    assert!(trace.lines().is_empty());
+}
+
+/// A predicate that raises an exception counts as an interrupt, checked through the python module.
+#[cfg(all(feature = "python", target_os = "linux"))]
+#[test]
+fn python_predicate_exceptions_interrupt() {
+    use std::process::Command;
+
+    let Ok(python) = Command::new("python3").arg("--version").output() else {
+        eprintln!("python3 is not installed, skipping");
+        return;
+    };
+    assert!(python.status.success());
+    // the extension module is the library built next to the test binary
+    let deps = std::env::current_exe().unwrap();
+    let module = deps.parent().unwrap().join("libanyinput.so");
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("python");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(module, dir.join("anyinput.so")).unwrap();
+    let script = r#"
+import anyinput
+
+def predicate():
+    raise RuntimeError("broken predicate")
+
+session = anyinput.Session("dry-run")
+policy = anyinput.InterruptPolicy(predicate=predicate)
+handle = session.mouse.hold_async("left", 2.0, interrupt=policy)
+try:
+    handle.wait()
+except anyinput.ActionInterrupt:
+    print("interrupted")
+print("truncated" if handle.receipt.truncated else "finished")
+print(session.state().mouse_buttons)
+"#;
+    let output = Command::new("python3")
+        .args(["-c", script])
+        .env("PYTHONPATH", &dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.split_once("] ").map_or(line, |(_, action)| action))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "mouse press left",
+            "mouse release left",
+            "interrupted",
+            "truncated",
+            "[]",
+        ]
+    );
+}
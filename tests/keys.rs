@@ -0,0 +1,223 @@
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use anyinput::core::action::DeviceAction;
+use anyinput::device::gamepad::{GamepadButton, Stick, Trigger};
+use anyinput::device::keyboard::KeyboardKey;
+use anyinput::device::mouse::MouseButton;
+use anyinput::device::pen::{PenButton, PenPoint, PenTool};
+
+/// Check that `value` survives its string form and its JSON form.
+fn round_trip<T>(value: T)
+where
+    T: ToString + FromStr + Serialize + DeserializeOwned + PartialEq + Debug,
+    T::Err: Debug,
+{
+    assert_eq!(value.to_string().parse::<T>().unwrap(), value);
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(json, serde_json::to_string(&value.to_string()).unwrap());
+    assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+}
+
+#[test]
+fn keys_and_buttons_round_trip() {
+    for key in KeyboardKey::NAMED {
+        round_trip(key);
+    }
+    round_trip(KeyboardKey::Unicode(' '));
+    round_trip(KeyboardKey::Unicode(':'));
+    round_trip(KeyboardKey::Unicode('é'));
+    round_trip("code:KeyW".parse::<KeyboardKey>().unwrap());
+    for button in MouseButton::ALL {
+        round_trip(button);
+    }
+    for button in GamepadButton::ALL {
+        round_trip(button);
+    }
+    for stick in Stick::ALL {
+        round_trip(stick);
+    }
+    for trigger in Trigger::ALL {
+        round_trip(trigger);
+    }
+    for button in PenButton::ALL {
+        round_trip(button);
+    }
+    for tool in PenTool::ALL {
+        round_trip(tool);
+    }
+}
+
+#[test]
+fn key_aliases_parse() {
+    for alias in ["ctrl", "control", "lctrl", "CTRL", "Control"] {
+        assert_eq!(alias.parse::<KeyboardKey>().unwrap(), KeyboardKey::Control);
+    }
+    for alias in ["page-up", "page_up", "PageUp", "pgup"] {
+        assert_eq!(alias.parse::<KeyboardKey>().unwrap(), KeyboardKey::PageUp);
+    }
+    assert_eq!(
+        "space".parse::<KeyboardKey>().unwrap(),
+        KeyboardKey::Unicode(' ')
+    );
+    assert!("hyper".parse::<KeyboardKey>().is_err());
+    assert!(serde_json::from_str::<KeyboardKey>(r#"{"unicode": "a"}"#).is_err());
+}
+
+#[test]
+fn device_actions_round_trip() {
+    let button = MouseButton::Right;
+    let key = KeyboardKey::Unicode(':');
+    let duration = Duration::from_millis(250);
+    let point = PenPoint {
+        x: 30,
+        y: 40,
+        pressure: 0.5,
+        tilt_x: -15.0,
+        tilt_y: 30.0,
+    };
+    let actions = [
+        DeviceAction::MousePress { button },
+        DeviceAction::MouseRelease { button },
+        DeviceAction::MouseClick { button },
+        DeviceAction::MouseHold { button, duration },
+        DeviceAction::MouseDrag {
+            button,
+            dx: 10,
+            dy: -20,
+            duration,
+        },
+        DeviceAction::MouseMoveAbs {
+            x: 100,
+            y: 200,
+            duration,
+        },
+        DeviceAction::MouseMoveRel {
+            dx: -5,
+            dy: 5,
+            duration,
+        },
+        DeviceAction::MouseScroll { dx: 0, dy: 3 },
+        DeviceAction::KeyPress { key },
+        DeviceAction::KeyRelease {
+            key: KeyboardKey::Shift,
+        },
+        DeviceAction::KeyTap {
+            key: KeyboardKey::Unicode(' '),
+        },
+        DeviceAction::KeyHold {
+            key: "evdev:17".parse().unwrap(),
+            duration,
+        },
+        DeviceAction::KeyText {
+            text: "héllo".to_string(),
+            duration,
+        },
+        DeviceAction::TouchDown {
+            id: 1,
+            x: 10,
+            y: 20,
+        },
+        DeviceAction::TouchMove {
+            id: 1,
+            x: 30,
+            y: 40,
+        },
+        DeviceAction::TouchUp { id: 1 },
+        DeviceAction::TouchTap {
+            id: 2,
+            x: 50,
+            y: 60,
+        },
+        DeviceAction::GamepadPress {
+            button: GamepadButton::A,
+        },
+        DeviceAction::GamepadRelease {
+            button: GamepadButton::A,
+        },
+        DeviceAction::GamepadTap {
+            button: GamepadButton::DpadUp,
+        },
+        DeviceAction::GamepadHold {
+            button: GamepadButton::LeftBumper,
+            duration,
+        },
+        DeviceAction::GamepadStick {
+            stick: Stick::Right,
+            x: 0.5,
+            y: -1.0,
+        },
+        DeviceAction::GamepadTrigger {
+            trigger: Trigger::Left,
+            value: 0.25,
+        },
+        DeviceAction::PenHover {
+            point: PenPoint::new(10, 20),
+        },
+        DeviceAction::PenDown { point },
+        DeviceAction::PenMove { point },
+        DeviceAction::PenUp,
+        DeviceAction::PenLeave,
+        DeviceAction::PenPress {
+            button: PenButton::Secondary,
+        },
+        DeviceAction::PenRelease {
+            button: PenButton::Secondary,
+        },
+        DeviceAction::PenTool {
+            tool: PenTool::Eraser,
+        },
+        DeviceAction::PenStroke {
+            points: vec![PenPoint::new(0, 0), point],
+            duration,
+        },
+    ];
+    for action in actions {
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(
+            serde_json::from_str::<DeviceAction>(&json).unwrap(),
+            action,
+            "{}",
+            json
+        );
+    }
+
+    let json = r#"{"action": "mouse_hold", "button": "left", "duration": 0.5}"#;
+    assert_eq!(
+        serde_json::from_str::<DeviceAction>(json).unwrap(),
+        DeviceAction::MouseHold {
+            button: MouseButton::Left,
+            duration: Duration::from_millis(500),
+        }
+    );
+    let json = r#"{"action": "key_tap", "key": "ctrl"}"#;
+    assert_eq!(
+        serde_json::from_str::<DeviceAction>(json).unwrap(),
+        DeviceAction::KeyTap {
+            key: KeyboardKey::Control
+        }
+    );
+    let json = r#"{"action": "pen_down", "x": 10, "y": 20, "pressure": 0.5}"#;
+    assert_eq!(
+        serde_json::from_str::<DeviceAction>(json).unwrap(),
+        DeviceAction::PenDown {
+            point: PenPoint {
+                pressure: 0.5,
+                ..PenPoint::new(10, 20)
+            }
+        }
+    );
+    let json = r#"{"action": "gamepad_stick", "stick": "left", "x": 1.0, "y": 0.0}"#;
+    assert_eq!(
+        serde_json::from_str::<DeviceAction>(json).unwrap(),
+        DeviceAction::GamepadStick {
+            stick: Stick::Left,
+            x: 1.0,
+            y: 0.0,
+        }
+    );
+}
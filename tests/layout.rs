@@ -0,0 +1,81 @@
+use anyinput::layout::physical::PhysicalKey;
+use anyinput::layout::{tables, KeyStroke, Layout, LAYOUTS};
+
+fn table(name: &str) -> &'static [(u16, &'static str)] {
+    match name {
+        "us" => tables::US,
+        "uk" => tables::UK,
+        "de" => tables::DE,
+        "fr" => tables::FR,
+        "dvorak" => tables::DVORAK,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn bundled_tables_are_consistent() {
+    for name in LAYOUTS {
+        let layout = Layout::bundled(name).unwrap();
+        let table = table(name);
+        for (i, (code, levels)) in table.iter().enumerate() {
+            assert!(
+                PhysicalKey::from_evdev(*code).name().is_some(),
+                "{}: {}",
+                name,
+                code
+            );
+            assert!(
+                table[i + 1..].iter().all(|(other, _)| other != code),
+                "{}: {} is listed twice",
+                name,
+                code
+            );
+            assert!(
+                (1..=4).contains(&levels.chars().count()),
+                "{}: {}",
+                name,
+                code
+            );
+        }
+        // every character types through a key that types it back
+        for (_, levels) in table {
+            for c in levels.chars().filter(|c| *c != ' ') {
+                let stroke = layout.stroke(c).unwrap();
+                assert_eq!(layout.char(stroke), Some(c), "{}: {:?}", name, c);
+            }
+        }
+        for c in 'a'..='z' {
+            assert!(layout.stroke(c).is_some(), "{}: {:?}", name, c);
+        }
+    }
+}
+
+#[test]
+fn characters_translate_per_layout() {
+    let us = Layout::bundled("us").unwrap();
+    let ascii: String = (' '..='~').collect();
+    assert!(us.untypeable(&ascii).is_empty());
+    let shift_2 = KeyStroke {
+        code: 3,
+        shift: true,
+        altgr: false,
+    };
+    assert_eq!(us.translate("@").unwrap(), [shift_2]);
+    let altgr_q = KeyStroke {
+        code: 16,
+        shift: false,
+        altgr: true,
+    };
+    assert_eq!(
+        Layout::bundled("de").unwrap().translate("@").unwrap(),
+        [altgr_q]
+    );
+    // the physical key is the same whatever the layout, only the character changes
+    let key = |layout, c| Layout::bundled(layout).unwrap().stroke(c).unwrap().key();
+    assert_eq!(key("fr", 'z'), PhysicalKey::from_name("KeyW").unwrap());
+    assert_eq!(key("de", 'z'), PhysicalKey::from_name("KeyY").unwrap());
+    assert_eq!(key("us", 'z'), PhysicalKey::from_name("KeyZ").unwrap());
+
+    assert_eq!(us.untypeable("aé€aé"), ['é', '€']);
+    assert!(us.translate("é").is_err());
+}
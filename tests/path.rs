@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyinput::core::interrupt::{InterruptCondition, InterruptPolicy};
+use anyinput::core::result::InputError;
+use anyinput::device::mouse::{MouseButton, MouseDevice, PathPoint};
+
+mod common;
+
+fn point(x: i32, millis: u64, down: bool) -> PathPoint {
+    PathPoint {
+        x,
+        y: 100,
+        time: Duration::from_millis(millis),
+        down,
+    }
+}
+
+#[test]
+fn paths_hold_the_button_through_down_points() {
+    let (backend, trace) = common::dry_run();
+    let mouse = MouseDevice::new(Arc::new(backend));
+    let path = [
+        point(100, 0, false),
+        point(110, 20, true),
+        point(120, 40, true),
+        point(130, 60, false),
+    ];
+    let start = Instant::now();
+    mouse.play_path(&path, MouseButton::Left).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(60));
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse move to (100, 100)",
+            "mouse move to (110, 100)",
+            "mouse press left",
+            "mouse move to (120, 100)",
+            "mouse release left",
+            "mouse move to (130, 100)",
+        ]
+    );
+
+    let backwards = [point(100, 20, false), point(110, 10, false)];
+    assert!(mouse.play_path(&backwards, MouseButton::Left).is_err());
+}
+
+#[test]
+fn interrupted_paths_release_the_button() {
+    let (backend, trace) = common::dry_run();
+    let mouse = MouseDevice::new(Arc::new(backend));
+    let start = Instant::now();
+    let late = move || start.elapsed() >= Duration::from_millis(30);
+    mouse.set_interrupt_policy(
+        InterruptPolicy::default().with(InterruptCondition::Custom(Arc::new(late))),
+    );
+    let path = [
+        point(100, 0, true),
+        point(110, 20, true),
+        point(120, 200, true),
+    ];
+    let result = mouse.play_path(&path, MouseButton::Right);
+    assert!(matches!(result, Err(InputError::Interrupt)));
+    assert!(start.elapsed() < Duration::from_millis(200));
+    assert!(mouse.held_buttons().is_empty());
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse move to (100, 100)",
+            "mouse press right",
+            "mouse move to (110, 100)",
+            "mouse release right",
+        ]
+    );
+}
@@ -0,0 +1,59 @@
+use anyinput::backends::uinput::UinputPen;
+use anyinput::core::PenHandler;
+use anyinput::device::pen::{PenButton, PenPoint, PenTool};
+use anyinput::evdev::{
+    codes, RawEvent, ABS_PRESSURE, ABS_TILT_X, ABS_TILT_Y, ABS_X, ABS_Y, EV_ABS, EV_KEY, EV_SYN,
+};
+
+/// The events written by the pen, without the `SYN_REPORT`s and timestamps.
+fn events(pen: UinputPen<Vec<u8>>) -> Vec<(u16, u16, i32)> {
+    let stream = pen.into_inner();
+    let mut reader = stream.as_slice();
+    let mut events = Vec::new();
+    while let Some(event) = RawEvent::read_from(&mut reader).unwrap() {
+        if event.kind != EV_SYN {
+            events.push((event.kind, event.code, event.value));
+        }
+    }
+    events
+}
+
+#[test]
+fn pen_writes_tablet_events() {
+    let pen = UinputPen::new(Vec::new(), (1920, 1080));
+    let point = PenPoint {
+        pressure: 0.5,
+        tilt_x: 30.0,
+        ..PenPoint::new(100, 200)
+    };
+    pen.hover(point).unwrap();
+    pen.down(point).unwrap();
+    pen.press(PenButton::Primary).unwrap();
+    pen.release(PenButton::Primary).unwrap();
+    pen.up().unwrap();
+    pen.set_tool(PenTool::Eraser).unwrap();
+    pen.leave().unwrap();
+    assert_eq!(
+        events(pen),
+        [
+            (EV_KEY, codes::BTN_TOOL_PEN, 1),
+            (EV_ABS, ABS_X, 100),
+            (EV_ABS, ABS_Y, 200),
+            (EV_ABS, ABS_TILT_X, 30),
+            (EV_ABS, ABS_TILT_Y, 0),
+            (EV_ABS, ABS_X, 100),
+            (EV_ABS, ABS_Y, 200),
+            (EV_ABS, ABS_TILT_X, 30),
+            (EV_ABS, ABS_TILT_Y, 0),
+            (EV_ABS, ABS_PRESSURE, 2048),
+            (EV_KEY, codes::BTN_TOUCH, 1),
+            (EV_KEY, codes::BTN_STYLUS, 1),
+            (EV_KEY, codes::BTN_STYLUS, 0),
+            (EV_ABS, ABS_PRESSURE, 0),
+            (EV_KEY, codes::BTN_TOUCH, 0),
+            (EV_KEY, codes::BTN_TOOL_PEN, 0),
+            (EV_KEY, codes::BTN_TOOL_RUBBER, 1),
+            (EV_KEY, codes::BTN_TOOL_RUBBER, 0),
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+use anyinput::device::keyboard::KeyboardKey;
+use anyinput::layout::physical::PhysicalKey;
+
+#[test]
+fn codes_convert_consistently() {
+    let keys: Vec<PhysicalKey> = PhysicalKey::named().collect();
+    assert!(keys
+        .windows(2)
+        .all(|pair| pair[0].evdev() < pair[1].evdev()));
+    for key in &keys {
+        let name = key.name().unwrap();
+        let hid = key.hid().unwrap();
+        assert_eq!(PhysicalKey::from_evdev(key.evdev()), *key);
+        assert_eq!(PhysicalKey::from_hid(hid), Some(*key), "{}", name);
+        assert_eq!(key.x11(), key.evdev() + 8);
+        assert_eq!(PhysicalKey::from_x11(key.x11()), Some(*key));
+        assert_eq!(PhysicalKey::from_name(name), Some(*key));
+        assert_eq!(key.to_string().parse::<PhysicalKey>().unwrap(), *key);
+    }
+    let mut usages: Vec<u16> = keys.iter().filter_map(PhysicalKey::hid).collect();
+    usages.sort();
+    usages.dedup();
+    assert_eq!(usages.len(), keys.len());
+
+    // the HID keyboard page numbers letters and digits in order
+    for (i, letter) in ('A'..='Z').enumerate() {
+        let key = PhysicalKey::from_name(&format!("Key{}", letter)).unwrap();
+        assert_eq!(key.hid(), Some(0x04 + i as u16));
+    }
+    for (i, digit) in "1234567890".chars().enumerate() {
+        let key = PhysicalKey::from_name(&format!("Digit{}", digit)).unwrap();
+        assert_eq!(key.hid(), Some(0x1e + i as u16));
+        assert_eq!(key.evdev(), 2 + i as u16);
+    }
+}
+
+#[test]
+fn every_form_parses_to_the_same_key() {
+    let w = PhysicalKey::from_evdev(17);
+    for form in [
+        "code:KeyW",
+        "KeyW",
+        "code:keyw",
+        "evdev:17",
+        "evdev:0x11",
+        "hid:26",
+        "hid:0x1a",
+        "x11:25",
+    ] {
+        assert_eq!(form.parse::<PhysicalKey>().unwrap(), w, "{}", form);
+    }
+    assert_eq!(w.to_string(), "code:KeyW");
+    assert_eq!(
+        "code:KeyW".parse::<KeyboardKey>().unwrap(),
+        KeyboardKey::Physical(w)
+    );
+
+    assert_eq!(PhysicalKey::from_evdev(600).to_string(), "evdev:600");
+    for invalid in ["code:KeyÄ", "hid:0", "x11:7", "evdev:w", "scan:17"] {
+        assert!(invalid.parse::<PhysicalKey>().is_err(), "{}", invalid);
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyinput::backends::traced::Traced;
+use anyinput::core::receipt::{self, Receipt};
+use anyinput::core::result::InputResult;
+use anyinput::core::{KeyboardHandler, MouseHandler, TouchHandler};
+use anyinput::device::keyboard::{KeyboardDevice, KeyboardKey};
+use anyinput::device::mouse::{MouseButton, MouseDevice};
+use anyinput::device::touch::TouchDevice;
+
+mod common;
+
+/// Run `action` and take its receipt.
+fn measured(action: impl FnOnce() -> InputResult) -> Receipt {
+    action().unwrap();
+    receipt::take_last().unwrap()
+}
+
+#[test]
+fn taps_count_a_press_and_a_release() {
+    let (backend, _) = common::dry_run();
+    let backend = Arc::new(Traced::new("dry-run", backend));
+    let mouse = MouseDevice::new(Arc::clone(&backend) as _);
+    let keyboard = KeyboardDevice::new(Arc::clone(&backend) as _);
+    let touch = TouchDevice::new(backend);
+
+    let primitives = |action: &dyn Fn() -> InputResult| measured(action).primitives;
+    assert_eq!(primitives(&|| mouse.press(MouseButton::Left)), 1);
+    assert_eq!(primitives(&|| mouse.release(MouseButton::Left)), 1);
+    assert_eq!(primitives(&|| mouse.click(MouseButton::Left)), 2);
+    assert_eq!(primitives(&|| keyboard.tap(KeyboardKey::Unicode('a'))), 2);
+    assert_eq!(primitives(&|| touch.tap(1, 10, 20)), 2);
+    // a hold is a press, a wait and a release
+    let hold = || keyboard.hold(KeyboardKey::Shift, Duration::from_millis(10));
+    assert_eq!(primitives(&hold), 2);
+}
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use anyinput::core::action::Action;
+use anyinput::device::keyboard::KeyboardKey;
+use anyinput::device::mouse::MouseButton;
+use anyinput::evdev::{
+    codes, RawEvent, EV_KEY, EV_REL, EV_SYN, REL_WHEEL, REL_X, REL_Y, SYN_REPORT,
+};
+use anyinput::record::{Coordinates, Recording, ReplayOptions};
+
+mod common;
+
+const KEY_A: u16 = 30;
+
+/// A stream as read from `/dev/input/eventN`, with a report every 100ms from an arbitrary epoch.
+fn stream() -> Vec<u8> {
+    let reports: &[&[(u16, u16, i32)]] = &[
+        &[(EV_REL, REL_X, 5), (EV_REL, REL_Y, -3)],
+        &[(EV_REL, REL_WHEEL, 1)],
+        &[(EV_KEY, KEY_A, 1)],
+        // auto-repeat
+        &[(EV_KEY, KEY_A, 2)],
+        &[(EV_KEY, KEY_A, 0), (EV_KEY, codes::BTN_LEFT, 1)],
+        &[(EV_KEY, codes::BTN_LEFT, 0)],
+    ];
+    let mut stream = Vec::new();
+    for (i, report) in reports.iter().enumerate() {
+        let time = Duration::from_secs(1_700_000_000) + Duration::from_millis(100) * i as u32;
+        let events = report.iter().copied().chain([(EV_SYN, SYN_REPORT, 0)]);
+        for (kind, code, value) in events {
+            let event = RawEvent {
+                time,
+                ..RawEvent::new(kind, code, value)
+            };
+            event.write_to(&mut stream).unwrap();
+        }
+    }
+    stream
+}
+
+#[test]
+fn evdev_streams_are_translated_to_actions() {
+    let recording = Recording::from_evdev_stream(&mut stream().as_slice()).unwrap();
+    let actions: Vec<(f64, Action)> = recording
+        .actions
+        .iter()
+        .map(|timed| (timed.time, timed.action))
+        .collect();
+    let a = KeyboardKey::Unicode('a');
+    let left = MouseButton::Left;
+    assert_eq!(
+        actions,
+        [
+            (0.0, Action::MouseMoveRel { dx: 5, dy: -3 }),
+            // evdev scrolls up with positive values
+            (0.1, Action::MouseScroll { dx: 0, dy: -1 }),
+            (0.2, Action::KeyPress { key: a }),
+            (0.4, Action::KeyRelease { key: a }),
+            (0.4, Action::MousePress { button: left }),
+            (0.5, Action::MouseRelease { button: left }),
+        ]
+    );
+}
+
+#[test]
+fn recordings_replay_with_relative_and_absolute_coordinates() {
+    let mut recording = Recording::from_evdev_stream(&mut stream().as_slice()).unwrap();
+    let (backend, trace) = common::dry_run();
+    let relative = ReplayOptions {
+        speed: 10.0,
+        coordinates: Coordinates::Relative,
+    };
+    let start = Instant::now();
+    recording.replay(&backend, &backend, &relative).unwrap();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(50) && elapsed < Duration::from_millis(500));
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse move by (5, -3) to (965, 537)",
+            "mouse scroll (0, -1)",
+            "key press 'a'",
+            "key release 'a'",
+            "mouse press left",
+            "mouse release left",
+        ]
+    );
+
+    let absolute = ReplayOptions {
+        coordinates: Coordinates::Absolute,
+        ..relative
+    };
+    let (backend, trace) = common::dry_run();
+    assert!(recording.replay(&backend, &backend, &absolute).is_err());
+    recording.origin = Some((100, 200));
+    recording.replay(&backend, &backend, &absolute).unwrap();
+    assert_eq!(
+        trace.lines()[..2],
+        ["mouse move to (100, 200)", "mouse move to (105, 197)"]
+    );
+}
@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use anyinput::backends::dry_run::{DryRunBackend, DryRunConfig};
+use anyinput::core::interrupt::InterruptPolicy;
+use anyinput::core::{receipt, scheduler};
+
+#[test]
+fn deadlines_are_not_met_early() {
+    for millis in [0, 1, 3, 10] {
+        let deadline = Instant::now() + Duration::from_millis(millis);
+        let late = scheduler::wait_until(deadline);
+        assert!(Instant::now() >= deadline);
+        assert!(late <= Instant::now() - deadline);
+    }
+}
+
+#[test]
+fn lateness_is_recorded_in_the_receipt() {
+    let duration = Duration::from_millis(10);
+    let mut late = Duration::ZERO;
+    receipt::measure(
+        duration,
+        || None,
+        || {
+            let start = Instant::now();
+            late = scheduler::wait_until(start + duration / 2);
+            late = late.max(scheduler::wait_until(start + duration));
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(receipt::take_last().unwrap().timing_error, Some(late));
+
+    receipt::measure(Duration::ZERO, || None, || Ok(())).unwrap();
+    assert_eq!(receipt::take_last().unwrap().timing_error, None);
+}
+
+#[test]
+fn sleeps_with_the_default_policy_stay_within_the_tolerance() {
+    scheduler::set_tolerance(Duration::from_millis(3));
+    assert_eq!(scheduler::tolerance(), Duration::from_millis(3));
+    scheduler::set_tolerance(scheduler::DEFAULT_TOLERANCE);
+
+    let backend = DryRunBackend::new(DryRunConfig {
+        output: Box::new(std::io::sink()),
+        ..DryRunConfig::default()
+    });
+    let policy = InterruptPolicy::default();
+    let watch = policy.watch(Some(&backend)).unwrap();
+    // a deadline just past the poll interval is the one that a plain sleep overshoots, the best
+    // of a few attempts is taken so that the thread being preempted does not fail the test
+    for remaining in [
+        policy.poll_interval + Duration::from_micros(100),
+        policy.poll_interval * 4,
+    ] {
+        let late = (0..5)
+            .map(|_| {
+                let deadline = Instant::now() + remaining;
+                watch.sleep_until(deadline).unwrap();
+                Instant::now() - deadline
+            })
+            .min()
+            .unwrap();
+        assert!(late < scheduler::tolerance(), "{:?} late", late);
+    }
+}
@@ -0,0 +1,165 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyinput::backends::dry_run::DryRunBackend;
+use anyinput::backends::Handlers;
+use anyinput::core::result::{InputError, InputResult};
+use anyinput::core::{KeyboardHandler, MouseHandler, TouchHandler};
+use anyinput::device::keyboard::{KeyboardDevice, KeyboardKey};
+use anyinput::device::mouse::{MouseButton, MouseDevice, PathPoint};
+use anyinput::session::Session;
+
+mod common;
+
+/// A dry-run backend that does not report what is pressed, so sessions track it instead.
+struct Unreported(DryRunBackend);
+
+impl MouseHandler for Unreported {
+    fn press(&self, button: MouseButton) -> InputResult {
+        MouseHandler::press(&self.0, button)
+    }
+
+    fn release(&self, button: MouseButton) -> InputResult {
+        MouseHandler::release(&self.0, button)
+    }
+
+    fn click(&self, button: MouseButton) -> InputResult {
+        self.0.click(button)
+    }
+
+    fn move_abs(&self, x: i32, y: i32, duration: Duration) -> InputResult {
+        self.0.move_abs(x, y, duration)
+    }
+
+    fn move_rel(&self, dx: i32, dy: i32, duration: Duration) -> InputResult {
+        self.0.move_rel(dx, dy, duration)
+    }
+
+    fn scroll(&self, dx: i32, dy: i32) -> InputResult {
+        self.0.scroll(dx, dy)
+    }
+
+    fn location(&self) -> Result<(i32, i32), InputError> {
+        self.0.location()
+    }
+}
+
+impl KeyboardHandler for Unreported {
+    fn press(&self, key: KeyboardKey) -> InputResult {
+        KeyboardHandler::press(&self.0, key)
+    }
+
+    fn release(&self, key: KeyboardKey) -> InputResult {
+        KeyboardHandler::release(&self.0, key)
+    }
+
+    fn text(&self, text: &str, duration: Duration) -> InputResult {
+        self.0.text(text, duration)
+    }
+}
+
+impl TouchHandler for Unreported {
+    fn down(&self, id: u32, x: i32, y: i32) -> InputResult {
+        TouchHandler::down(&self.0, id, x, y)
+    }
+
+    fn move_to(&self, id: u32, x: i32, y: i32) -> InputResult {
+        TouchHandler::move_to(&self.0, id, x, y)
+    }
+
+    fn up(&self, id: u32) -> InputResult {
+        TouchHandler::up(&self.0, id)
+    }
+}
+
+fn session() -> Session {
+    traced_session().0
+}
+
+fn traced_session() -> (Session, common::Trace) {
+    let (backend, trace) = common::dry_run();
+    let session = Session::from_handlers(Handlers::from_backend(Unreported(backend)));
+    (session, trace)
+}
+
+#[test]
+fn held_inputs_are_tracked_during_timed_actions() {
+    let session = session();
+    let duration = Duration::from_millis(200);
+    std::thread::scope(|scope| {
+        let hold = scope.spawn(|| session.mouse().hold(MouseButton::Right, duration));
+        let key = scope.spawn(|| session.keyboard().hold(KeyboardKey::Shift, duration));
+        std::thread::sleep(duration / 2);
+        let state = session.state();
+        assert!(!state.reconciled);
+        assert_eq!(state.mouse_buttons, [MouseButton::Right]);
+        assert_eq!(state.keys, [KeyboardKey::Shift]);
+        hold.join().unwrap().unwrap();
+        key.join().unwrap().unwrap();
+    });
+    let state = session.state();
+    assert!(state.mouse_buttons.is_empty() && state.keys.is_empty());
+}
+
+#[test]
+fn held_buttons_are_tracked_during_paths() {
+    let session = session();
+    let point = |x, millis, down| PathPoint {
+        x,
+        y: 100,
+        time: Duration::from_millis(millis),
+        down,
+    };
+    let path = [
+        point(100, 0, false),
+        point(110, 50, true),
+        point(120, 250, true),
+    ];
+    std::thread::scope(|scope| {
+        let played = scope.spawn(|| session.mouse().play_path(&path, MouseButton::Left));
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(session.state().mouse_buttons, [MouseButton::Left]);
+        played.join().unwrap().unwrap();
+    });
+    assert!(session.state().mouse_buttons.is_empty());
+}
+
+#[test]
+fn dropped_sessions_release_what_is_held() {
+    let (session, trace) = traced_session();
+    session.mouse().press(MouseButton::Left).unwrap();
+    session.keyboard().press(KeyboardKey::Shift).unwrap();
+    drop(session);
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse press left",
+            "key press Shift",
+            "mouse release left",
+            "key release Shift",
+        ]
+    );
+}
+
+#[test]
+fn the_last_device_clone_releases_what_is_held() {
+    let (backend, trace) = common::dry_run();
+    let backend = Arc::new(Unreported(backend));
+    let mouse = MouseDevice::new(Arc::clone(&backend) as _);
+    let keyboard = KeyboardDevice::new(backend);
+    let (mouse_clone, keyboard_clone) = (mouse.clone(), keyboard.clone());
+    mouse_clone.press(MouseButton::Left).unwrap();
+    keyboard_clone.press(KeyboardKey::Shift).unwrap();
+    drop((mouse, keyboard));
+    assert_eq!(trace.lines(), ["mouse press left", "key press Shift"]);
+    drop((mouse_clone, keyboard_clone));
+    assert_eq!(
+        trace.lines(),
+        [
+            "mouse press left",
+            "key press Shift",
+            "mouse release left",
+            "key release Shift",
+        ]
+    );
+}
@@ -0,0 +1,12 @@
+//! The committed `anyinput.pyi` has to match the stub that `build.rs` generates from the bindings.
+#![cfg(feature = "python")]
+
+#[test]
+fn stub_matches_bindings() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/anyinput.pyi"));
+    let committed = include_str!("../anyinput.pyi").replace("\r\n", "\n");
+    assert!(
+        committed == generated,
+        "anyinput.pyi is out of date, run `ANYINPUT_WRITE_STUB=1 cargo build` to update it"
+    );
+}
@@ -0,0 +1,44 @@
+use anyinput::core::typos::{Stroke, Typist, TypoConfig};
+
+/// The text left by typing `strokes`.
+fn typed(strokes: &[Stroke]) -> String {
+    let mut text = String::new();
+    for stroke in strokes {
+        match stroke {
+            Stroke::Char(c) => text.push(*c),
+            Stroke::Backspace => {
+                // a correction never reaches back past a control character
+                assert!(!text.pop().unwrap().is_control());
+            }
+        }
+    }
+    text
+}
+
+fn typist(rate: f64, seed: u64) -> Typist {
+    Typist::new(TypoConfig {
+        rate,
+        seed: Some(seed),
+        ..TypoConfig::default()
+    })
+}
+
+#[test]
+fn the_same_seed_gives_the_same_typos() {
+    let text = "The quick brown fox jumps over the lazy dog.";
+    assert_eq!(typist(0.3, 7).strokes(text), typist(0.3, 7).strokes(text));
+    assert_ne!(typist(0.3, 7).strokes(text), typist(0.3, 8).strokes(text));
+}
+
+#[test]
+fn typos_are_corrected() {
+    let text = "Hello, world!\nSecond line\twith a tab.";
+    for seed in 0..50 {
+        let strokes = typist(0.5, seed).strokes(text);
+        assert!(strokes.contains(&Stroke::Backspace));
+        assert_eq!(typed(&strokes), text);
+    }
+    let strokes = typist(0.0, 1).strokes(text);
+    assert_eq!(strokes.len(), text.chars().count());
+    assert_eq!(typed(&strokes), text);
+}